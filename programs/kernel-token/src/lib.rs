@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
-    self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+    self, spl_token_2022::instruction::AuthorityType, Burn, HarvestWithheldTokensToMint, Mint,
+    MintTo, SetAuthority, Token2022, TokenAccount, TokenInterface, TransferChecked,
+    WithdrawWithheldTokensFromMint,
 };
+use solana_sha256_hasher::hashv;
 
 declare_id!("5QVVrCBUgqjG3pWcSmRkqaagFaokaAwgoFFDLXBJgFJw");
 
@@ -9,11 +13,29 @@ declare_id!("5QVVrCBUgqjG3pWcSmRkqaagFaokaAwgoFFDLXBJgFJw");
 /// "The Core of Crypto Security - No Kernel Panics Here!"
 ///
 /// Features:
-/// - Reflection pool for 2% holder rewards
+/// - Reflection pool for 2% holder rewards, optionally streamed over time
 /// - Staking mechanism with real token transfers
+/// - Lock-up tiers with boosted reward multipliers
+/// - Unbonding queue with a configurable unstake cooldown
+/// - Reflection compounding with an opt-in permissionless crank
+/// - Extra reward mints distributed alongside reflections
 /// - Community burns (1% auto-burn on fees)
-/// - Airdrop distribution system
-/// - Fee harvesting for auto-LP
+/// - Merkle-proof airdrop claims
+/// - Permissionless on-chain fee harvest and split
+/// - Typed CPI events for every state change
+/// - Versioned account layouts with in-place migration
+/// - Nonce-keyed governance action queue behind a timelock
+/// - Per-action timelock delays stored in config, changed through the queue
+/// - Staker voting on fee changes with quorum and majority
+/// - Two-step authority transfer accepted by the new key
+/// - Separate role keys for operations, granted through the timelock
+/// - Per-subsystem pause flags with an optional auto-unpause time
+/// - Reflection pool solvency sync with pro-rata payouts during a shortfall
+/// - Delegated staking: fund a beneficiary's position, optionally keeping withdrawals
+/// - Transferable stake positions with an optional Token-2022 NFT receipt
+/// - Cliff + linear vesting schedules, revocable and stakeable while locked
+/// - Emergency unstake that returns principal without any reward math
+/// - M-of-N guardian set for emergency actions
 
 #[program]
 pub mod kernel_token {
@@ -21,18 +43,25 @@ pub mod kernel_token {
 
     /// Initialize the $KERNEL ecosystem
     /// Sets up staking vault, reflection pool, and program authority
+    /// action_delay seeds every governance delay (24h on mainnet, short on devnet)
     pub fn initialize(
         ctx: Context<Initialize>,
         reflection_share_bps: u16, // 200 = 2%
         lp_share_bps: u16,         // 200 = 2%
         burn_share_bps: u16,       // 100 = 1%
+        action_delay: i64,         // Seconds, at least MIN_ACTION_DELAY
     ) -> Result<()> {
         require!(
-            reflection_share_bps + lp_share_bps + burn_share_bps == 500,
+            valid_fee_split(reflection_share_bps, lp_share_bps, burn_share_bps),
             KernelError::InvalidFeeConfig
         );
+        require!(
+            valid_action_delay(action_delay),
+            KernelError::InvalidActionDelay
+        );
 
         let config = &mut ctx.accounts.config;
+        config.version = ACCOUNT_VERSION;
         config.authority = ctx.accounts.authority.key();
         config.token_mint = ctx.accounts.token_mint.key();
         config.staking_vault = ctx.accounts.staking_vault.key();
@@ -41,38 +70,77 @@ pub mod kernel_token {
         config.lp_share_bps = lp_share_bps;
         config.burn_share_bps = burn_share_bps;
         config.total_staked = 0;
+        config.total_shares = 0;
         config.total_reflections_distributed = 0;
         config.pending_reflections = 0;
         config.accumulated_per_share = 0;
-        config.is_paused = false;
+        config.pause_flags = 0;
+        config.paused_until = 0;
+        config.unbonding_period = 0;
+        config.total_unbonding = 0;
         config.bump = ctx.bumps.config;
         config.vault_bump = ctx.bumps.staking_vault;
+        config.reward_pool_count = 0;
+        config.reward_stream_duration = 0;
+        config.reward_rate = 0;
+        config.reward_period_end = 0;
+        config.last_update_time = 0;
+        config.governance_action_count = 0;
+        config.action_delays = [action_delay; ACTION_KIND_COUNT];
+        config.reflection_surplus = 0;
+        config.reflection_deficit = 0;
+        config.reflection_owed = 0;
+        config.undistributed_reflections = 0;
+        config.reflection_remainder = 0;
+        config.position_count = 0;
+        config.vesting_count = 0;
 
         msg!("$KERNEL initialized! No kernel panics here!");
         msg!("Staking Vault: {}", ctx.accounts.staking_vault.key());
         msg!("Reflection Pool: {}", ctx.accounts.reflection_pool.key());
 
+        emit_cpi!(ProgramInitialized {
+            config: config.key(),
+            authority: config.authority,
+            token_mint: config.token_mint,
+            reflection_share_bps,
+            lp_share_bps,
+            burn_share_bps,
+        });
+
         Ok(())
     }
 
     /// Stake $KERNEL to earn reflections
     /// Transfers tokens from user to staking vault
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    ///
+    /// `lock_tier` optionally locks the whole position for that tier's
+    /// duration in exchange for its reward multiplier. A tier that runs past the
+    /// current lock replaces it, multiplier included; locks are never shortened.
+    ///
+    /// Stakers with reward pools registered pass `[reward_pool, user_reward_state]`
+    /// for every pool as remaining accounts so their pool debts follow the new shares.
+    pub fn stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
+        amount: u64,
+        lock_tier: Option<u8>,
+    ) -> Result<()> {
         require!(amount > 0, KernelError::ZeroAmount);
-        require!(!ctx.accounts.config.is_paused, KernelError::ProgramPaused);
+        // Delegated positions only take more stake through stake_for
+        require!(
+            ctx.accounts.user_stake.delegate == Pubkey::default(),
+            KernelError::DelegationMismatch
+        );
+        let tier = lock_tier_for(ctx.accounts.staking_tiers.as_ref(), lock_tier)?;
 
         let user_stake = &mut ctx.accounts.user_stake;
         let config = &mut ctx.accounts.config;
-
-        // Calculate pending rewards before updating stake
-        if user_stake.staked_amount > 0 && config.accumulated_per_share > 0 {
-            let pending = calculate_pending_rewards(
-                user_stake.staked_amount,
-                config.accumulated_per_share,
-                user_stake.reward_debt,
-            );
-            user_stake.pending_rewards = user_stake.pending_rewards.checked_add(pending).unwrap();
-        }
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(config, PauseScope::Stake, current_time),
+            KernelError::ProgramPaused
+        );
+        accrue_reflection_stream(config, current_time)?;
 
         // Transfer tokens from user to staking vault
         let decimals = ctx.accounts.token_mint.decimals;
@@ -92,21 +160,122 @@ pub mod kernel_token {
         )?;
 
         // Update stake
+        user_stake.version = ACCOUNT_VERSION;
         user_stake.owner = ctx.accounts.owner.key();
-        user_stake.staked_amount = user_stake.staked_amount.checked_add(amount).unwrap();
-        user_stake.stake_time = Clock::get()?.unix_timestamp;
         user_stake.bump = ctx.bumps.user_stake;
+        let old_shares = credit_stake(config, user_stake, amount, tier, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
 
-        // Update global state
-        config.total_staked = config.total_staked.checked_add(amount).unwrap();
+        msg!("Staked {} $KERNEL. Total staked: {}", amount, config.total_staked);
+        if user_stake.lock_until > 0 {
+            msg!(
+                "Locked until {} at {}bps",
+                user_stake.lock_until,
+                user_stake.lock_multiplier_bps
+            );
+        }
+
+        emit_cpi!(Staked {
+            owner: user_stake.owner,
+            amount,
+            shares: user_stake.shares,
+            lock_multiplier_bps: user_stake.lock_multiplier_bps,
+            lock_until: user_stake.lock_until,
+            total_staked: config.total_staked,
+        });
+
+        Ok(())
+    }
+
+    /// Stake $KERNEL from a funder's wallet into a beneficiary's position
+    /// For treasuries, vesting contracts and custodians. The beneficiary
+    /// earns the rewards and votes with the stake.
+    ///
+    /// With `beneficiary_only` the tokens are a plain gift. Without it the
+    /// funder becomes the position's delegate: only the delegate can unstake
+    /// and the principal goes back to the delegate. A position is either
+    /// delegated to one funder or owned outright, never a mix.
+    ///
+    /// Only an unlocked gift goes through without the beneficiary: a
+    /// delegated stake or one with a lock tier needs their signature too.
+    ///
+    /// With reward pools registered the beneficiary must already have opened
+    /// a reward state for each pool; they are passed as in `stake`.
+    pub fn stake_for<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StakeFor<'info>>,
+        beneficiary: Pubkey,
+        amount: u64,
+        lock_tier: Option<u8>,
+        beneficiary_only: bool,
+    ) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+        let funder = ctx.accounts.funder.key();
+        check_beneficiary_consent(
+            beneficiary,
+            ctx.accounts.beneficiary_signer.as_ref().map(|s| s.key()),
+            beneficiary_only,
+            lock_tier.is_some(),
+        )?;
+        let delegate = check_delegation(&ctx.accounts.user_stake, funder, beneficiary_only)?;
+        let tier = lock_tier_for(ctx.accounts.staking_tiers.as_ref(), lock_tier)?;
 
-        // Update reward debt
-        user_stake.reward_debt = calculate_reward_debt(
-            user_stake.staked_amount,
-            config.accumulated_per_share,
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(config, PauseScope::Stake, current_time),
+            KernelError::ProgramPaused
         );
+        accrue_reflection_stream(config, current_time)?;
 
-        msg!("Staked {} $KERNEL. Total staked: {}", amount, config.total_staked);
+        // Transfer tokens from the funder to staking vault
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        user_stake.version = ACCOUNT_VERSION;
+        user_stake.owner = beneficiary;
+        user_stake.delegate = delegate;
+        user_stake.bump = ctx.bumps.user_stake;
+        let old_shares = credit_stake(config, user_stake, amount, tier, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        msg!("Staked {} $KERNEL for {}", amount, beneficiary);
+        if delegate != Pubkey::default() {
+            msg!("Withdrawals held by {}", delegate);
+        }
+
+        emit_cpi!(StakedFor {
+            funder,
+            beneficiary,
+            delegate: (delegate != Pubkey::default()).then_some(delegate),
+            amount,
+            shares: user_stake.shares,
+            lock_until: user_stake.lock_until,
+            total_staked: config.total_staked,
+        });
 
         Ok(())
     }
@@ -114,28 +283,33 @@ pub mod kernel_token {
     /// Unstake $KERNEL and collect any pending rewards
     /// Transfers tokens from staking vault back to user
     ///
-    /// NOTE: This function intentionally does NOT check is_paused.
+    /// Reward pools are passed as in `stake`; a pool the staker never
+    /// opened a reward state for passes that state's address uninitialized.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
     /// Users must always be able to withdraw their staked tokens,
     /// even during emergency pauses. This is a safety feature.
-    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         let user_stake = &mut ctx.accounts.user_stake;
         let config = &mut ctx.accounts.config;
 
-        require!(amount > 0, KernelError::ZeroAmount);
-        require!(
-            user_stake.staked_amount >= amount,
-            KernelError::InsufficientStake
-        );
+        // With a cooldown configured, withdrawals go through the unbonding queue
+        require!(config.unbonding_period == 0, KernelError::UnbondingRequired);
 
-        // Calculate and add pending rewards
-        if config.accumulated_per_share > 0 {
-            let pending = calculate_pending_rewards(
-                user_stake.staked_amount,
-                config.accumulated_per_share,
-                user_stake.reward_debt,
-            );
-            user_stake.pending_rewards = user_stake.pending_rewards.checked_add(pending).unwrap();
-        }
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = user_stake.shares;
+        remove_stake(config, user_stake, amount, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
 
         // Transfer tokens from staking vault back to user
         let mint_key = ctx.accounts.token_mint.key();
@@ -163,47 +337,166 @@ pub mod kernel_token {
             decimals,
         )?;
 
-        // Update stake
-        user_stake.staked_amount = user_stake.staked_amount.checked_sub(amount).unwrap();
-        config.total_staked = config.total_staked.checked_sub(amount).unwrap();
+        msg!("Unstaked {} $KERNEL", amount);
 
-        // Update reward debt
-        user_stake.reward_debt = calculate_reward_debt(
-            user_stake.staked_amount,
-            config.accumulated_per_share,
-        );
+        emit_cpi!(Unstaked {
+            owner: user_stake.owner,
+            amount,
+            total_staked: config.total_staked,
+        });
 
-        msg!("Unstaked {} $KERNEL", amount);
+        Ok(())
+    }
+
+    /// Withdraw the whole position and forfeit every pending reward
+    /// Skips all reward math, so principal never depends on the reflection
+    /// pool being solvent or the accumulators being sane. Locks and the
+    /// unbonding cooldown still apply: while a cooldown is configured, pass an
+    /// `unbonding_ticket` and the principal is paid by `withdraw_unbonded`.
+    ///
+    /// Reward pools are passed as `[reward_pool, user_reward_state]` pairs, as
+    /// in `unstake`. Their states are zeroed without being settled, so pool
+    /// rewards are forfeited too and stay with the remaining stakers.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn emergency_unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyUnstake<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let forfeited_rewards = user_stake.pending_rewards;
+        let amount = forfeit_stake(config, user_stake, current_time)?;
+        forfeit_reward_states(ctx.remaining_accounts, config, user_stake.key())?;
+
+        if let Some(ticket) = ctx.accounts.unbonding_ticket.as_mut() {
+            // The principal waits out the cooldown like any other exit
+            ticket.owner = ctx.accounts.owner.key();
+            ticket.user_stake = user_stake.key();
+            ticket.bump = ctx
+                .bumps
+                .unbonding_ticket
+                .ok_or(KernelError::UnbondingRequired)?;
+            open_unbonding_ticket(config, user_stake, ticket, amount, current_time)?;
+
+            msg!("Emergency unbonding {} $KERNEL, rewards forfeited", amount);
+            msg!("Release at {}", ticket.release_time);
+
+            emit_cpi!(UnstakeRequested {
+                owner: ticket.owner,
+                ticket: ticket.key(),
+                amount,
+                release_time: ticket.release_time,
+            });
+        } else {
+            require!(config.unbonding_period == 0, KernelError::UnbondingRequired);
+
+            let mint_key = ctx.accounts.token_mint.key();
+            let seeds = &[b"staking_vault", mint_key.as_ref(), &[config.vault_bump]];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.staking_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.staking_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!("Emergency unstaked {} $KERNEL, rewards forfeited", amount);
+        }
+
+        emit_cpi!(EmergencyUnstaked {
+            owner: user_stake.owner,
+            amount,
+            forfeited_rewards,
+            total_staked: config.total_staked,
+        });
 
         Ok(())
     }
 
-    /// Claim reflection rewards
-    /// Transfers pending rewards from reflection pool to user
+    /// Start unbonding staked $KERNEL
+    /// The amount stops earning rewards immediately and is held in the
+    /// staking vault until the ticket's release time
     ///
-    /// NOTE: This function intentionally does NOT check is_paused.
-    /// Users must always be able to claim their earned rewards,
+    /// Reward pools are passed as in `unstake`.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
     /// even during emergency pauses. This is a safety feature.
-    pub fn claim_reflections(ctx: Context<ClaimReflections>) -> Result<()> {
+    pub fn request_unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RequestUnstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         let user_stake = &mut ctx.accounts.user_stake;
         let config = &mut ctx.accounts.config;
 
-        // Calculate total claimable
-        let pending = calculate_pending_rewards(
-            user_stake.staked_amount,
-            config.accumulated_per_share,
-            user_stake.reward_debt,
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = user_stake.shares;
+        remove_stake(config, user_stake, amount, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        let ticket = &mut ctx.accounts.unbonding_ticket;
+        ticket.owner = ctx.accounts.owner.key();
+        ticket.user_stake = user_stake.key();
+        ticket.bump = ctx.bumps.unbonding_ticket;
+        open_unbonding_ticket(config, user_stake, ticket, amount, current_time)?;
+
+        msg!("Unbonding {} $KERNEL", amount);
+        msg!("Release at {}", ticket.release_time);
+
+        emit_cpi!(UnstakeRequested {
+            owner: ticket.owner,
+            ticket: ticket.key(),
+            amount,
+            release_time: ticket.release_time,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw an unbonding ticket once its cooldown has passed
+    /// Pays the ticket amount from the staking vault and closes the ticket
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+        let ticket = &ctx.accounts.unbonding_ticket;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            unbonding_complete(ticket, current_time),
+            KernelError::UnbondingNotReady
         );
-        let total_claimable = user_stake.pending_rewards.checked_add(pending).unwrap();
 
-        require!(total_claimable > 0, KernelError::NothingToClaim);
+        let amount = ticket.amount;
+        let config = &mut ctx.accounts.config;
 
-        // Transfer rewards from reflection pool to user
+        // Transfer tokens from staking vault back to user
         let mint_key = ctx.accounts.token_mint.key();
         let seeds = &[
-            b"reflection_pool",
+            b"staking_vault",
             mint_key.as_ref(),
-            &[ctx.bumps.reflection_pool],
+            &[config.vault_bump],
         ];
         let signer_seeds = &[&seeds[..]];
 
@@ -213,513 +506,6406 @@ pub mod kernel_token {
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 TransferChecked {
-                    from: ctx.accounts.reflection_pool.to_account_info(),
+                    from: ctx.accounts.staking_vault.to_account_info(),
                     mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.reflection_pool.to_account_info(),
+                    authority: ctx.accounts.staking_vault.to_account_info(),
                 },
                 signer_seeds,
             ),
-            total_claimable,
+            amount,
             decimals,
         )?;
 
-        // Update state
-        user_stake.pending_rewards = 0;
-        user_stake.total_claimed = user_stake.total_claimed.checked_add(total_claimable).unwrap();
-        user_stake.reward_debt = calculate_reward_debt(
-            user_stake.staked_amount,
-            config.accumulated_per_share,
-        );
+        let user_stake = &mut ctx.accounts.user_stake;
+        user_stake.unbonding_amount = user_stake.unbonding_amount.safe_sub(amount)?;
+        config.total_unbonding = config.total_unbonding.safe_sub(amount)?;
 
-        config.total_reflections_distributed = config
-            .total_reflections_distributed
-            .checked_add(total_claimable)
-            .unwrap();
-        config.pending_reflections = config
-            .pending_reflections
-            .saturating_sub(total_claimable);
+        msg!("Withdrew {} unbonded $KERNEL", amount);
 
-        msg!("Claimed {} $KERNEL in reflections!", total_claimable);
+        emit_cpi!(UnbondedWithdrawn {
+            owner: user_stake.owner,
+            ticket: ctx.accounts.unbonding_ticket.key(),
+            amount,
+        });
 
         Ok(())
     }
 
-    /// Deposit fees to reflection pool (called after fee harvest)
-    /// Updates accumulated_per_share for reward distribution
-    pub fn deposit_reflections(ctx: Context<DepositReflections>, amount: u64) -> Result<()> {
-        require!(amount > 0, KernelError::ZeroAmount);
-
+    /// Claim reflection rewards
+    /// Transfers pending rewards from reflection pool to user
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to claim their earned rewards,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn claim_reflections(ctx: Context<ClaimReflections>) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
         let config = &mut ctx.accounts.config;
 
-        // Transfer tokens from authority to reflection pool
+        // Accrue any streamed reflections, then settle the position
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+
+        require!(paid > 0, KernelError::NothingToClaim);
+
+        // Transfer rewards from reflection pool to user
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"reflection_pool",
+            mint_key.as_ref(),
+            &[ctx.bumps.reflection_pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
         let decimals = ctx.accounts.token_mint.decimals;
 
         token_interface::transfer_checked(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 TransferChecked {
-                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    from: ctx.accounts.reflection_pool.to_account_info(),
                     mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.reflection_pool.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reflection_pool.to_account_info(),
                 },
+                signer_seeds,
             ),
-            amount,
+            paid,
             decimals,
         )?;
 
-        // Update accumulated per share (scaled by 1e12 for precision)
-        if config.total_staked > 0 {
-            let reward_per_share = (amount as u128)
-                .checked_mul(PRECISION)
-                .unwrap()
-                .checked_div(config.total_staked as u128)
-                .unwrap();
-            config.accumulated_per_share = config
-                .accumulated_per_share
-                .checked_add(reward_per_share)
-                .unwrap();
+        msg!("Claimed {} $KERNEL in reflections!", paid);
+        if deferred > 0 {
+            msg!(
+                "Reflection pool short - {} $KERNEL owed until it is topped up",
+                deferred
+            );
         }
 
-        config.pending_reflections = config.pending_reflections.checked_add(amount).unwrap();
-
-        msg!("Deposited {} to reflection pool", amount);
+        emit_cpi!(ReflectionsClaimed {
+            owner: user_stake.owner,
+            amount: paid,
+            deferred,
+        });
 
         Ok(())
     }
 
-    /// Burn tokens from supply
-    /// Actually burns tokens using SPL Token burn instruction
-    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
-        require!(amount > 0, KernelError::ZeroAmount);
+    /// Compound pending reflections back into stake
+    /// Moves the claimable amount from the reflection pool to the staking vault
+    pub fn compound_reflections<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompoundReflections<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
 
-        // Burn tokens from authority's account
-        // Note: burn() doesn't require decimals unlike transfer_checked()
-        let _decimals = ctx.accounts.token_mint.decimals;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(config, PauseScope::Stake, current_time),
+            KernelError::ProgramPaused
+        );
+        // Compounding into a delegated position would hand the rewards to the delegate
+        require!(
+            user_stake.delegate == Pubkey::default(),
+            KernelError::DelegationMismatch
+        );
+        accrue_reflection_stream(config, current_time)?;
 
-        token_interface::burn(
-            CpiContext::new(
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+        require!(paid > 0, KernelError::NothingToClaim);
+
+        // Transfer rewards from reflection pool to staking vault
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"reflection_pool",
+            mint_key.as_ref(),
+            &[ctx.bumps.reflection_pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Burn {
+                TransferChecked {
+                    from: ctx.accounts.reflection_pool.to_account_info(),
                     mint: ctx.accounts.token_mint.to_account_info(),
-                    from: ctx.accounts.authority_token_account.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.reflection_pool.to_account_info(),
                 },
+                signer_seeds,
             ),
-            amount,
+            paid,
+            decimals,
         )?;
 
-        // Update burn record
-        let burn_record = &mut ctx.accounts.burn_record;
-        burn_record.total_burned = burn_record.total_burned.checked_add(amount).unwrap();
-        burn_record.burn_count = burn_record.burn_count.checked_add(1).unwrap();
-        burn_record.last_burn_time = Clock::get()?.unix_timestamp;
-        burn_record.bump = ctx.bumps.burn_record;
+        let old_shares = user_stake.shares;
+        add_compounded_stake(config, user_stake, paid, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
 
-        msg!("Burned {} $KERNEL! Total burned: {}", amount, burn_record.total_burned);
-        msg!("Colonel Kernel salutes your sacrifice!");
+        msg!("Compounded {} $KERNEL into stake!", paid);
+
+        emit_cpi!(ReflectionsCompounded {
+            owner: user_stake.owner,
+            cranker: None,
+            amount: paid,
+            tip: 0,
+            deferred,
+        });
 
         Ok(())
     }
 
-    /// Register an airdrop campaign for tracking purposes
-    ///
-    /// IMPORTANT: This function does NOT transfer tokens. It only records
-    /// airdrop accounting (total amount, recipient count) for campaign tracking.
-    ///
-    /// Token transfers must be done separately via standard SPL transfers.
-    /// This design allows:
-    /// - Batch processing of large airdrops across multiple transactions
-    /// - Off-chain verification before actual token distribution
-    /// - Campaign tracking and analytics
-    ///
-    /// # Arguments
-    /// * `recipients` - List of wallet addresses (max 50 per call)
-    /// * `amount_per_recipient` - Token amount each recipient will receive
-    ///
-    /// # Example Flow
-    /// 1. Call `airdrop()` to register campaign and update accounting
-    /// 2. Execute SPL token transfers to each recipient off-chain
-    /// 3. Recipients can verify their allocation via airdrop_state
-    pub fn airdrop(
-        ctx: Context<Airdrop>,
-        recipients: Vec<Pubkey>,
-        amount_per_recipient: u64,
+    /// Opt in or out of crank compounding
+    /// `tip_bps` is the share of each compound paid to the cranker
+    pub fn set_auto_compound(
+        ctx: Context<SetAutoCompound>,
+        enabled: bool,
+        tip_bps: u16,
     ) -> Result<()> {
-        require!(recipients.len() <= 50, KernelError::TooManyRecipients);
-        require!(amount_per_recipient > 0, KernelError::ZeroAmount);
-
-        let airdrop_state = &mut ctx.accounts.airdrop_state;
-        let total_amount = (recipients.len() as u64)
-            .checked_mul(amount_per_recipient)
-            .unwrap();
-
-        airdrop_state.total_airdropped = airdrop_state
-            .total_airdropped
-            .checked_add(total_amount)
-            .unwrap();
-        airdrop_state.recipient_count = airdrop_state
-            .recipient_count
-            .checked_add(recipients.len() as u64)
-            .unwrap();
-        airdrop_state.bump = ctx.bumps.airdrop_state;
+        require!(tip_bps <= MAX_CRANK_TIP_BPS, KernelError::CrankTipTooHigh);
 
-        msg!(
-            "Airdrop registered: {} $KERNEL to {} recipients!",
-            total_amount,
-            recipients.len()
-        );
+        let user_stake = &mut ctx.accounts.user_stake;
+        user_stake.auto_compound = enabled;
+        user_stake.compound_tip_bps = tip_bps;
+
+        msg!("Auto-compound: {} (tip {}bps)", enabled, tip_bps);
+
+        emit_cpi!(AutoCompoundUpdated {
+            owner: user_stake.owner,
+            enabled,
+            tip_bps,
+        });
 
         Ok(())
     }
 
-    /// Propose a fee configuration change (starts timelock)
-    /// Changes require 24-hour delay before execution
-    pub fn propose_fee_update(
-        ctx: Context<ProposeFeeUpdate>,
-        reflection_share_bps: u16,
-        lp_share_bps: u16,
-        burn_share_bps: u16,
+    /// Permissionless crank: compound a staker's reflections on their behalf
+    /// The cranker keeps the staker's chosen tip, the rest is staked
+    pub fn crank_compound<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankCompound<'info>>,
     ) -> Result<()> {
         require!(
-            reflection_share_bps + lp_share_bps + burn_share_bps == 500,
-            KernelError::InvalidFeeConfig
+            !is_paused(
+                &ctx.accounts.config,
+                PauseScope::Stake,
+                Clock::get()?.unix_timestamp
+            ),
+            KernelError::ProgramPaused
+        );
+        require!(
+            ctx.accounts.user_stake.auto_compound,
+            KernelError::AutoCompoundDisabled
+        );
+        require!(
+            ctx.accounts.user_stake.delegate == Pubkey::default(),
+            KernelError::DelegationMismatch
         );
 
-        let proposal = &mut ctx.accounts.fee_proposal;
-        proposal.proposer = ctx.accounts.authority.key();
-        proposal.reflection_share_bps = reflection_share_bps;
-        proposal.lp_share_bps = lp_share_bps;
-        proposal.burn_share_bps = burn_share_bps;
-        proposal.proposed_at = Clock::get()?.unix_timestamp;
-        proposal.executed = false;
-        proposal.cancelled = false;
-        proposal.bump = ctx.bumps.fee_proposal;
-
-        msg!("Fee update proposed! Timelock: 24 hours");
-        msg!("Proposed: reflection={}bps, lp={}bps, burn={}bps",
-            reflection_share_bps, lp_share_bps, burn_share_bps);
-
-        Ok(())
-    }
-
-    /// Execute a proposed fee update after timelock expires
-    /// Requires 24-hour delay from proposal
-    pub fn execute_fee_update(ctx: Context<ExecuteFeeUpdate>) -> Result<()> {
-        let proposal = &ctx.accounts.fee_proposal;
+        let user_stake = &mut ctx.accounts.user_stake;
         let config = &mut ctx.accounts.config;
 
-        require!(!proposal.executed, KernelError::ProposalAlreadyExecuted);
-        require!(!proposal.cancelled, KernelError::ProposalCancelled);
-
         let current_time = Clock::get()?.unix_timestamp;
-        let time_elapsed = current_time - proposal.proposed_at;
-
-        require!(
-            time_elapsed >= TIMELOCK_DURATION,
-            KernelError::TimelockNotExpired
-        );
+        accrue_reflection_stream(config, current_time)?;
 
-        config.reflection_share_bps = proposal.reflection_share_bps;
-        config.lp_share_bps = proposal.lp_share_bps;
-        config.burn_share_bps = proposal.burn_share_bps;
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+        let (tip, compounded) = split_crank_tip(paid, user_stake.compound_tip_bps);
+        require!(compounded > 0, KernelError::NothingToClaim);
 
-        // Mark as executed
-        let proposal = &mut ctx.accounts.fee_proposal;
-        proposal.executed = true;
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"reflection_pool",
+            mint_key.as_ref(),
+            &[ctx.bumps.reflection_pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
 
-        msg!("Fee config updated after timelock! Colonel Kernel approves!");
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.reflection_pool.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.reflection_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            compounded,
+            decimals,
+        )?;
+
+        if tip > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.reflection_pool.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.cranker_token_account.to_account_info(),
+                        authority: ctx.accounts.reflection_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                tip,
+                decimals,
+            )?;
+        }
+
+        let old_shares = user_stake.shares;
+        add_compounded_stake(config, user_stake, compounded, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        msg!("Cranked {} $KERNEL into stake", compounded);
+        msg!("Cranker tip: {} $KERNEL", tip);
+
+        emit_cpi!(ReflectionsCompounded {
+            owner: user_stake.owner,
+            cranker: Some(ctx.accounts.cranker.key()),
+            amount: compounded,
+            tip,
+            deferred,
+        });
 
         Ok(())
     }
 
-    /// Cancel a pending fee proposal (authority only)
-    pub fn cancel_fee_proposal(ctx: Context<CancelFeeProposal>) -> Result<()> {
-        let proposal = &mut ctx.accounts.fee_proposal;
+    /// Permissionless: drop an expired lock back to the base multiplier
+    /// Rewards are settled on the boosted shares up to now, so the staker
+    /// keeps everything earned while locked but stops out-earning 1x stakers.
+    ///
+    /// Reward pool states are passed as in `stake`.
+    pub fn refresh_boost<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefreshBoost<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
 
-        require!(!proposal.executed, KernelError::ProposalAlreadyExecuted);
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = expire_lock(config, user_stake, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
 
-        proposal.cancelled = true;
+        msg!(
+            "Boost expired: {} -> {} shares",
+            old_shares,
+            user_stake.shares
+        );
 
-        msg!("Fee proposal cancelled");
+        emit_cpi!(BoostRefreshed {
+            owner: user_stake.owner,
+            old_shares,
+            shares: user_stake.shares,
+            total_shares: config.total_shares,
+        });
 
         Ok(())
     }
 
-    /// Legacy update_fees - now requires guardian co-signature
-    /// For emergency use only with multisig
-    pub fn update_fees(
-        ctx: Context<UpdateFees>,
-        reflection_share_bps: u16,
-        lp_share_bps: u16,
-        burn_share_bps: u16,
-    ) -> Result<()> {
+    /// Deposit fees to reflection pool (called after fee harvest)
+    /// Updates accumulated_per_share for reward distribution
+    pub fn deposit_reflections(ctx: Context<DepositReflections>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
         require!(
-            reflection_share_bps + lp_share_bps + burn_share_bps == 500,
-            KernelError::InvalidFeeConfig
+            !is_paused(config, PauseScope::RewardDeposits, current_time),
+            KernelError::ProgramPaused
         );
 
-        let config = &mut ctx.accounts.config;
-        config.reflection_share_bps = reflection_share_bps;
-        config.lp_share_bps = lp_share_bps;
-        config.burn_share_bps = burn_share_bps;
+        // Transfer tokens from authority to reflection pool
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.reflection_pool.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
 
-        msg!("Emergency fee update by Colonel Kernel + Guardian!");
+        distribute_reflections(config, amount, current_time)?;
 
-        Ok(())
-    }
+        msg!("Deposited {} to reflection pool", amount);
+
+        emit_cpi!(ReflectionsDeposited {
+            depositor: ctx.accounts.authority.key(),
+            amount,
+            accumulated_per_share: config.accumulated_per_share,
+            reward_rate: config.reward_rate,
+            reward_period_end: config.reward_period_end,
+        });
 
-    /// Pause/unpause the program (emergency only)
-    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
-        ctx.accounts.config.is_paused = paused;
-        msg!("Program paused: {}", paused);
         Ok(())
     }
 
-    /// Propose authority transfer (starts 24-hour timelock)
-    /// Changes require 24-hour delay before execution for security
-    pub fn propose_authority_transfer(
-        ctx: Context<ProposeAuthorityTransfer>,
-        new_authority: Pubkey,
-    ) -> Result<()> {
-        let transfer = &mut ctx.accounts.pending_transfer;
-        transfer.proposer = ctx.accounts.authority.key();
-        transfer.new_authority = new_authority;
-        transfer.proposed_at = Clock::get()?.unix_timestamp;
-        transfer.executed = false;
-        transfer.cancelled = false;
-        transfer.bump = ctx.bumps.pending_transfer;
-
-        msg!("Authority transfer proposed! Timelock: 24 hours");
-        msg!("Current authority: {}", ctx.accounts.authority.key());
-        msg!("Proposed new authority: {}", new_authority);
+    /// Reconcile the reflection pool balance against what it owes stakers
+    /// Permissionless: records the surplus or deficit on the config. Claims
+    /// read the live balance, so a shortfall is paid pro-rata either way and
+    /// the unpaid part is repaid once the pool is topped up.
+    pub fn sync_reflection_pool(ctx: Context<SyncReflectionPool>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let balance = ctx.accounts.reflection_pool.amount;
+        reconcile_reflection_pool(config, balance);
+
+        if config.reflection_deficit > 0 {
+            msg!(
+                "Reflection pool short by {} $KERNEL - top it up to repay stakers",
+                config.reflection_deficit
+            );
+        } else {
+            msg!(
+                "Reflection pool solvent, {} $KERNEL spare",
+                config.reflection_surplus
+            );
+        }
+
+        emit_cpi!(ReflectionPoolSynced {
+            balance,
+            liability: reflection_liability(config),
+            surplus: config.reflection_surplus,
+            deficit: config.reflection_deficit,
+        });
 
         Ok(())
     }
 
-    /// Execute a proposed authority transfer after timelock expires
-    /// Requires 24-hour delay from proposal
-    pub fn execute_authority_transfer(ctx: Context<ExecuteAuthorityTransfer>) -> Result<()> {
-        let transfer = &ctx.accounts.pending_transfer;
+    /// Register an extra reward mint (e.g. USDC or a partner token)
+    /// Each reward mint gets its own pool PDA, vault and accumulator
+    pub fn add_reward_pool(ctx: Context<AddRewardPool>) -> Result<()> {
         let config = &mut ctx.accounts.config;
+        require!(
+            config.reward_pool_count < MAX_REWARD_POOLS,
+            KernelError::TooManyRewardPools
+        );
+        config.reward_pool_count = config.reward_pool_count.safe_add(1)?;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.version = ACCOUNT_VERSION;
+        reward_pool.config = config.key();
+        reward_pool.reward_mint = ctx.accounts.reward_mint.key();
+        reward_pool.reward_vault = ctx.accounts.reward_vault.key();
+        reward_pool.accumulated_per_share = 0;
+        reward_pool.total_funded = 0;
+        reward_pool.total_claimed = 0;
+        reward_pool.bump = ctx.bumps.reward_pool;
+        reward_pool.vault_bump = ctx.bumps.reward_vault;
+
+        msg!("Reward pool added for {}", reward_pool.reward_mint);
+
+        emit_cpi!(RewardPoolAdded {
+            reward_pool: reward_pool.key(),
+            reward_mint: reward_pool.reward_mint,
+            reward_pool_count: config.reward_pool_count,
+        });
 
-        require!(!transfer.executed, KernelError::AuthorityTransferAlreadyExecuted);
-        require!(!transfer.cancelled, KernelError::AuthorityTransferCancelled);
-
-        let current_time = Clock::get()?.unix_timestamp;
-        let time_elapsed = current_time - transfer.proposed_at;
+        Ok(())
+    }
 
+    /// Fund a reward pool (permissionless, so partners can add co-incentives)
+    /// Rewards are split across current stakers by boosted shares; only what
+    /// reaches the vault, net of any transfer fee, is credited
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+        require!(ctx.accounts.config.total_shares > 0, KernelError::NoStakers);
         require!(
-            time_elapsed >= TIMELOCK_DURATION,
-            KernelError::TimelockNotExpired
+            !is_paused(
+                &ctx.accounts.config,
+                PauseScope::RewardDeposits,
+                Clock::get()?.unix_timestamp
+            ),
+            KernelError::ProgramPaused
         );
 
-        let old_authority = config.authority;
-        config.authority = transfer.new_authority;
+        let decimals = ctx.accounts.reward_mint.decimals;
+        let vault_before = ctx.accounts.reward_vault.amount;
 
-        // Mark as executed
-        let transfer = &mut ctx.accounts.pending_transfer;
-        transfer.executed = true;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
 
-        msg!("Authority transferred after timelock!");
-        msg!("Old authority: {}", old_authority);
-        msg!("New authority: {}", config.authority);
+        // Only what reached the vault can be paid out
+        ctx.accounts.reward_vault.reload()?;
+        let received = ctx.accounts.reward_vault.amount.safe_sub(vault_before)?;
 
-        Ok(())
-    }
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        record_reward_deposit(reward_pool, ctx.accounts.config.total_shares, received)?;
 
-    /// Cancel a pending authority transfer (current authority only)
-    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
-        let transfer = &mut ctx.accounts.pending_transfer;
+        msg!("Funded reward pool with {} tokens", received);
 
-        require!(!transfer.executed, KernelError::AuthorityTransferAlreadyExecuted);
+        emit_cpi!(RewardPoolFunded {
+            reward_pool: reward_pool.key(),
+            funder: ctx.accounts.funder.key(),
+            amount: received,
+            accumulated_per_share: reward_pool.accumulated_per_share,
+        });
 
-        transfer.cancelled = true;
+        Ok(())
+    }
 
-        msg!("Authority transfer cancelled");
+    /// Open a staker's reward state for a pool (permissionless)
+    /// Must exist before the stake's shares change while the pool is live,
+    /// so first-time stakers open it before their first stake. Starts with
+    /// zero debt: shares cannot have changed since the pool was added
+    /// without this state, so everything accrued so far is owed. Anyone can
+    /// pay for it, so a grantor can open the states a vesting stake needs.
+    pub fn init_user_reward_state(ctx: Context<InitUserRewardState>) -> Result<()> {
+        let state = &mut ctx.accounts.user_reward_state;
+        state.version = ACCOUNT_VERSION;
+        state.owner = ctx.accounts.owner.key();
+        state.user_stake = ctx.accounts.user_stake.key();
+        state.reward_pool = ctx.accounts.reward_pool.key();
+        state.reward_debt = 0;
+        state.pending_rewards = 0;
+        state.total_claimed = 0;
+        state.bump = ctx.bumps.user_reward_state;
+
+        msg!("Reward state opened for pool {}", state.reward_pool);
+
+        emit_cpi!(RewardStateOpened {
+            reward_pool: state.reward_pool,
+            user_stake: state.user_stake,
+            owner: state.owner,
+            payer: ctx.accounts.payer.key(),
+        });
 
         Ok(())
     }
 
-    /// Initialize LP vault for fee allocation tracking
-    pub fn initialize_lp_vault(ctx: Context<InitializeLPVault>) -> Result<()> {
-        let lp_vault = &mut ctx.accounts.lp_vault;
-        lp_vault.authority = ctx.accounts.authority.key();
-        lp_vault.token_mint = ctx.accounts.token_mint.key();
-        lp_vault.total_allocated = 0;
-        lp_vault.total_deployed = 0;
-        lp_vault.pending_deployment = 0;
-        lp_vault.last_deployment_time = 0;
-        lp_vault.bump = ctx.bumps.lp_vault;
-        lp_vault.vault_token_bump = ctx.bumps.lp_vault_token;
+    /// Claim reflections and every reward pool in one call
+    /// Pools are passed as remaining accounts in groups of
+    /// `[reward_pool, user_reward_state, reward_vault, reward_mint, owner_reward_account]`.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to claim their earned rewards,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn claim_all_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAllRewards<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let mut claimed_any = false;
+
+        // Reflections in the staking mint
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+        if paid > 0 {
+            let mint_key = ctx.accounts.token_mint.key();
+            let seeds = &[
+                b"reflection_pool",
+                mint_key.as_ref(),
+                &[ctx.bumps.reflection_pool],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.reflection_pool.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.reflection_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                paid,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!("Claimed {} $KERNEL in reflections!", paid);
+            if deferred > 0 {
+                msg!(
+                    "Reflection pool short - {} $KERNEL owed until it is topped up",
+                    deferred
+                );
+            }
+            emit_cpi!(ReflectionsClaimed {
+                owner: user_stake.owner,
+                amount: paid,
+                deferred,
+            });
+            claimed_any = true;
+        }
+
+        // Extra reward pools
+        let claims = claim_reward_pools(
+            ctx.remaining_accounts,
+            config.key(),
+            user_stake.key(),
+            user_stake.shares,
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_token_program,
+        )?;
+        for (reward_mint, amount) in claims {
+            emit_cpi!(RewardsClaimed {
+                owner: user_stake.owner,
+                reward_mint,
+                amount,
+            });
+            claimed_any = true;
+        }
+
+        require!(claimed_any, KernelError::NothingToClaim);
 
-        msg!("LP Vault initialized!");
         Ok(())
     }
 
-    /// Allocate tokens to LP vault from harvested fees
-    /// This is the on-chain portion - actual LP addition happens off-chain
+    /// Open a transferable stake position
+    /// Each position is a PDA keyed by a config-wide id, with its stake held
+    /// in a UserStake owned by the position, so it earns exactly like a
+    /// wallet stake. The opener controls it until a receipt NFT is minted.
     ///
-    /// NOTE: This function intentionally does NOT check is_paused.
-    /// LP operations should continue during pauses to maintain liquidity.
-    pub fn allocate_to_lp(ctx: Context<AllocateToLP>, amount: u64) -> Result<()> {
+    /// With reward pools registered, open the position's reward states with
+    /// init_position_reward_state first and pass them as in `stake`.
+    pub fn open_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenPosition<'info>>,
+        amount: u64,
+        lock_tier: Option<u8>,
+    ) -> Result<()> {
         require!(amount > 0, KernelError::ZeroAmount);
+        let tier = lock_tier_for(ctx.accounts.staking_tiers.as_ref(), lock_tier)?;
 
-        let lp_vault = &mut ctx.accounts.lp_vault;
-        let decimals = ctx.accounts.token_mint.decimals;
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(config, PauseScope::Stake, current_time),
+            KernelError::ProgramPaused
+        );
+        accrue_reflection_stream(config, current_time)?;
 
-        // Transfer tokens from authority to LP vault
         token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 TransferChecked {
-                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    from: ctx.accounts.user_token_account.to_account_info(),
                     mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.lp_vault_token.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
                 },
             ),
             amount,
-            decimals,
+            ctx.accounts.token_mint.decimals,
         )?;
 
-        // Update tracking
-        lp_vault.total_allocated = lp_vault.total_allocated.checked_add(amount).unwrap();
-        lp_vault.pending_deployment = lp_vault.pending_deployment.checked_add(amount).unwrap();
+        let position = &mut ctx.accounts.position;
+        position.version = ACCOUNT_VERSION;
+        position.config = config.key();
+        position.position_id = config.position_count;
+        position.owner = ctx.accounts.owner.key();
+        position.receipt_mint = Pubkey::default();
+        position.user_stake = ctx.accounts.user_stake.key();
+        position.created_at = current_time;
+        position.bump = ctx.bumps.position;
+        config.position_count = config.position_count.safe_add(1)?;
 
-        msg!("Allocated {} tokens to LP vault", amount);
-        msg!("Pending deployment: {}", lp_vault.pending_deployment);
+        let user_stake = &mut ctx.accounts.user_stake;
+        user_stake.version = ACCOUNT_VERSION;
+        user_stake.owner = position.key();
+        user_stake.bump = ctx.bumps.user_stake;
+        credit_stake(config, user_stake, amount, tier, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            0,
+            user_stake.shares,
+        )?;
+
+        msg!(
+            "Opened position #{} with {} $KERNEL",
+            position.position_id,
+            amount
+        );
+
+        emit_cpi!(PositionOpened {
+            position: position.key(),
+            position_id: position.position_id,
+            owner: position.owner,
+            amount,
+            shares: user_stake.shares,
+            lock_until: user_stake.lock_until,
+            total_staked: config.total_staked,
+        });
 
         Ok(())
     }
 
-    /// Mark tokens as deployed to LP (called after off-chain LP addition)
-    /// Records the actual LP deployment for accounting
-    pub fn record_lp_deployment(
-        ctx: Context<RecordLPDeployment>,
-        amount: u64,
-        lp_tokens_received: u64,
-        pool_address: Pubkey,
-    ) -> Result<()> {
-        require!(amount > 0, KernelError::ZeroAmount);
+    /// Mint a Token-2022 NFT receipt for a position to its owner
+    /// From then on whoever holds the receipt controls the position, so
+    /// transferring the NFT moves the stake along with its reward debt.
+    /// The receipt mint is a position PDA with supply 1, and its mint
+    /// authority is revoked once the receipt is minted.
+    pub fn mint_position_receipt(ctx: Context<MintPositionReceipt>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let config_key = ctx.accounts.config.key();
+        let position_id = position.position_id.to_le_bytes();
+        let seeds = &[
+            b"position",
+            config_key.as_ref(),
+            position_id.as_ref(),
+            &[position.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
 
-        let lp_vault = &mut ctx.accounts.lp_vault;
-        let deployment = &mut ctx.accounts.lp_deployment;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.receipt_token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.receipt_account.to_account_info(),
+                    authority: position.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.receipt_token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: position.to_account_info(),
+                    account_or_mint: ctx.accounts.receipt_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        position.receipt_mint = ctx.accounts.receipt_mint.key();
+
+        msg!("Receipt minted for position #{}", position.position_id);
+
+        emit_cpi!(PositionReceiptMinted {
+            position: position.key(),
+            receipt_mint: position.receipt_mint,
+            holder: ctx.accounts.owner.key(),
+        });
+
+        Ok(())
+    }
 
+    /// Hand a position without a receipt to a new owner (e.g. a rotated key)
+    /// Positions with a receipt move by transferring the NFT instead
+    pub fn transfer_position(ctx: Context<TransferPosition>, new_owner: Pubkey) -> Result<()> {
         require!(
-            lp_vault.pending_deployment >= amount,
-            KernelError::InsufficientLPFunds
+            new_owner != Pubkey::default(),
+            KernelError::InvalidPositionOwner
         );
+        let position = &mut ctx.accounts.position;
+        let from = position.owner;
+        position.owner = new_owner;
 
-        // Update vault accounting
-        lp_vault.pending_deployment = lp_vault.pending_deployment.checked_sub(amount).unwrap();
-        lp_vault.total_deployed = lp_vault.total_deployed.checked_add(amount).unwrap();
-        lp_vault.last_deployment_time = Clock::get()?.unix_timestamp;
-
-        // Record deployment details
-        deployment.pool_address = pool_address;
-        deployment.kernel_amount = amount;
-        deployment.lp_tokens_received = lp_tokens_received;
-        deployment.deployed_at = Clock::get()?.unix_timestamp;
-        deployment.withdrawn = false;
-        deployment.bump = ctx.bumps.lp_deployment;
+        msg!("Position #{} moved to {}", position.position_id, new_owner);
 
-        msg!("LP deployment recorded!");
-        msg!("  KERNEL deployed: {}", amount);
-        msg!("  LP tokens received: {}", lp_tokens_received);
-        msg!("  Pool: {}", pool_address);
+        emit_cpi!(PositionTransferred {
+            position: position.key(),
+            from,
+            to: new_owner,
+        });
 
         Ok(())
     }
 
-    /// Withdraw tokens from LP vault (emergency only)
+    /// Unstake from a position and collect any pending rewards
+    /// Same as `unstake`, signed by the position's owner or receipt holder
     ///
-    /// NOTE: This function intentionally does NOT check is_paused.
-    /// Emergency withdrawals must always be possible for fund recovery.
-    pub fn withdraw_from_lp_vault(ctx: Context<WithdrawFromLPVault>, amount: u64) -> Result<()> {
-        require!(amount > 0, KernelError::ZeroAmount);
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn unstake_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakePosition<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
 
-        let lp_vault = &mut ctx.accounts.lp_vault;
+        // With a cooldown configured, withdrawals go through the unbonding queue
+        require!(config.unbonding_period == 0, KernelError::UnbondingRequired);
 
-        require!(
-            lp_vault.pending_deployment >= amount,
-            KernelError::InsufficientLPFunds
-        );
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = user_stake.shares;
+        remove_stake(config, user_stake, amount, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
 
         let mint_key = ctx.accounts.token_mint.key();
         let seeds = &[
-            b"lp_vault_token",
+            b"staking_vault",
             mint_key.as_ref(),
-            &[lp_vault.vault_token_bump],
+            &[config.vault_bump],
         ];
         let signer_seeds = &[&seeds[..]];
 
-        let decimals = ctx.accounts.token_mint.decimals;
-
         token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 TransferChecked {
-                    from: ctx.accounts.lp_vault_token.to_account_info(),
+                    from: ctx.accounts.staking_vault.to_account_info(),
                     mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.authority_token_account.to_account_info(),
-                    authority: ctx.accounts.lp_vault_token.to_account_info(),
+                    to: ctx.accounts.holder_token_account.to_account_info(),
+                    authority: ctx.accounts.staking_vault.to_account_info(),
                 },
                 signer_seeds,
             ),
             amount,
-            decimals,
+            ctx.accounts.token_mint.decimals,
         )?;
 
-        lp_vault.pending_deployment = lp_vault.pending_deployment.checked_sub(amount).unwrap();
+        msg!(
+            "Unstaked {} $KERNEL from position #{}",
+            amount,
+            ctx.accounts.position.position_id
+        );
 
-        msg!("Withdrew {} from LP vault", amount);
+        emit_cpi!(PositionUnstaked {
+            position: ctx.accounts.position.key(),
+            holder: ctx.accounts.holder.key(),
+            amount,
+            total_staked: config.total_staked,
+        });
 
         Ok(())
     }
-}
-
-// === CONSTANTS ===
 
-/// Precision for accumulated_per_share calculations (1e12)
-const PRECISION: u128 = 1_000_000_000_000;
+    /// Withdraw a whole position and forfeit every pending reward
+    /// Same as `emergency_unstake`, signed by the position's owner or receipt
+    /// holder, with reward pools and the unbonding ticket passed the same way.
+    /// The ticket belongs to the holder, as in `request_position_unstake`.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn emergency_unstake_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyUnstakePosition<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
 
-/// Timelock duration for fee updates (24 hours in seconds)
-const TIMELOCK_DURATION: i64 = 24 * 60 * 60;
+        let current_time = Clock::get()?.unix_timestamp;
+        let forfeited_rewards = user_stake.pending_rewards;
+        let amount = forfeit_stake(config, user_stake, current_time)?;
+        forfeit_reward_states(ctx.remaining_accounts, config, user_stake.key())?;
+
+        if let Some(ticket) = ctx.accounts.unbonding_ticket.as_mut() {
+            // The principal waits out the cooldown like any other exit
+            ticket.owner = ctx.accounts.holder.key();
+            ticket.user_stake = user_stake.key();
+            ticket.bump = ctx
+                .bumps
+                .unbonding_ticket
+                .ok_or(KernelError::UnbondingRequired)?;
+            open_unbonding_ticket(config, user_stake, ticket, amount, current_time)?;
+
+            msg!(
+                "Emergency unbonding {} $KERNEL from position #{}, rewards forfeited",
+                amount,
+                ctx.accounts.position.position_id
+            );
+            msg!("Release at {}", ticket.release_time);
+
+            emit_cpi!(UnstakeRequested {
+                owner: ticket.owner,
+                ticket: ticket.key(),
+                amount,
+                release_time: ticket.release_time,
+            });
+        } else {
+            require!(config.unbonding_period == 0, KernelError::UnbondingRequired);
+
+            let mint_key = ctx.accounts.token_mint.key();
+            let seeds = &[b"staking_vault", mint_key.as_ref(), &[config.vault_bump]];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.staking_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.holder_token_account.to_account_info(),
+                        authority: ctx.accounts.staking_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!(
+                "Emergency unstaked {} $KERNEL from position #{}, rewards forfeited",
+                amount,
+                ctx.accounts.position.position_id
+            );
+        }
 
-// === HELPER FUNCTIONS ===
+        emit_cpi!(PositionEmergencyUnstaked {
+            position: ctx.accounts.position.key(),
+            holder: ctx.accounts.holder.key(),
+            amount,
+            forfeited_rewards,
+            total_staked: config.total_staked,
+        });
 
-fn calculate_pending_rewards(
-    user_staked: u64,
-    accumulated_per_share: u128,
-    reward_debt: u128,
-) -> u64 {
-    if user_staked == 0 {
-        return 0;
+        Ok(())
     }
 
-    let accumulated = (user_staked as u128)
-        .checked_mul(accumulated_per_share)
-        .unwrap()
-        .checked_div(PRECISION)
-        .unwrap();
-
-    accumulated.saturating_sub(reward_debt) as u64
+    /// Start unbonding part of a position
+    /// The ticket belongs to the holder who requested it and is withdrawn
+    /// with `withdraw_unbonded`, even if the position changes hands later.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn request_position_unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RequestPositionUnstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = user_stake.shares;
+        remove_stake(config, user_stake, amount, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        let ticket = &mut ctx.accounts.unbonding_ticket;
+        ticket.owner = ctx.accounts.holder.key();
+        ticket.user_stake = user_stake.key();
+        ticket.bump = ctx.bumps.unbonding_ticket;
+        open_unbonding_ticket(config, user_stake, ticket, amount, current_time)?;
+
+        msg!(
+            "Unbonding {} $KERNEL from position #{}",
+            amount,
+            ctx.accounts.position.position_id
+        );
+        msg!("Release at {}", ticket.release_time);
+
+        emit_cpi!(UnstakeRequested {
+            owner: ticket.owner,
+            ticket: ticket.key(),
+            amount,
+            release_time: ticket.release_time,
+        });
+
+        Ok(())
+    }
+
+    /// Open a position's reward state for a pool (permissionless)
+    /// Like init_user_reward_state, but keyed by position id so it can be
+    /// opened before the position itself. Zero debt is safe for the same
+    /// reason: the position's shares cannot have changed without it.
+    pub fn init_position_reward_state(
+        ctx: Context<InitPositionRewardState>,
+        _position_id: u64,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.user_reward_state;
+        state.version = ACCOUNT_VERSION;
+        state.owner = ctx.accounts.position.key();
+        state.user_stake = ctx.accounts.user_stake.key();
+        state.reward_pool = ctx.accounts.reward_pool.key();
+        state.reward_debt = 0;
+        state.pending_rewards = 0;
+        state.total_claimed = 0;
+        state.bump = ctx.bumps.user_reward_state;
+
+        msg!("Reward state opened for pool {}", state.reward_pool);
+
+        emit_cpi!(RewardStateOpened {
+            reward_pool: state.reward_pool,
+            user_stake: state.user_stake,
+            owner: state.owner,
+            payer: ctx.accounts.payer.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Claim a position's reflections and reward pools to its holder
+    /// Remaining accounts are grouped as in `claim_all_rewards`.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to claim their earned rewards,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn claim_position_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimPositionRewards<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let holder = ctx.accounts.holder.key();
+        let mut claimed_any = false;
+
+        // Reflections in the staking mint
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+        if paid > 0 {
+            let mint_key = ctx.accounts.token_mint.key();
+            let seeds = &[
+                b"reflection_pool",
+                mint_key.as_ref(),
+                &[ctx.bumps.reflection_pool],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.reflection_pool.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.holder_token_account.to_account_info(),
+                        authority: ctx.accounts.reflection_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                paid,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!("Claimed {} $KERNEL in reflections!", paid);
+            if deferred > 0 {
+                msg!(
+                    "Reflection pool short - {} $KERNEL owed until it is topped up",
+                    deferred
+                );
+            }
+            emit_cpi!(ReflectionsClaimed {
+                owner: holder,
+                amount: paid,
+                deferred,
+            });
+            claimed_any = true;
+        }
+
+        // Extra reward pools
+        let claims = claim_reward_pools(
+            ctx.remaining_accounts,
+            config.key(),
+            user_stake.key(),
+            user_stake.shares,
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_token_program,
+        )?;
+        for (reward_mint, amount) in claims {
+            emit_cpi!(RewardsClaimed {
+                owner: holder,
+                reward_mint,
+                amount,
+            });
+            claimed_any = true;
+        }
+
+        require!(claimed_any, KernelError::NothingToClaim);
+
+        Ok(())
+    }
+
+    /// Lock tokens in a vesting schedule for a beneficiary
+    /// Each schedule gets its own vault PDA. Nothing vests before
+    /// `start + cliff`; after that the grant vests linearly until
+    /// `start + duration`. A revocable grant can be revoked by its grantor,
+    /// who gets back whatever has not vested yet.
+    ///
+    /// The grant is what the vault actually received, net of any transfer fee.
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        beneficiary: Pubkey,
+        total: u64,
+        cliff: i64,    // Seconds after start before anything vests
+        start: i64,    // Unix timestamp vesting is measured from
+        duration: i64, // Seconds after start until fully vested
+        revocable: bool,
+    ) -> Result<()> {
+        require!(
+            valid_vesting(total, cliff, start, duration),
+            KernelError::InvalidVestingSchedule
+        );
+
+        let vault_before = ctx.accounts.vesting_vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.grantor_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.vesting_vault.to_account_info(),
+                    authority: ctx.accounts.grantor.to_account_info(),
+                },
+            ),
+            total,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        ctx.accounts.vesting_vault.reload()?;
+        let total = ctx.accounts.vesting_vault.amount.safe_sub(vault_before)?;
+        require!(total > 0, KernelError::InvalidVestingSchedule);
+
+        let config = &mut ctx.accounts.config;
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        schedule.version = ACCOUNT_VERSION;
+        schedule.config = config.key();
+        schedule.vesting_id = config.vesting_count;
+        schedule.grantor = ctx.accounts.grantor.key();
+        schedule.beneficiary = beneficiary;
+        schedule.total_amount = total;
+        schedule.released_amount = 0;
+        schedule.staked_amount = 0;
+        schedule.start_time = start;
+        schedule.cliff = cliff;
+        schedule.duration = duration;
+        schedule.revocable = revocable;
+        schedule.revoked_at = 0;
+        schedule.unbonding_until = 0;
+        schedule.bump = ctx.bumps.vesting_schedule;
+        schedule.vault_bump = ctx.bumps.vesting_vault;
+        config.vesting_count = config.vesting_count.safe_add(1)?;
+
+        msg!(
+            "Vesting #{}: {} $KERNEL for {}",
+            schedule.vesting_id,
+            total,
+            beneficiary
+        );
+
+        emit_cpi!(VestingCreated {
+            schedule: schedule.key(),
+            vesting_id: schedule.vesting_id,
+            grantor: schedule.grantor,
+            beneficiary,
+            total,
+            start,
+            cliff,
+            duration,
+            revocable,
+        });
+
+        Ok(())
+    }
+
+    /// Release vested tokens from a schedule's vault to its beneficiary
+    /// Vested tokens that are staked must be unstaked with unstake_vesting
+    /// first, and wait out the unbonding cooldown if one is set.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Beneficiaries must always be able to collect vested tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time >= schedule.unbonding_until,
+            KernelError::UnbondingNotReady
+        );
+
+        let amount = releasable_amount(schedule, current_time)?;
+        require!(amount > 0, KernelError::NothingVested);
+
+        let schedule_key = schedule.key();
+        let seeds = &[
+            b"vesting_vault",
+            schedule_key.as_ref(),
+            &[schedule.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vesting_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                    authority: ctx.accounts.vesting_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        schedule.released_amount = schedule.released_amount.safe_add(amount)?;
+
+        msg!("Released {} vested $KERNEL", amount);
+
+        emit_cpi!(VestingReleased {
+            schedule: schedule_key,
+            beneficiary: schedule.beneficiary,
+            amount,
+            released_amount: schedule.released_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Stake tokens still held by a vesting schedule
+    /// The tokens move from the vesting vault into the staking vault on the
+    /// schedule's own stake (`[b"stake", config, schedule]`), delegated to
+    /// the schedule: the beneficiary claims its rewards with
+    /// claim_vesting_rewards, but only unstake_vesting (or a revoke) takes the
+    /// tokens out, and only back into the vesting vault. The beneficiary's
+    /// wallet stake and other schedules are untouched.
+    ///
+    /// The stake is credited with what the staking vault received; a transfer
+    /// fee lost on the way comes out of the grant.
+    ///
+    /// Reward pool states are passed as in `stake`.
+    pub fn stake_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StakeVesting<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+        let schedule_key = ctx.accounts.vesting_schedule.key();
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        require!(schedule.revoked_at == 0, KernelError::VestingRevoked);
+        require!(
+            amount <= vesting_held(schedule)?,
+            KernelError::InsufficientVesting
+        );
+
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(config, PauseScope::Stake, current_time),
+            KernelError::ProgramPaused
+        );
+        accrue_reflection_stream(config, current_time)?;
+
+        let seeds = &[
+            b"vesting_vault",
+            schedule_key.as_ref(),
+            &[schedule.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let vault_before = ctx.accounts.staking_vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vesting_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.vesting_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        ctx.accounts.staking_vault.reload()?;
+        let received = ctx.accounts.staking_vault.amount.safe_sub(vault_before)?;
+        charge_vesting_fee(schedule, amount, received)?;
+
+        // Always (re)delegated, so stake_for can never mix gifts into it
+        user_stake.version = ACCOUNT_VERSION;
+        user_stake.owner = schedule_key;
+        user_stake.delegate = schedule_key;
+        user_stake.bump = ctx.bumps.user_stake;
+        let old_shares = credit_stake(config, user_stake, received, None, current_time)?;
+        sync_reward_states(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        schedule.staked_amount = schedule.staked_amount.safe_add(received)?;
+
+        msg!("Staked {} vesting $KERNEL", received);
+
+        emit_cpi!(VestingStaked {
+            schedule: schedule_key,
+            beneficiary: schedule.beneficiary,
+            amount: received,
+            staked_amount: schedule.staked_amount,
+            total_staked: config.total_staked,
+        });
+
+        Ok(())
+    }
+
+    /// Unstake vesting tokens back into the schedule's vault
+    /// Pending rewards stay on the schedule's stake for claim_vesting_rewards.
+    /// With an unbonding cooldown set, releases from the schedule wait it out.
+    /// A transfer fee lost on the way back comes out of the grant.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn unstake_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeVesting<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            amount <= ctx.accounts.vesting_schedule.staked_amount,
+            KernelError::InsufficientStake
+        );
+
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let old_shares = user_stake.shares;
+        remove_stake(config, user_stake, amount, current_time)?;
+        sync_reward_states_on_exit(
+            ctx.remaining_accounts,
+            config,
+            user_stake.key(),
+            old_shares,
+            user_stake.shares,
+        )?;
+
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"staking_vault",
+            mint_key.as_ref(),
+            &[config.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let vault_before = ctx.accounts.vesting_vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.vesting_vault.to_account_info(),
+                    authority: ctx.accounts.staking_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        ctx.accounts.vesting_vault.reload()?;
+        let received = ctx.accounts.vesting_vault.amount.safe_sub(vault_before)?;
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        schedule.staked_amount = schedule.staked_amount.safe_sub(amount)?;
+        charge_vesting_fee(schedule, amount, received)?;
+        schedule.unbonding_until = current_time.safe_add(config.unbonding_period)?;
+
+        msg!("Unstaked {} vesting $KERNEL", amount);
+
+        emit_cpi!(VestingUnstaked {
+            schedule: schedule.key(),
+            beneficiary: schedule.beneficiary,
+            amount,
+            staked_amount: schedule.staked_amount,
+            total_staked: config.total_staked,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a schedule's whole stake into its vault, forfeiting rewards
+    /// Same as `emergency_unstake`, signed by the beneficiary, with reward
+    /// pools passed the same way. Like `unstake_vesting`, releases then wait
+    /// out the unbonding cooldown.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to withdraw their staked tokens,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn emergency_unstake_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyUnstakeVesting<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let forfeited_rewards = user_stake.pending_rewards;
+        let amount = forfeit_stake(config, user_stake, current_time)?;
+        forfeit_reward_states(ctx.remaining_accounts, config, user_stake.key())?;
+
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"staking_vault",
+            mint_key.as_ref(),
+            &[config.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let vault_before = ctx.accounts.vesting_vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.vesting_vault.to_account_info(),
+                    authority: ctx.accounts.staking_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        ctx.accounts.vesting_vault.reload()?;
+        let received = ctx.accounts.vesting_vault.amount.safe_sub(vault_before)?;
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        return_vesting_stake(schedule, received)?;
+        schedule.unbonding_until = current_time.safe_add(config.unbonding_period)?;
+
+        msg!(
+            "Emergency unstaked {} vesting $KERNEL, rewards forfeited",
+            received
+        );
+
+        emit_cpi!(VestingEmergencyUnstaked {
+            schedule: schedule.key(),
+            beneficiary: schedule.beneficiary,
+            amount: received,
+            forfeited_rewards,
+            total_staked: config.total_staked,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a vesting stake's reflections and reward pools to the beneficiary
+    /// Remaining accounts are grouped as in `claim_all_rewards`.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Users must always be able to claim their earned rewards,
+    /// even during emergency pauses. This is a safety feature.
+    pub fn claim_vesting_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimVestingRewards<'info>>,
+    ) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let config = &mut ctx.accounts.config;
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let mut claimed_any = false;
+
+        // Reflections in the staking mint
+        let current_time = Clock::get()?.unix_timestamp;
+        accrue_reflection_stream(config, current_time)?;
+        let total_claimable = settle_claimable(config, user_stake)?;
+        let (paid, deferred) = draw_reflections(
+            config,
+            user_stake,
+            total_claimable,
+            ctx.accounts.reflection_pool.amount,
+        )?;
+        if paid > 0 {
+            let mint_key = ctx.accounts.token_mint.key();
+            let seeds = &[
+                b"reflection_pool",
+                mint_key.as_ref(),
+                &[ctx.bumps.reflection_pool],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.reflection_pool.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                        authority: ctx.accounts.reflection_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                paid,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!("Claimed {} $KERNEL in reflections!", paid);
+            if deferred > 0 {
+                msg!(
+                    "Reflection pool short - {} $KERNEL owed until it is topped up",
+                    deferred
+                );
+            }
+            emit_cpi!(ReflectionsClaimed {
+                owner: beneficiary,
+                amount: paid,
+                deferred,
+            });
+            claimed_any = true;
+        }
+
+        // Extra reward pools
+        let claims = claim_reward_pools(
+            ctx.remaining_accounts,
+            config.key(),
+            user_stake.key(),
+            user_stake.shares,
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_token_program,
+        )?;
+        for (reward_mint, amount) in claims {
+            emit_cpi!(RewardsClaimed {
+                owner: beneficiary,
+                reward_mint,
+                amount,
+            });
+            claimed_any = true;
+        }
+
+        require!(claimed_any, KernelError::NothingToClaim);
+
+        Ok(())
+    }
+
+    /// Revoke a revocable schedule and return its unvested tokens to the grantor
+    /// Any staked vesting tokens are unstaked first (pass the schedule's
+    /// stake and reward states as in `unstake`; missing states are skipped).
+    /// What had vested stays in the vault for the beneficiary to release.
+    pub fn revoke_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RevokeVesting<'info>>,
+    ) -> Result<()> {
+        let schedule_key = ctx.accounts.vesting_schedule.key();
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        require!(schedule.revocable, KernelError::VestingNotRevocable);
+        require!(schedule.revoked_at == 0, KernelError::VestingRevoked);
+
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        let mint_key = ctx.accounts.token_mint.key();
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Pull the staked part back into the vesting vault
+        let staked = schedule.staked_amount;
+        if staked > 0 {
+            let user_stake = ctx
+                .accounts
+                .user_stake
+                .as_mut()
+                .ok_or(KernelError::DelegationMismatch)?;
+
+            accrue_reflection_stream(config, current_time)?;
+            let old_shares = user_stake.shares;
+            remove_stake(config, user_stake, staked, current_time)?;
+            sync_reward_states_on_exit(
+                ctx.remaining_accounts,
+                config,
+                user_stake.key(),
+                old_shares,
+                user_stake.shares,
+            )?;
+
+            let seeds = &[b"staking_vault", mint_key.as_ref(), &[config.vault_bump]];
+            let signer_seeds = &[&seeds[..]];
+
+            let vault_before = ctx.accounts.vesting_vault.amount;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.staking_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.vesting_vault.to_account_info(),
+                        authority: ctx.accounts.staking_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                staked,
+                decimals,
+            )?;
+            ctx.accounts.vesting_vault.reload()?;
+            let received = ctx.accounts.vesting_vault.amount.safe_sub(vault_before)?;
+
+            return_vesting_stake(schedule, received)?;
+            schedule.unbonding_until = current_time.safe_add(config.unbonding_period)?;
+        }
+
+        // Freeze the grant at what has vested and return the rest
+        let returned = freeze_vesting(schedule, current_time)?;
+        let vested = schedule.total_amount;
+
+        if returned > 0 {
+            let seeds = &[
+                b"vesting_vault",
+                schedule_key.as_ref(),
+                &[schedule.vault_bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vesting_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.grantor_token_account.to_account_info(),
+                        authority: ctx.accounts.vesting_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                returned,
+                decimals,
+            )?;
+        }
+
+        msg!(
+            "Vesting #{} revoked - {} $KERNEL returned",
+            schedule.vesting_id,
+            returned
+        );
+
+        emit_cpi!(VestingRevoked {
+            schedule: schedule_key,
+            grantor: schedule.grantor,
+            beneficiary: schedule.beneficiary,
+            vested,
+            returned,
+        });
+
+        Ok(())
+    }
+
+    /// Burn tokens from supply
+    /// Actually burns tokens using SPL Token burn instruction
+    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(&ctx.accounts.config, PauseScope::Burns, current_time),
+            KernelError::ProgramPaused
+        );
+
+        // Burn tokens from authority's account
+        // Note: burn() doesn't require decimals unlike transfer_checked()
+        let _decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // Update burn record
+        let burn_record = &mut ctx.accounts.burn_record;
+        burn_record.version = ACCOUNT_VERSION;
+        burn_record.total_burned = burn_record.total_burned.safe_add(amount)?;
+        burn_record.burn_count = burn_record.burn_count.safe_add(1)?;
+        burn_record.last_burn_time = current_time;
+        burn_record.bump = ctx.bumps.burn_record;
+
+        msg!("Burned {} $KERNEL! Total burned: {}", amount, burn_record.total_burned);
+        msg!("Colonel Kernel salutes your sacrifice!");
+
+        emit_cpi!(TokensBurned {
+            burner: ctx.accounts.authority.key(),
+            amount,
+            total_burned: burn_record.total_burned,
+        });
+
+        Ok(())
+    }
+
+    /// Create the fee vault that receives withheld transfer fees
+    /// The vault PDA must then be set as the mint's withdraw-withheld authority
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        msg!("Fee vault initialized: {}", ctx.accounts.fee_vault.key());
+        msg!("Set it as the mint's withdraw-withheld authority");
+        emit_cpi!(FeeVaultInitialized {
+            fee_vault: ctx.accounts.fee_vault.key(),
+        });
+        Ok(())
+    }
+
+    /// Harvest withheld transfer fees and split them on-chain
+    /// Permissionless: withheld fees are pulled into the fee vault PDA and
+    /// split by the config shares into reflections, LP and burn. Reflections
+    /// and the LP vault are credited with what they receive net of transfer fees.
+    ///
+    /// Token accounts passed as remaining accounts are harvested into the
+    /// mint first, so their withheld fees are included in the same run.
+    ///
+    /// Pausing reward deposits, LP allocation or burns pauses the harvest;
+    /// fees stay withheld on the mint until it is lifted.
+    pub fn harvest_and_distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestAndDistribute<'info>>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !HARVEST_SCOPES
+                .iter()
+                .any(|s| is_paused(config, *s, current_time)),
+            KernelError::ProgramPaused
+        );
+
+        // Sweep withheld fees from the given token accounts into the mint
+        if !ctx.remaining_accounts.is_empty() {
+            token_interface::harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                    },
+                ),
+                ctx.remaining_accounts.to_vec(),
+            )?;
+        }
+
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[b"fee_vault", mint_key.as_ref(), &[ctx.bumps.fee_vault]];
+        let signer_seeds = &[&seeds[..]];
+
+        // Withdraw everything withheld on the mint into the fee vault
+        token_interface::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            WithdrawWithheldTokensFromMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                destination: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        ctx.accounts.fee_vault.reload()?;
+        let amount = ctx.accounts.fee_vault.amount;
+        require!(amount > 0, KernelError::NothingToHarvest);
+
+        let config = &mut ctx.accounts.config;
+        let (reflection_amount, lp_amount, burn_amount) = split_fees(
+            amount,
+            config.reflection_share_bps,
+            config.lp_share_bps,
+            config.burn_share_bps,
+        );
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Each destination is credited with what it receives, net of any
+        // transfer fee (which is withheld again and picked up next harvest)
+        let mut reflection_received = 0;
+        let mut lp_received = 0;
+
+        // Reflections
+        if reflection_amount > 0 {
+            let pool_before = ctx.accounts.reflection_pool.amount;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.fee_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.reflection_pool.to_account_info(),
+                        authority: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                reflection_amount,
+                decimals,
+            )?;
+            ctx.accounts.reflection_pool.reload()?;
+            reflection_received = ctx.accounts.reflection_pool.amount.safe_sub(pool_before)?;
+            if reflection_received > 0 {
+                distribute_reflections(config, reflection_received, current_time)?;
+            }
+        }
+
+        // LP allocation
+        if lp_amount > 0 {
+            let lp_before = ctx.accounts.lp_vault_token.amount;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.fee_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.lp_vault_token.to_account_info(),
+                        authority: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                lp_amount,
+                decimals,
+            )?;
+            ctx.accounts.lp_vault_token.reload()?;
+            lp_received = ctx.accounts.lp_vault_token.amount.safe_sub(lp_before)?;
+
+            let lp_vault = &mut ctx.accounts.lp_vault;
+            lp_vault.total_allocated = lp_vault.total_allocated.safe_add(lp_received)?;
+            lp_vault.pending_deployment = lp_vault.pending_deployment.safe_add(lp_received)?;
+        }
+
+        // Burn
+        if burn_amount > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        from: ctx.accounts.fee_vault.to_account_info(),
+                        authority: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                burn_amount,
+            )?;
+
+            let burn_record = &mut ctx.accounts.burn_record;
+            burn_record.version = ACCOUNT_VERSION;
+            burn_record.total_burned = burn_record.total_burned.safe_add(burn_amount)?;
+            burn_record.burn_count = burn_record.burn_count.safe_add(1)?;
+            burn_record.last_burn_time = current_time;
+            burn_record.bump = ctx.bumps.burn_record;
+        }
+
+        msg!("Harvested {} $KERNEL in fees!", amount);
+        msg!("Reflections: {}", reflection_received);
+        msg!("LP: {}", lp_received);
+        msg!("Burned: {}", burn_amount);
+
+        emit_cpi!(FeesHarvested {
+            harvester: ctx.accounts.harvester.key(),
+            amount,
+            reflection_amount: reflection_received,
+            lp_amount: lp_received,
+            burn_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Create an airdrop campaign (starts in Draft)
+    /// Each campaign has its own Merkle root, claim window, budget and vault
+    ///
+    /// Each leaf commits to `(claimant, amount)`. Claims are recorded in a
+    /// per-claimant receipt PDA, so a wallet claims at most once per campaign.
+    pub fn create_campaign(
+        ctx: Context<CreateCampaign>,
+        campaign_id: u64,
+        merkle_root: [u8; 32],
+        budget: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require!(budget > 0, KernelError::ZeroAmount);
+        require!(end_time > start_time, KernelError::InvalidCampaignWindow);
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.version = ACCOUNT_VERSION;
+        campaign.config = ctx.accounts.config.key();
+        campaign.campaign_id = campaign_id;
+        campaign.merkle_root = merkle_root;
+        campaign.start_time = start_time;
+        campaign.end_time = end_time;
+        campaign.budget = budget;
+        campaign.total_funded = 0;
+        campaign.total_claimed = 0;
+        campaign.claim_count = 0;
+        campaign.status = CampaignStatus::Draft;
+        campaign.bump = ctx.bumps.campaign;
+        campaign.vault_bump = ctx.bumps.campaign_vault;
+
+        msg!("Campaign {} created! Popcorn incoming!", campaign_id);
+        msg!("Budget: {} $KERNEL", budget);
+
+        emit_cpi!(CampaignCreated {
+            campaign: campaign.key(),
+            campaign_id,
+            merkle_root,
+            budget,
+            start_time,
+            end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Fund a campaign vault from the airdrop manager's token account
+    /// The campaign becomes Active once its full budget has reached the vault;
+    /// only what arrives, net of any transfer fee, counts towards it
+    pub fn fund_campaign(ctx: Context<FundCampaign>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+
+        let campaign = &ctx.accounts.campaign;
+        require!(
+            campaign.status != CampaignStatus::Closed,
+            KernelError::CampaignClosed
+        );
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let vault_before = ctx.accounts.campaign_vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.campaign_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
+        // Only what reached the vault counts towards the budget
+        ctx.accounts.campaign_vault.reload()?;
+        let received = ctx.accounts.campaign_vault.amount.safe_sub(vault_before)?;
+
+        let campaign = &mut ctx.accounts.campaign;
+        let new_funded = campaign.total_funded.safe_add(received)?;
+        require!(
+            new_funded <= campaign.budget,
+            KernelError::CampaignBudgetExceeded
+        );
+        campaign.total_funded = new_funded;
+        if campaign.total_funded == campaign.budget {
+            campaign.status = CampaignStatus::Active;
+        }
+
+        msg!(
+            "Campaign {} funded with {} $KERNEL ({}/{})",
+            campaign.campaign_id,
+            received,
+            campaign.total_funded,
+            campaign.budget
+        );
+
+        emit_cpi!(CampaignFunded {
+            campaign: campaign.key(),
+            campaign_id: campaign.campaign_id,
+            amount: received,
+            total_funded: campaign.total_funded,
+            active: campaign.status == CampaignStatus::Active,
+        });
+
+        Ok(())
+    }
+
+    /// Claim an airdrop allocation with a Merkle proof
+    /// Transfers tokens from the campaign vault to the claimant
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+
+        let campaign = &ctx.accounts.campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !is_paused(
+                &ctx.accounts.config,
+                PauseScope::AirdropClaims,
+                current_time
+            ),
+            KernelError::ProgramPaused
+        );
+
+        require!(
+            campaign_accepts_claims(campaign, current_time),
+            KernelError::CampaignNotActive
+        );
+        require!(
+            campaign.total_claimed.safe_add(amount)? <= campaign.budget,
+            KernelError::CampaignBudgetExceeded
+        );
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf = airdrop_leaf(&claimant, amount);
+        require!(
+            verify_merkle_proof(&proof, campaign.merkle_root, leaf),
+            KernelError::InvalidMerkleProof
+        );
+
+        // Transfer tokens from campaign vault to claimant
+        let campaign_key = campaign.key();
+        let seeds = &[
+            b"campaign_vault",
+            campaign_key.as_ref(),
+            &[campaign.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.campaign_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: ctx.accounts.campaign_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )?;
+
+        // Record the claim so it can never be repeated
+        let receipt = &mut ctx.accounts.claim_receipt;
+        receipt.version = ACCOUNT_VERSION;
+        receipt.claimant = claimant;
+        receipt.amount = amount;
+        receipt.claimed_at = current_time;
+        receipt.bump = ctx.bumps.claim_receipt;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.total_claimed = campaign.total_claimed.safe_add(amount)?;
+        campaign.claim_count = campaign.claim_count.safe_add(1)?;
+
+        msg!(
+            "Airdrop claimed: {} $KERNEL from campaign {}. Enjoy the popcorn!",
+            amount,
+            campaign.campaign_id
+        );
+
+        emit_cpi!(AirdropClaimed {
+            campaign: campaign.key(),
+            campaign_id: campaign.campaign_id,
+            claimant,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Close a campaign (airdrop manager only)
+    /// Stops further funding and claims so leftovers can be swept
+    pub fn close_campaign(ctx: Context<CloseCampaign>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(
+            campaign.status != CampaignStatus::Closed,
+            KernelError::CampaignClosed
+        );
+
+        campaign.status = CampaignStatus::Closed;
+
+        msg!("Campaign {} closed", campaign.campaign_id);
+
+        emit_cpi!(CampaignClosed {
+            campaign: campaign.key(),
+            campaign_id: campaign.campaign_id,
+        });
+
+        Ok(())
+    }
+
+    /// Sweep unclaimed tokens from a closed campaign back to the airdrop manager
+    pub fn sweep_campaign(ctx: Context<SweepCampaign>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Closed,
+            KernelError::CampaignNotClosed
+        );
+
+        let amount = ctx.accounts.campaign_vault.amount;
+        require!(amount > 0, KernelError::NothingToSweep);
+
+        let campaign_key = campaign.key();
+        let seeds = &[
+            b"campaign_vault",
+            campaign_key.as_ref(),
+            &[campaign.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.campaign_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.campaign_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )?;
+
+        msg!(
+            "Swept {} unclaimed $KERNEL from campaign {}",
+            amount,
+            campaign.campaign_id
+        );
+
+        emit_cpi!(CampaignSwept {
+            campaign: campaign.key(),
+            campaign_id: campaign.campaign_id,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Queue a timelocked governance action
+    /// Executable between eta (the action type's delay out) and expires_at
+    /// Fee changes are also put to a staker vote until eta
+    /// A queued pause is for planned maintenance; emergencies use `set_paused`
+    /// (pauser role) or the guardians, which take effect immediately.
+    pub fn propose_action(ctx: Context<ProposeAction>, payload: GovernancePayload) -> Result<()> {
+        validate_governance_payload(&payload)?;
+
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        let delay = action_delay(config, &payload);
+
+        let action = &mut ctx.accounts.governance_action;
+        action.version = ACCOUNT_VERSION;
+        action.config = config.key();
+        action.nonce = config.governance_action_count;
+        action.proposer = ctx.accounts.authority.key();
+        action.payload = payload;
+        action.proposed_at = current_time;
+        action.eta = current_time.safe_add(delay)?;
+        action.expires_at = action.eta.safe_add(EXECUTION_WINDOW)?;
+        action.executed = false;
+        action.cancelled = false;
+        action.bump = ctx.bumps.governance_action;
+        action.votes_for = 0;
+        action.votes_against = 0;
+        action.total_staked_snapshot = config.total_staked;
+        if is_voted_payload(&action.payload) {
+            config.last_vote_opened_at = current_time;
+            config.vote_closes_at = config.vote_closes_at.max(action.eta);
+        }
+
+        config.governance_action_count = config.governance_action_count.safe_add(1)?;
+
+        msg!("Action #{} proposed! Timelock: {}s", action.nonce, delay);
+
+        emit_cpi!(GovernanceActionProposed {
+            action: action.key(),
+            nonce: action.nonce,
+            proposer: action.proposer,
+            payload: action.payload.clone(),
+            eta: action.eta,
+            expires_at: action.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Execute a queued governance action inside its execution window
+    /// Tier, guardian, LP and role payloads need their target account passed in
+    pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+        let action = &ctx.accounts.governance_action;
+        let current_time = Clock::get()?.unix_timestamp;
+        check_action_executable(action, current_time)?;
+        if is_voted_payload(&action.payload) {
+            check_vote_passed(action)?;
+        }
+
+        let payload = action.payload.clone();
+        let config = &mut ctx.accounts.config;
+
+        match &payload {
+            GovernancePayload::UpdateFees {
+                reflection_share_bps,
+                lp_share_bps,
+                burn_share_bps,
+            } => {
+                config.reflection_share_bps = *reflection_share_bps;
+                config.lp_share_bps = *lp_share_bps;
+                config.burn_share_bps = *burn_share_bps;
+            }
+            GovernancePayload::TransferAuthority { .. } => {
+                return err!(KernelError::AuthorityAcceptanceRequired);
+            }
+            GovernancePayload::SetPaused { paused } => {
+                let flags = legacy_pause_flags(*paused);
+                set_pause(config, flags, 0);
+                emit_cpi!(Paused {
+                    flags,
+                    paused_until: 0,
+                });
+            }
+            GovernancePayload::SetPauseFlags {
+                flags,
+                paused_until,
+            } => {
+                // The expiry may have passed while the action sat in the queue
+                require!(
+                    valid_pause(*flags, *paused_until, current_time),
+                    KernelError::InvalidPause
+                );
+                set_pause(config, *flags, *paused_until);
+                emit_cpi!(Paused {
+                    flags: *flags,
+                    paused_until: *paused_until,
+                });
+            }
+            GovernancePayload::UpdateTiers { tiers } => {
+                let staking_tiers = ctx
+                    .accounts
+                    .staking_tiers
+                    .as_mut()
+                    .ok_or(KernelError::MissingActionAccount)?;
+                staking_tiers.tiers = tiers.clone();
+                emit_cpi!(StakingTiersUpdated {
+                    tiers: tiers.clone(),
+                });
+            }
+            GovernancePayload::UpdateGuardianSet {
+                guardians,
+                threshold,
+            } => {
+                let guardian_set = ctx
+                    .accounts
+                    .guardian_set
+                    .as_mut()
+                    .ok_or(KernelError::MissingActionAccount)?;
+                guardian_set.guardians = guardians.clone();
+                guardian_set.threshold = *threshold;
+                // Approvals collected by the old membership stop counting
+                guardian_set.epoch = guardian_set.epoch.safe_add(1)?;
+                emit_cpi!(GuardianSetUpdated {
+                    guardians: guardians.clone(),
+                    threshold: *threshold,
+                    epoch: guardian_set.epoch,
+                });
+            }
+            GovernancePayload::SetLpWithdrawLimit { withdraw_limit } => {
+                let lp_vault = ctx
+                    .accounts
+                    .lp_vault
+                    .as_mut()
+                    .ok_or(KernelError::MissingActionAccount)?;
+                lp_vault.withdraw_limit = *withdraw_limit;
+            }
+            GovernancePayload::GrantRole { role, holder } => {
+                let roles = ctx
+                    .accounts
+                    .roles
+                    .as_mut()
+                    .ok_or(KernelError::MissingActionAccount)?;
+                set_role(roles, *role, *holder);
+                emit_cpi!(roles_updated(roles));
+            }
+            GovernancePayload::RevokeRole { role } => {
+                let roles = ctx
+                    .accounts
+                    .roles
+                    .as_mut()
+                    .ok_or(KernelError::MissingActionAccount)?;
+                set_role(roles, *role, Pubkey::default());
+                emit_cpi!(roles_updated(roles));
+            }
+            GovernancePayload::SetActionDelay { kind, delay } => {
+                config.action_delays[*kind as usize] = *delay;
+                emit_cpi!(ActionDelayUpdated {
+                    kind: *kind,
+                    delay: *delay,
+                });
+            }
+            GovernancePayload::SetUnbondingPeriod { unbonding_period } => {
+                // Existing tickets keep the release time they were created with
+                config.unbonding_period = *unbonding_period;
+                emit_cpi!(UnbondingPeriodUpdated {
+                    unbonding_period: *unbonding_period,
+                });
+            }
+            GovernancePayload::SetRewardStreamDuration {
+                reward_stream_duration,
+            } => {
+                // Settle the running stream first; it keeps its rate and end,
+                // only later deposits use the new duration
+                accrue_reflection_stream(config, Clock::get()?.unix_timestamp)?;
+                config.reward_stream_duration = *reward_stream_duration;
+                emit_cpi!(RewardStreamDurationUpdated {
+                    reward_stream_duration: *reward_stream_duration,
+                });
+            }
+        }
+
+        // Mark as executed
+        let action = &mut ctx.accounts.governance_action;
+        action.executed = true;
+
+        msg!("Action #{} executed after timelock!", action.nonce);
+
+        emit_cpi!(GovernanceActionExecuted {
+            action: action.key(),
+            nonce: action.nonce,
+            payload,
+        });
+
+        Ok(())
+    }
+
+    /// Complete a queued authority transfer, signed by the new authority
+    /// Proves the new key is live; a transfer nobody accepts lapses at expiry
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        let action = &mut ctx.accounts.governance_action;
+        check_authority_acceptance(action, &new_authority, Clock::get()?.unix_timestamp)?;
+
+        let config = &mut ctx.accounts.config;
+        let old_authority = config.authority;
+        config.authority = new_authority;
+        action.executed = true;
+
+        msg!("Old authority: {}", old_authority);
+        msg!("New authority accepted: {}", new_authority);
+
+        emit_cpi!(AuthorityTransferExecuted {
+            action: action.key(),
+            old_authority,
+            new_authority,
+        });
+
+        // Roles the outgoing authority held move with it; skipped before initialize_roles
+        let roles_info = ctx.accounts.roles.to_account_info();
+        if !roles_info.data_is_empty() {
+            let mut roles = Roles::try_deserialize(&mut &roles_info.try_borrow_data()?[..])?;
+            if rotate_roles(&mut roles, old_authority, new_authority) {
+                roles.try_serialize(&mut &mut roles_info.try_borrow_mut_data()?[..])?;
+                emit_cpi!(roles_updated(&roles));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a queued governance action (authority only)
+    pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+        let action = &mut ctx.accounts.governance_action;
+
+        require!(!action.executed, KernelError::ProposalAlreadyExecuted);
+
+        action.cancelled = true;
+
+        msg!("Governance action #{} cancelled", action.nonce);
+
+        emit_cpi!(GovernanceActionCancelled {
+            action: action.key(),
+            nonce: action.nonce,
+        });
+
+        Ok(())
+    }
+
+    /// Close a settled governance action and return its rent to the proposer
+    /// Only executed, cancelled or expired actions, so anyone can crank it
+    pub fn close_action(ctx: Context<CloseAction>) -> Result<()> {
+        let action = &ctx.accounts.governance_action;
+
+        require!(
+            action_settled(action, Clock::get()?.unix_timestamp),
+            KernelError::ActionStillPending
+        );
+
+        msg!("Governance action #{} closed", action.nonce);
+
+        emit_cpi!(GovernanceActionClosed {
+            action: action.key(),
+            nonce: action.nonce,
+        });
+
+        Ok(())
+    }
+
+    /// Vote for or against a queued fee change, weighted by staked amount
+    /// Only stake that has not moved since the proposal can vote
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let action = &mut ctx.accounts.governance_action;
+        let weight = record_vote(action, &ctx.accounts.user_stake, support, current_time)?;
+
+        let receipt = &mut ctx.accounts.vote_receipt;
+        receipt.version = ACCOUNT_VERSION;
+        receipt.governance_action = action.key();
+        receipt.voter = ctx.accounts.voter.key();
+        receipt.support = support;
+        receipt.weight = weight;
+        receipt.voted_at = current_time;
+        receipt.bump = ctx.bumps.vote_receipt;
+        receipt.voting_ends_at = action.eta;
+
+        msg!("Voted {} on #{} with {}", support, action.nonce, weight);
+
+        emit_cpi!(VoteCast {
+            action: action.key(),
+            voter: receipt.voter,
+            support,
+            weight,
+            votes_for: action.votes_for,
+            votes_against: action.votes_against,
+        });
+
+        Ok(())
+    }
+
+    /// Close a vote receipt once voting on its action is over
+    /// The receipt only guards against double votes, so anyone can close it
+    /// after the voting window and the rent goes back to the voter
+    pub fn close_vote_receipt(ctx: Context<CloseVoteReceipt>) -> Result<()> {
+        let receipt = &ctx.accounts.vote_receipt;
+        require!(
+            Clock::get()?.unix_timestamp >= receipt.voting_ends_at,
+            KernelError::VotingStillOpen
+        );
+
+        msg!("Vote receipt closed for {}", receipt.voter);
+
+        emit_cpi!(VoteReceiptClosed {
+            action: receipt.governance_action,
+            voter: receipt.voter,
+        });
+
+        Ok(())
+    }
+
+    /// Initialize the lock-up tier table with the default tiers
+    /// 30/90/180/365 days at 1.1x/1.25x/1.5x/2x reward multipliers
+    pub fn initialize_staking_tiers(ctx: Context<InitializeStakingTiers>) -> Result<()> {
+        let staking_tiers = &mut ctx.accounts.staking_tiers;
+        staking_tiers.version = ACCOUNT_VERSION;
+        staking_tiers.tiers = DEFAULT_LOCK_TIERS
+            .iter()
+            .map(|&(lock_duration, multiplier_bps)| LockTier {
+                lock_duration,
+                multiplier_bps,
+            })
+            .collect();
+        staking_tiers.bump = ctx.bumps.staking_tiers;
+
+        msg!("Staking tiers: {}", staking_tiers.tiers.len());
+
+        emit_cpi!(StakingTiersUpdated {
+            tiers: staking_tiers.tiers.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Create the role table with every role held by the authority
+    /// Roles are then split across keys through timelocked governance actions
+    pub fn initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+
+        let roles = &mut ctx.accounts.roles;
+        roles.version = ACCOUNT_VERSION;
+        roles.config = ctx.accounts.config.key();
+        roles.admin = authority;
+        roles.pauser = authority;
+        roles.reflection_depositor = authority;
+        roles.lp_operator = authority;
+        roles.burner = authority;
+        roles.airdrop_manager = authority;
+        roles.bump = ctx.bumps.roles;
+
+        msg!("Roles initialized to {}", authority);
+
+        emit_cpi!(roles_updated(roles));
+
+        Ok(())
+    }
+
+    /// Register the guardian set that approves emergency actions
+    /// Later membership changes go through a timelocked governance action
+    pub fn initialize_guardian_set(
+        ctx: Context<InitializeGuardianSet>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            validate_guardian_set(&guardians, threshold),
+            KernelError::InvalidGuardianSet
+        );
+
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        guardian_set.version = ACCOUNT_VERSION;
+        guardian_set.config = ctx.accounts.config.key();
+        guardian_set.guardians = guardians;
+        guardian_set.threshold = threshold;
+        guardian_set.epoch = 0;
+        guardian_set.action_count = 0;
+        guardian_set.bump = ctx.bumps.guardian_set;
+
+        msg!(
+            "Guardian set: {}-of-{}",
+            threshold,
+            guardian_set.guardians.len()
+        );
+
+        emit_cpi!(GuardianSetUpdated {
+            guardians: guardian_set.guardians.clone(),
+            threshold,
+            epoch: guardian_set.epoch,
+        });
+
+        Ok(())
+    }
+
+    /// Propose an emergency action (guardian only)
+    /// The proposer's approval is counted immediately
+    pub fn propose_guardian_action(
+        ctx: Context<ProposeGuardianAction>,
+        action: EmergencyAction,
+    ) -> Result<()> {
+        if let EmergencyAction::UpdateFees {
+            reflection_share_bps,
+            lp_share_bps,
+            burn_share_bps,
+        } = action
+        {
+            require!(
+                valid_fee_split(reflection_share_bps, lp_share_bps, burn_share_bps),
+                KernelError::InvalidFeeConfig
+            );
+        }
+        if let EmergencyAction::SetPauseFlags {
+            flags,
+            paused_until,
+        } = action
+        {
+            require!(
+                valid_pause(flags, paused_until, Clock::get()?.unix_timestamp),
+                KernelError::InvalidPause
+            );
+        }
+
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        let proposal = &mut ctx.accounts.guardian_action;
+        proposal.version = ACCOUNT_VERSION;
+        proposal.guardian_set = guardian_set.key();
+        proposal.nonce = guardian_set.action_count;
+        proposal.proposer = ctx.accounts.guardian.key();
+        proposal.action = action;
+        proposal.epoch = guardian_set.epoch;
+        proposal.approvals = 0;
+        proposal.proposed_at = Clock::get()?.unix_timestamp;
+        proposal.expires_at = proposal.proposed_at.safe_add(GUARDIAN_ACTION_WINDOW)?;
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.guardian_action;
+        record_guardian_approval(guardian_set, proposal, &ctx.accounts.guardian.key())?;
+
+        guardian_set.action_count = guardian_set.action_count.safe_add(1)?;
+
+        msg!("Emergency action #{} proposed", proposal.nonce);
+
+        emit_cpi!(GuardianActionProposed {
+            proposal: proposal.key(),
+            nonce: proposal.nonce,
+            proposer: proposal.proposer,
+            action,
+            expires_at: proposal.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Approve a pending emergency action (guardian only)
+    pub fn approve_guardian_action(ctx: Context<ApproveGuardianAction>) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let proposal = &mut ctx.accounts.guardian_action;
+
+        check_guardian_action_open(proposal, Clock::get()?.unix_timestamp)?;
+        record_guardian_approval(guardian_set, proposal, &ctx.accounts.guardian.key())?;
+
+        let approvals = proposal.approvals.count_ones() as u8;
+        msg!(
+            "Emergency action #{} approvals: {}/{}",
+            proposal.nonce,
+            approvals,
+            guardian_set.threshold
+        );
+
+        emit_cpi!(GuardianActionApproved {
+            proposal: proposal.key(),
+            guardian: ctx.accounts.guardian.key(),
+            approvals,
+        });
+
+        Ok(())
+    }
+
+    /// Execute an emergency action once the guardian threshold is met
+    /// No timelock - this is the break-glass path
+    pub fn execute_guardian_action(ctx: Context<ExecuteGuardianAction>) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let proposal = &mut ctx.accounts.guardian_action;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        check_guardian_action_open(proposal, current_time)?;
+        require!(
+            proposal.epoch == guardian_set.epoch,
+            KernelError::StaleGuardianProposal
+        );
+        require!(
+            proposal.approvals.count_ones() >= guardian_set.threshold as u32,
+            KernelError::GuardianThresholdNotMet
+        );
+
+        let config = &mut ctx.accounts.config;
+        match proposal.action {
+            EmergencyAction::UpdateFees {
+                reflection_share_bps,
+                lp_share_bps,
+                burn_share_bps,
+            } => {
+                config.reflection_share_bps = reflection_share_bps;
+                config.lp_share_bps = lp_share_bps;
+                config.burn_share_bps = burn_share_bps;
+            }
+            EmergencyAction::SetPaused { paused } => {
+                let flags = legacy_pause_flags(paused);
+                set_pause(config, flags, 0);
+                emit_cpi!(Paused {
+                    flags,
+                    paused_until: 0,
+                });
+            }
+            EmergencyAction::SetPauseFlags {
+                flags,
+                paused_until,
+            } => {
+                // Approvals can take long enough for the expiry to pass
+                require!(
+                    valid_pause(flags, paused_until, current_time),
+                    KernelError::InvalidPause
+                );
+                set_pause(config, flags, paused_until);
+                emit_cpi!(Paused {
+                    flags,
+                    paused_until,
+                });
+            }
+        }
+
+        proposal.executed = true;
+
+        msg!("Emergency action executed by the guardians!");
+
+        emit_cpi!(GuardianActionExecuted {
+            proposal: proposal.key(),
+            nonce: proposal.nonce,
+            action: proposal.action,
+        });
+
+        Ok(())
+    }
+
+    /// Pause/unpause subsystems (emergency only)
+    /// `flags` replaces the whole mask (0 unpauses everything). A non-zero
+    /// `paused_until` lifts the pause by itself at that time.
+    pub fn set_paused(ctx: Context<SetPaused>, flags: u8, paused_until: i64) -> Result<()> {
+        require!(
+            valid_pause(flags, paused_until, Clock::get()?.unix_timestamp),
+            KernelError::InvalidPause
+        );
+        set_pause(&mut ctx.accounts.config, flags, paused_until);
+        msg!("Pause flags: {:#07b} until {}", flags, paused_until);
+        emit_cpi!(Paused {
+            flags,
+            paused_until,
+        });
+        Ok(())
+    }
+
+    /// Upgrade a version 0 config to the current versioned layout
+    /// Reallocs in place; the authority pays any extra rent. The burn record
+    /// and LP vault are upgraded in the same call when they still use the
+    /// version 0 layout.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let authority = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        let config_info = ctx.accounts.config.to_account_info();
+        let legacy: LegacyKernelConfig = read_legacy_account(
+            &config_info,
+            KernelConfig::DISCRIMINATOR,
+            8 + KernelConfig::INIT_SPACE,
+        )?;
+        require_keys_eq!(legacy.authority, authority.key(), KernelError::NotAuthority);
+        let config = migrate_config_layout(&legacy);
+        write_upgraded_account(&config_info, &authority, &system_program, &config)?;
+
+        let burn_info = ctx.accounts.burn_record.to_account_info();
+        if is_legacy_layout(
+            &burn_info,
+            BurnRecord::DISCRIMINATOR,
+            8 + LegacyBurnRecord::INIT_SPACE,
+        ) {
+            let legacy: LegacyBurnRecord = read_legacy_account(
+                &burn_info,
+                BurnRecord::DISCRIMINATOR,
+                8 + BurnRecord::INIT_SPACE,
+            )?;
+            let burn_record = migrate_burn_record_layout(&legacy);
+            write_upgraded_account(&burn_info, &authority, &system_program, &burn_record)?;
+        }
+
+        let lp_vault_info = ctx.accounts.lp_vault.to_account_info();
+        if is_legacy_layout(
+            &lp_vault_info,
+            LPVault::DISCRIMINATOR,
+            8 + LegacyLPVault::INIT_SPACE,
+        ) {
+            let legacy: LegacyLPVault = read_legacy_account(
+                &lp_vault_info,
+                LPVault::DISCRIMINATOR,
+                8 + LPVault::INIT_SPACE,
+            )?;
+            let lp_vault = migrate_lp_vault_layout(&legacy);
+            write_upgraded_account(&lp_vault_info, &authority, &system_program, &lp_vault)?;
+        }
+
+        msg!("Config migrated to layout v{}", ACCOUNT_VERSION);
+
+        emit_cpi!(AccountMigrated {
+            account: config_info.key(),
+            version: ACCOUNT_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade a version 0 stake position to the current versioned layout
+    /// Permissionless: the migration is deterministic and the payer only
+    /// covers the extra rent. The config must be migrated first.
+    pub fn migrate_user_stake(ctx: Context<MigrateUserStake>) -> Result<()> {
+        let user_stake_info = ctx.accounts.user_stake.to_account_info();
+        let legacy: LegacyUserStake = read_legacy_account(
+            &user_stake_info,
+            UserStake::DISCRIMINATOR,
+            8 + UserStake::INIT_SPACE,
+        )?;
+        require_keys_eq!(
+            legacy.owner,
+            ctx.accounts.owner.key(),
+            KernelError::UnknownAccountLayout
+        );
+
+        let user_stake = migrate_user_stake_layout(&legacy);
+        write_upgraded_account(
+            &user_stake_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &user_stake,
+        )?;
+
+        msg!("Stake migrated to layout v{}", ACCOUNT_VERSION);
+
+        emit_cpi!(AccountMigrated {
+            account: user_stake_info.key(),
+            version: ACCOUNT_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Initialize LP vault for fee allocation tracking
+    pub fn initialize_lp_vault(ctx: Context<InitializeLPVault>) -> Result<()> {
+        let lp_vault = &mut ctx.accounts.lp_vault;
+        lp_vault.version = ACCOUNT_VERSION;
+        lp_vault.authority = ctx.accounts.authority.key();
+        lp_vault.token_mint = ctx.accounts.token_mint.key();
+        lp_vault.total_allocated = 0;
+        lp_vault.total_deployed = 0;
+        lp_vault.pending_deployment = 0;
+        lp_vault.last_deployment_time = 0;
+        lp_vault.withdraw_limit = 0;
+        lp_vault.withdrawn_in_period = 0;
+        lp_vault.period_start = 0;
+        lp_vault.bump = ctx.bumps.lp_vault;
+        lp_vault.vault_token_bump = ctx.bumps.lp_vault_token;
+
+        msg!("LP Vault initialized!");
+        emit_cpi!(LpVaultInitialized {
+            lp_vault: lp_vault.key(),
+            lp_vault_token: ctx.accounts.lp_vault_token.key(),
+        });
+        Ok(())
+    }
+
+    /// Allocate tokens to LP vault from harvested fees
+    /// This is the on-chain portion - actual LP addition happens off-chain
+    ///
+    /// Only the LP allocation flag stops it; pausing other subsystems
+    /// leaves LP operations running to maintain liquidity.
+    pub fn allocate_to_lp(ctx: Context<AllocateToLP>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+        require!(
+            !is_paused(
+                &ctx.accounts.config,
+                PauseScope::LpAllocation,
+                Clock::get()?.unix_timestamp
+            ),
+            KernelError::ProgramPaused
+        );
+
+        let lp_vault = &mut ctx.accounts.lp_vault;
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Transfer tokens from authority to LP vault
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.lp_vault_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
+
+        // Update tracking
+        lp_vault.total_allocated = lp_vault.total_allocated.safe_add(amount)?;
+        lp_vault.pending_deployment = lp_vault.pending_deployment.safe_add(amount)?;
+
+        msg!("Allocated {} tokens to LP vault", amount);
+        msg!("Pending deployment: {}", lp_vault.pending_deployment);
+
+        emit_cpi!(LpAllocated {
+            amount,
+            pending_deployment: lp_vault.pending_deployment,
+        });
+
+        Ok(())
+    }
+
+    /// Mark tokens as deployed to LP (called after off-chain LP addition)
+    /// Records the actual LP deployment for accounting
+    pub fn record_lp_deployment(
+        ctx: Context<RecordLPDeployment>,
+        amount: u64,
+        lp_tokens_received: u64,
+        pool_address: Pubkey,
+    ) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+
+        let lp_vault = &mut ctx.accounts.lp_vault;
+        let deployment = &mut ctx.accounts.lp_deployment;
+
+        require!(
+            lp_vault.pending_deployment >= amount,
+            KernelError::InsufficientLPFunds
+        );
+
+        // Update vault accounting
+        lp_vault.pending_deployment = lp_vault.pending_deployment.safe_sub(amount)?;
+        lp_vault.total_deployed = lp_vault.total_deployed.safe_add(amount)?;
+        lp_vault.last_deployment_time = Clock::get()?.unix_timestamp;
+
+        // Record deployment details
+        deployment.version = ACCOUNT_VERSION;
+        deployment.pool_address = pool_address;
+        deployment.kernel_amount = amount;
+        deployment.lp_tokens_received = lp_tokens_received;
+        deployment.deployed_at = Clock::get()?.unix_timestamp;
+        deployment.withdrawn = false;
+        deployment.bump = ctx.bumps.lp_deployment;
+
+        msg!("LP deployment recorded!");
+        msg!("  KERNEL deployed: {}", amount);
+        msg!("  LP tokens received: {}", lp_tokens_received);
+        msg!("  Pool: {}", pool_address);
+
+        emit_cpi!(LpDeployed {
+            deployment: deployment.key(),
+            pool_address,
+            amount,
+            lp_tokens_received,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw tokens from LP vault (emergency only)
+    /// With a withdraw limit set, at most that much leaves per 24-hour period.
+    ///
+    /// NOTE: This function intentionally does NOT check the pause flags.
+    /// Emergency withdrawals must always be possible for fund recovery.
+    pub fn withdraw_from_lp_vault(ctx: Context<WithdrawFromLPVault>, amount: u64) -> Result<()> {
+        require!(amount > 0, KernelError::ZeroAmount);
+
+        let lp_vault = &mut ctx.accounts.lp_vault;
+
+        require!(
+            lp_vault.pending_deployment >= amount,
+            KernelError::InsufficientLPFunds
+        );
+        record_lp_withdrawal(lp_vault, amount, Clock::get()?.unix_timestamp)?;
+
+        let mint_key = ctx.accounts.token_mint.key();
+        let seeds = &[
+            b"lp_vault_token",
+            mint_key.as_ref(),
+            &[lp_vault.vault_token_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.lp_vault_token.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.lp_vault_token.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )?;
+
+        lp_vault.pending_deployment = lp_vault.pending_deployment.safe_sub(amount)?;
+
+        msg!("Withdrew {} from LP vault", amount);
+
+        emit_cpi!(LpWithdrawn {
+            amount,
+            pending_deployment: lp_vault.pending_deployment,
+        });
+
+        Ok(())
+    }
+}
+
+// === CONSTANTS ===
+
+/// Precision for accumulated_per_share calculations (1e12)
+const PRECISION: u128 = 1_000_000_000_000;
+
+/// Delay migrated configs start with for every action type (24 hours)
+const DEFAULT_ACTION_DELAY: i64 = 24 * 60 * 60;
+
+/// Shortest delay any action type can be set to (5 minutes, for devnet)
+const MIN_ACTION_DELAY: i64 = 5 * 60;
+
+/// Longest delay any action type can be set to (30 days)
+const MAX_ACTION_DELAY: i64 = 30 * 24 * 60 * 60;
+
+/// Number of ActionKind variants (length of config.action_delays)
+const ACTION_KIND_COUNT: usize = 9;
+
+/// How long a governance action stays executable after its timelock (7 days)
+const EXECUTION_WINDOW: i64 = 7 * 24 * 60 * 60;
+
+/// How long an emergency action can collect approvals and execute (3 days)
+const GUARDIAN_ACTION_WINDOW: i64 = 3 * 24 * 60 * 60;
+
+/// Share of the staked supply that must vote on a fee change (10%)
+const QUORUM_BPS: u64 = 1_000;
+
+/// Basis-point denominator for reward multipliers (10000 = 1x)
+const BASE_MULTIPLIER_BPS: u16 = 10_000;
+
+/// Highest reward multiplier a lock tier may grant (3x)
+const MAX_MULTIPLIER_BPS: u16 = 30_000;
+
+/// Maximum number of lock-up tiers
+const MAX_LOCK_TIERS: usize = 8;
+
+/// Highest tip a staker can offer the compound crank (0.5%)
+const MAX_CRANK_TIP_BPS: u16 = 50;
+
+/// Longest unstake cooldown the authority can configure (30 days)
+const MAX_UNBONDING_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+/// Longest period a deposit can be streamed over (30 days)
+const MAX_REWARD_STREAM_DURATION: i64 = 30 * 24 * 60 * 60;
+
+/// Window the LP vault withdraw limit applies to (24 hours)
+const LP_WITHDRAW_PERIOD: i64 = 24 * 60 * 60;
+
+/// Default lock-up tiers: (lock duration in seconds, multiplier bps)
+const DEFAULT_LOCK_TIERS: [(i64, u16); 4] = [
+    (30 * 24 * 60 * 60, 11_000),
+    (90 * 24 * 60 * 60, 12_500),
+    (180 * 24 * 60 * 60, 15_000),
+    (365 * 24 * 60 * 60, 20_000),
+];
+
+/// Current layout version written to every program account
+/// Version 0 is the original unversioned layout (see migrate_config)
+const ACCOUNT_VERSION: u8 = 1;
+
+/// Maximum number of extra reward pools per config
+const MAX_REWARD_POOLS: u8 = 4;
+
+/// Remaining accounts per pool in claim_all_rewards
+const REWARD_CLAIM_ACCOUNTS: usize = 5;
+
+/// Maximum number of guardians (approvals are a u16 bitmap)
+const MAX_GUARDIANS: usize = 10;
+
+/// Pause flags, one bit per subsystem in config.pause_flags
+const PAUSE_STAKE: u8 = 1 << 0;
+const PAUSE_REWARD_DEPOSITS: u8 = 1 << 1;
+const PAUSE_AIRDROP_CLAIMS: u8 = 1 << 2;
+const PAUSE_LP_ALLOCATION: u8 = 1 << 3;
+const PAUSE_BURNS: u8 = 1 << 4;
+
+/// Every pause flag
+const PAUSE_ALL: u8 =
+    PAUSE_STAKE | PAUSE_REWARD_DEPOSITS | PAUSE_AIRDROP_CLAIMS | PAUSE_LP_ALLOCATION | PAUSE_BURNS;
+
+/// Subsystems harvest_and_distribute feeds; pausing any of them pauses it
+const HARVEST_SCOPES: [PauseScope; 3] = [
+    PauseScope::RewardDeposits,
+    PauseScope::LpAllocation,
+    PauseScope::Burns,
+];
+
+// === SAFE MATH ===
+
+/// Checked arithmetic that fails with a KernelError instead of panicking
+/// Overflow and underflow are MathOverflow; a zero divisor is DivisionByZero
+trait SafeMath: Sized {
+    fn safe_add(self, rhs: Self) -> Result<Self>;
+    fn safe_sub(self, rhs: Self) -> Result<Self>;
+    fn safe_mul(self, rhs: Self) -> Result<Self>;
+    fn safe_div(self, rhs: Self) -> Result<Self>;
+}
+
+macro_rules! impl_safe_math {
+    ($($t:ty),*) => {$(
+        impl SafeMath for $t {
+            fn safe_add(self, rhs: Self) -> Result<Self> {
+                self.checked_add(rhs).ok_or(error!(KernelError::MathOverflow))
+            }
+
+            fn safe_sub(self, rhs: Self) -> Result<Self> {
+                self.checked_sub(rhs).ok_or(error!(KernelError::MathOverflow))
+            }
+
+            fn safe_mul(self, rhs: Self) -> Result<Self> {
+                self.checked_mul(rhs).ok_or(error!(KernelError::MathOverflow))
+            }
+
+            fn safe_div(self, rhs: Self) -> Result<Self> {
+                require!(rhs != 0, KernelError::DivisionByZero);
+                self.checked_div(rhs).ok_or(error!(KernelError::MathOverflow))
+            }
+        }
+    )*};
+}
+
+impl_safe_math!(u8, u32, u64, u128, i64);
+
+/// Narrow a u128 intermediate back to a token amount
+fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(KernelError::MathOverflow))
+}
+
+// === HELPER FUNCTIONS ===
+
+/// Reward shares for a staked amount under a lock multiplier
+fn calculate_shares(staked_amount: u64, multiplier_bps: u16) -> Result<u64> {
+    to_u64(
+        (staked_amount as u128)
+            .safe_mul(multiplier_bps as u128)?
+            .safe_div(BASE_MULTIPLIER_BPS as u128)?,
+    )
+}
+
+/// Look up a lock tier, if one was asked for
+fn lock_tier_for(
+    staking_tiers: Option<&Account<StakingTiers>>,
+    lock_tier: Option<u8>,
+) -> Result<Option<LockTier>> {
+    let Some(tier_index) = lock_tier else {
+        return Ok(None);
+    };
+    let tier = staking_tiers
+        .ok_or(KernelError::InvalidLockTier)?
+        .tiers
+        .get(tier_index as usize)
+        .ok_or(KernelError::InvalidLockTier)?;
+    Ok(Some(*tier))
+}
+
+/// Settle pending rewards and add already-transferred `amount` to a position
+/// An expired lock falls back to the base multiplier; a tier replaces a running
+/// lock only if it runs longer. Returns the shares before the change.
+fn credit_stake(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    amount: u64,
+    tier: Option<LockTier>,
+    current_time: i64,
+) -> Result<u64> {
+    // Resolve the lock: an expired lock falls back to the base multiplier
+    let (mut multiplier_bps, mut lock_until) = if user_stake.lock_until > current_time {
+        (user_stake.lock_multiplier_bps, user_stake.lock_until)
+    } else {
+        (BASE_MULTIPLIER_BPS, 0)
+    };
+    // The multiplier travels with the lock that earned it: a new tier only
+    // applies if it locks for longer than what is already in place
+    if let Some(tier) = tier {
+        let tier_until = current_time.safe_add(tier.lock_duration)?;
+        if tier_until > lock_until {
+            multiplier_bps = tier.multiplier_bps;
+            lock_until = tier_until;
+        }
+    }
+
+    checkpoint_vote_weight(config, user_stake, current_time);
+    user_stake.staked_amount = user_stake.staked_amount.safe_add(amount)?;
+    user_stake.stake_time = current_time;
+    user_stake.lock_multiplier_bps = multiplier_bps;
+    user_stake.lock_until = lock_until;
+
+    let old_shares = user_stake.shares;
+    user_stake.shares = calculate_shares(user_stake.staked_amount, multiplier_bps)?;
+
+    // Update global state
+    config.total_staked = config.total_staked.safe_add(amount)?;
+    config.total_shares = config
+        .total_shares
+        .safe_sub(old_shares)?
+        .safe_add(user_stake.shares)?;
+
+    settle_reflection_debt(user_stake, old_shares, config.accumulated_per_share)?;
+
+    Ok(old_shares)
+}
+
+/// Settle pending rewards and rebase an expired lock to base shares
+/// Returns the shares before the change.
+fn expire_lock(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    current_time: i64,
+) -> Result<u64> {
+    require!(
+        user_stake.lock_until > 0 && current_time >= user_stake.lock_until,
+        KernelError::NoExpiredLock
+    );
+
+    user_stake.lock_multiplier_bps = BASE_MULTIPLIER_BPS;
+    user_stake.lock_until = 0;
+
+    let old_shares = user_stake.shares;
+    user_stake.shares = calculate_shares(user_stake.staked_amount, BASE_MULTIPLIER_BPS)?;
+
+    config.total_shares = config
+        .total_shares
+        .safe_sub(old_shares)?
+        .safe_add(user_stake.shares)?;
+
+    settle_reflection_debt(user_stake, old_shares, config.accumulated_per_share)?;
+
+    Ok(old_shares)
+}
+
+/// Key allowed to take principal out of a position: its delegate if it has one
+fn withdraw_authority(user_stake: &UserStake) -> Pubkey {
+    if user_stake.delegate == Pubkey::default() {
+        user_stake.owner
+    } else {
+        user_stake.delegate
+    }
+}
+
+/// Key controlling a stake position: its owner until a receipt is minted,
+/// then the owner of the token account holding the receipt (None if absent)
+fn position_holder(position: &StakePosition, receipt_owner: Option<Pubkey>) -> Option<Pubkey> {
+    if position.receipt_mint == Pubkey::default() {
+        Some(position.owner)
+    } else {
+        receipt_owner
+    }
+}
+
+/// A `stake_for` deposit that delegates or locks the position needs the
+/// beneficiary's signature; only an unlocked gift can be made without it
+fn check_beneficiary_consent(
+    beneficiary: Pubkey,
+    signer: Option<Pubkey>,
+    beneficiary_only: bool,
+    locked: bool,
+) -> Result<()> {
+    if beneficiary_only && !locked {
+        return Ok(());
+    }
+    require!(
+        signer == Some(beneficiary),
+        KernelError::BeneficiaryNotSigner
+    );
+    Ok(())
+}
+
+/// Delegate a `stake_for` deposit leaves on the position (default = none)
+/// A delegated position only takes more delegated stake from the same funder,
+/// and a funded or gifted position never becomes delegated.
+fn check_delegation(
+    user_stake: &UserStake,
+    funder: Pubkey,
+    beneficiary_only: bool,
+) -> Result<Pubkey> {
+    if beneficiary_only {
+        require!(
+            user_stake.delegate == Pubkey::default(),
+            KernelError::DelegationMismatch
+        );
+        return Ok(Pubkey::default());
+    }
+
+    let fresh = user_stake.delegate == Pubkey::default()
+        && user_stake.staked_amount == 0
+        && user_stake.unbonding_amount == 0;
+    require!(
+        fresh || user_stake.delegate == funder,
+        KernelError::DelegationMismatch
+    );
+    Ok(funder)
+}
+
+/// Schedule parameters create_vesting accepts: a positive total and duration,
+/// with the cliff inside the duration and the end a valid timestamp
+fn valid_vesting(total: u64, cliff: i64, start: i64, duration: i64) -> bool {
+    total > 0
+        && duration > 0
+        && (0..=duration).contains(&cliff)
+        && start >= 0
+        && start.checked_add(duration).is_some()
+}
+
+/// Tokens a schedule has vested by `current_time`
+/// Nothing before the cliff, then linear from start_time to the end.
+/// A revoked schedule is frozen at total_amount, set to what had vested.
+fn vested_amount(schedule: &VestingSchedule, current_time: i64) -> Result<u64> {
+    if schedule.revoked_at > 0 || current_time >= schedule.start_time.safe_add(schedule.duration)? {
+        return Ok(schedule.total_amount);
+    }
+    if current_time < schedule.start_time.safe_add(schedule.cliff)? {
+        return Ok(0);
+    }
+
+    let elapsed = current_time.safe_sub(schedule.start_time)?;
+    to_u64(
+        (schedule.total_amount as u128)
+            .safe_mul(elapsed as u128)?
+            .safe_div(schedule.duration as u128)?,
+    )
+}
+
+/// Tokens sitting in a schedule's vault (neither released nor staked)
+fn vesting_held(schedule: &VestingSchedule) -> Result<u64> {
+    schedule
+        .total_amount
+        .safe_sub(schedule.released_amount)?
+        .safe_sub(schedule.staked_amount)
+}
+
+/// Vested tokens the beneficiary can release now
+/// Vested tokens that are still staked have to be unstaked first
+fn releasable_amount(schedule: &VestingSchedule, current_time: i64) -> Result<u64> {
+    // A grant shrunk by transfer fees can vest less than was already released
+    let unreleased =
+        vested_amount(schedule, current_time)?.saturating_sub(schedule.released_amount);
+    Ok(unreleased.min(vesting_held(schedule)?))
+}
+
+/// Shrink a grant by what moving it between vaults lost to the transfer fee,
+/// so `vesting_held` keeps matching the vesting vault's balance
+fn charge_vesting_fee(schedule: &mut VestingSchedule, sent: u64, received: u64) -> Result<()> {
+    schedule.total_amount = schedule.total_amount.safe_sub(sent.safe_sub(received)?)?;
+    Ok(())
+}
+
+/// Bring a schedule's whole stake back into its vault
+/// The grant is rebased on what arrived: less after a transfer fee, more if
+/// a gift landed on the stake while it sat empty
+fn return_vesting_stake(schedule: &mut VestingSchedule, received: u64) -> Result<()> {
+    schedule.total_amount = schedule
+        .total_amount
+        .safe_sub(schedule.staked_amount)?
+        .safe_add(received)?;
+    schedule.staked_amount = 0;
+    Ok(())
+}
+
+/// Freeze a grant at what has vested (never below what was released) and
+/// return how much of the unstaked remainder goes back to the grantor
+fn freeze_vesting(schedule: &mut VestingSchedule, current_time: i64) -> Result<u64> {
+    let kept = vested_amount(schedule, current_time)?.max(schedule.released_amount);
+    let returned = schedule.total_amount.safe_sub(kept)?;
+    schedule.total_amount = kept;
+    schedule.revoked_at = current_time;
+    Ok(returned)
+}
+
+/// Settle pending rewards and take `amount` out of a stake position
+/// The lock must have expired, so the position drops back to base shares
+fn remove_stake(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    require!(amount > 0, KernelError::ZeroAmount);
+    require!(
+        user_stake.staked_amount >= amount,
+        KernelError::InsufficientStake
+    );
+    require!(
+        current_time >= user_stake.lock_until,
+        KernelError::StakeLocked
+    );
+
+    checkpoint_vote_weight(config, user_stake, current_time);
+    user_stake.staked_amount = user_stake.staked_amount.safe_sub(amount)?;
+    user_stake.lock_multiplier_bps = BASE_MULTIPLIER_BPS;
+    user_stake.lock_until = 0;
+    if user_stake.staked_amount == 0 {
+        user_stake.delegate = Pubkey::default();
+    }
+
+    let old_shares = user_stake.shares;
+    user_stake.shares = calculate_shares(user_stake.staked_amount, BASE_MULTIPLIER_BPS)?;
+
+    config.total_staked = config.total_staked.safe_sub(amount)?;
+    config.total_shares = config
+        .total_shares
+        .safe_sub(old_shares)?
+        .safe_add(user_stake.shares)?;
+
+    settle_reflection_debt(user_stake, old_shares, config.accumulated_per_share)?;
+
+    Ok(())
+}
+
+/// Queue `amount`, already taken off the stake, behind the unbonding cooldown
+/// The tokens stay in the staking vault until the ticket's release time.
+/// The caller fills in the ticket's owner, stake and bump.
+fn open_unbonding_ticket(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    ticket: &mut UnbondingTicket,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    ticket.version = ACCOUNT_VERSION;
+    ticket.nonce = user_stake.unbonding_nonce;
+    ticket.amount = amount;
+    ticket.requested_at = current_time;
+    ticket.release_time = current_time.safe_add(config.unbonding_period)?;
+
+    user_stake.unbonding_nonce = user_stake.unbonding_nonce.safe_add(1)?;
+    user_stake.unbonding_amount = user_stake.unbonding_amount.safe_add(amount)?;
+    config.total_unbonding = config.total_unbonding.safe_add(amount)?;
+    Ok(())
+}
+
+/// Empty a stake position without touching any reward accumulator
+/// Settled rewards are zeroed and unsettled ones are never computed.
+/// Totals saturate so bookkeeping drift cannot block the exit.
+/// Returns the principal to pay out.
+fn forfeit_stake(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    current_time: i64,
+) -> Result<u64> {
+    let amount = user_stake.staked_amount;
+    require!(amount > 0, KernelError::InsufficientStake);
+    require!(
+        current_time >= user_stake.lock_until,
+        KernelError::StakeLocked
+    );
+
+    config.total_staked = config.total_staked.saturating_sub(amount);
+    config.total_shares = config.total_shares.saturating_sub(user_stake.shares);
+    config.reflection_owed = config
+        .reflection_owed
+        .saturating_sub(user_stake.reflection_owed);
+
+    checkpoint_vote_weight(config, user_stake, current_time);
+    user_stake.staked_amount = 0;
+    user_stake.shares = 0;
+    user_stake.pending_rewards = 0;
+    user_stake.reward_debt = 0;
+    user_stake.reward_fraction = 0;
+    user_stake.reflection_owed = 0;
+    user_stake.lock_multiplier_bps = BASE_MULTIPLIER_BPS;
+    user_stake.lock_until = 0;
+    user_stake.delegate = Pubkey::default();
+
+    Ok(amount)
+}
+
+/// Fold accrued rewards into a claim, leaving the position fully settled
+/// Returns the total claimable amount, which the caller must pay out
+fn settle_claimable(config: &mut KernelConfig, user_stake: &mut UserStake) -> Result<u64> {
+    settle_reflection_debt(user_stake, user_stake.shares, config.accumulated_per_share)?;
+    let total_claimable = user_stake.pending_rewards;
+
+    user_stake.pending_rewards = 0;
+    user_stake.total_claimed = user_stake.total_claimed.safe_add(total_claimable)?;
+
+    Ok(total_claimable)
+}
+
+/// Move reflections accrued on `old_shares` into pending_rewards and rebase
+/// the debt on the current shares
+/// Whatever is left below one base unit stays owed in reward_fraction. The
+/// new debt is rounded up and the difference added to the fraction, so
+/// rounding never takes anything from the staker.
+fn settle_reflection_debt(
+    user_stake: &mut UserStake,
+    old_shares: u64,
+    accumulated_per_share: u128,
+) -> Result<()> {
+    let owed = (old_shares as u128)
+        .safe_mul(accumulated_per_share)?
+        .safe_add(user_stake.reward_fraction as u128)?
+        .saturating_sub(user_stake.reward_debt.safe_mul(PRECISION)?);
+    user_stake.pending_rewards = user_stake
+        .pending_rewards
+        .safe_add(to_u64(owed.safe_div(PRECISION)?)?)?;
+
+    let accumulated = (user_stake.shares as u128).safe_mul(accumulated_per_share)?;
+    user_stake.reward_debt = accumulated.div_ceil(PRECISION);
+    let rounded_up = user_stake
+        .reward_debt
+        .safe_mul(PRECISION)?
+        .safe_sub(accumulated)?;
+    user_stake.reward_fraction = to_u64((owed % PRECISION).safe_add(rounded_up)?)?;
+    Ok(())
+}
+
+/// Pay a settled claim out of a reflection pool holding `pool_balance`
+/// While the pool holds less than pending_reflections every claim is paid at
+/// the same balance / liability ratio. The unpaid part moves out of
+/// pending_reflections into the staker's reflection_owed, keeping the ratio
+/// the same for everyone else, and is repaid from any balance above
+/// pending_reflections once the pool is topped up.
+/// A claim that would pay nothing stays pending instead.
+/// Returns (paid, deferred); the caller transfers `paid`.
+fn draw_reflections(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    claimable: u64,
+    pool_balance: u64,
+) -> Result<(u64, u64)> {
+    let mut paid = 0;
+    let mut deferred = 0;
+
+    if claimable > 0 {
+        // A claim above the recorded liability is drift; count it as liability
+        let liability = config.pending_reflections.max(claimable);
+        let payout = if pool_balance >= liability {
+            claimable
+        } else {
+            to_u64(
+                (claimable as u128)
+                    .safe_mul(pool_balance as u128)?
+                    .safe_div(liability as u128)?,
+            )?
+        };
+
+        if payout == 0 {
+            user_stake.pending_rewards = user_stake.pending_rewards.safe_add(claimable)?;
+            user_stake.total_claimed = user_stake.total_claimed.safe_sub(claimable)?;
+        } else {
+            deferred = claimable.safe_sub(payout)?;
+            user_stake.total_claimed = user_stake.total_claimed.safe_sub(deferred)?;
+            user_stake.reflection_owed = user_stake.reflection_owed.safe_add(deferred)?;
+            config.pending_reflections = liability.safe_sub(claimable)?;
+            config.reflection_owed = config.reflection_owed.safe_add(deferred)?;
+            paid = payout;
+        }
+    }
+
+    // Earlier shortfalls come out of whatever is left above current claims
+    let surplus = pool_balance
+        .saturating_sub(paid)
+        .saturating_sub(config.pending_reflections);
+    let repaid = user_stake.reflection_owed.min(surplus);
+    if repaid > 0 {
+        user_stake.reflection_owed = user_stake.reflection_owed.safe_sub(repaid)?;
+        user_stake.total_claimed = user_stake.total_claimed.safe_add(repaid)?;
+        config.reflection_owed = config.reflection_owed.saturating_sub(repaid);
+        paid = paid.safe_add(repaid)?;
+    }
+
+    config.total_reflections_distributed = config.total_reflections_distributed.safe_add(paid)?;
+    Ok((paid, deferred))
+}
+
+/// Record how far the reflection pool balance is above or below what it owes
+/// Owed covers pending_reflections and the unpaid part of earlier pro-rata claims.
+fn reconcile_reflection_pool(config: &mut KernelConfig, pool_balance: u64) {
+    let owed = reflection_liability(config);
+    config.reflection_surplus = pool_balance.saturating_sub(owed);
+    config.reflection_deficit = owed.saturating_sub(pool_balance);
+}
+
+/// Everything the reflection pool owes stakers
+fn reflection_liability(config: &KernelConfig) -> u64 {
+    config
+        .pending_reflections
+        .saturating_add(config.reflection_owed)
+}
+
+/// Add already-transferred rewards to a settled stake position
+/// A running lock keeps its multiplier; an expired one drops to base
+fn add_compounded_stake(
+    config: &mut KernelConfig,
+    user_stake: &mut UserStake,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    if user_stake.lock_until <= current_time {
+        user_stake.lock_multiplier_bps = BASE_MULTIPLIER_BPS;
+        user_stake.lock_until = 0;
+    }
+
+    checkpoint_vote_weight(config, user_stake, current_time);
+    user_stake.staked_amount = user_stake.staked_amount.safe_add(amount)?;
+
+    let old_shares = user_stake.shares;
+    user_stake.shares = calculate_shares(user_stake.staked_amount, user_stake.lock_multiplier_bps)?;
+
+    config.total_staked = config.total_staked.safe_add(amount)?;
+    config.total_shares = config
+        .total_shares
+        .safe_sub(old_shares)?
+        .safe_add(user_stake.shares)?;
+
+    settle_reflection_debt(user_stake, old_shares, config.accumulated_per_share)?;
+    Ok(())
+}
+
+/// Credit a reflection deposit to stakers
+/// With nobody staked the deposit waits in undistributed_reflections
+fn record_reflection_deposit(config: &mut KernelConfig, amount: u64) -> Result<()> {
+    if config.total_shares > 0 {
+        credit_reflections(config, (amount as u128).safe_mul(PRECISION)?)?;
+    } else {
+        config.undistributed_reflections = config.undistributed_reflections.safe_add(amount)?;
+    }
+
+    config.pending_reflections = config.pending_reflections.safe_add(amount)?;
+    Ok(())
+}
+
+/// Add `scaled` (tokens * PRECISION) and any carried dust to accumulated_per_share
+/// Rewards are split by boosted shares, not raw staked tokens. The division
+/// remainder is kept in reflection_remainder for the next credit, so nothing
+/// is rounded away. Callers make sure total_shares > 0.
+fn credit_reflections(config: &mut KernelConfig, scaled: u128) -> Result<()> {
+    let numerator = scaled
+        .safe_add((config.undistributed_reflections as u128).safe_mul(PRECISION)?)?
+        .safe_add(config.reflection_remainder as u128)?;
+    let total_shares = config.total_shares as u128;
+
+    config.accumulated_per_share = config
+        .accumulated_per_share
+        .safe_add(numerator.safe_div(total_shares)?)?;
+    config.reflection_remainder = to_u64(numerator % total_shares)?;
+    config.undistributed_reflections = 0;
+    Ok(())
+}
+
+/// Route a reflection deposit: streamed when a stream duration is set,
+/// otherwise credited to current stakers at once
+fn distribute_reflections(config: &mut KernelConfig, amount: u64, current_time: i64) -> Result<()> {
+    if config.reward_stream_duration > 0 {
+        start_reflection_stream(config, amount, current_time)
+    } else {
+        record_reflection_deposit(config, amount)
+    }
+}
+
+/// Accrue streamed reflections up to `current_time` (Synthetix-style)
+/// Time with no stakers pushes the period end back, so nothing streams
+/// into an empty pool. Deposits that arrived while nobody was staked are
+/// credited here once stake exists.
+fn accrue_reflection_stream(config: &mut KernelConfig, current_time: i64) -> Result<()> {
+    let applicable = current_time.min(config.reward_period_end);
+    let elapsed = (applicable - config.last_update_time).max(0);
+
+    if config.total_shares > 0 {
+        if elapsed > 0 || config.undistributed_reflections > 0 {
+            credit_reflections(config, config.reward_rate.safe_mul(elapsed as u128)?)?;
+        }
+    } else {
+        config.reward_period_end = config.reward_period_end.safe_add(elapsed)?;
+    }
+
+    if elapsed > 0 {
+        config.last_update_time = applicable;
+    }
+    Ok(())
+}
+
+/// Stream a deposit over `reward_stream_duration`
+/// Whatever is left of a running stream is rolled into the new one
+fn start_reflection_stream(
+    config: &mut KernelConfig,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    accrue_reflection_stream(config, current_time)?;
+
+    let leftover = if config.reward_period_end > current_time {
+        config
+            .reward_rate
+            .safe_mul((config.reward_period_end - current_time) as u128)?
+    } else {
+        0
+    };
+
+    // The part of the deposit the rate cannot stream is carried as dust
+    let scaled = (amount as u128).safe_mul(PRECISION)?.safe_add(leftover)?;
+    let duration = config.reward_stream_duration as u128;
+    config.reward_rate = scaled.safe_div(duration)?;
+    config.reflection_remainder = config
+        .reflection_remainder
+        .safe_add(to_u64(scaled % duration)?)?;
+    config.reward_period_end = current_time.safe_add(config.reward_stream_duration)?;
+    config.last_update_time = current_time;
+    config.pending_reflections = config.pending_reflections.safe_add(amount)?;
+    Ok(())
+}
+
+/// Record a deposit into an extra reward pool, split by boosted shares
+fn record_reward_deposit(
+    reward_pool: &mut RewardPool,
+    total_shares: u64,
+    amount: u64,
+) -> Result<()> {
+    let reward_per_share = (amount as u128)
+        .safe_mul(PRECISION)?
+        .safe_div(total_shares as u128)?;
+    reward_pool.accumulated_per_share = reward_pool
+        .accumulated_per_share
+        .safe_add(reward_per_share)?;
+    reward_pool.total_funded = reward_pool.total_funded.safe_add(amount)?;
+    Ok(())
+}
+
+/// Accrue a pool's rewards on the old shares and re-base debt on the new ones
+fn settle_reward_state(
+    state: &mut UserRewardState,
+    accumulated_per_share: u128,
+    old_shares: u64,
+    new_shares: u64,
+) -> Result<()> {
+    let pending = calculate_pending_rewards(old_shares, accumulated_per_share, state.reward_debt)?;
+    state.pending_rewards = state.pending_rewards.safe_add(pending)?;
+    state.reward_debt = calculate_reward_debt(new_shares, accumulated_per_share)?;
+    Ok(())
+}
+
+/// Drop a pool position's settled rewards; with no shares left the debt is zero
+fn forfeit_reward_state(state: &mut UserRewardState) {
+    state.pending_rewards = 0;
+    state.reward_debt = 0;
+}
+
+/// Settle a pool position and return everything it can claim
+fn settle_reward_claimable(
+    reward_pool: &mut RewardPool,
+    state: &mut UserRewardState,
+    shares: u64,
+) -> Result<u64> {
+    settle_reward_state(state, reward_pool.accumulated_per_share, shares, shares)?;
+    let amount = state.pending_rewards;
+    state.pending_rewards = 0;
+    state.total_claimed = state.total_claimed.safe_add(amount)?;
+    reward_pool.total_claimed = reward_pool.total_claimed.safe_add(amount)?;
+    Ok(amount)
+}
+
+/// Settle every reward pool around a share change
+/// `remaining` must hold `[reward_pool, user_reward_state]` for each pool
+fn sync_reward_states<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: &Account<KernelConfig>,
+    user_stake: Pubkey,
+    old_shares: u64,
+    new_shares: u64,
+) -> Result<()> {
+    sync_reward_pairs(remaining, config, user_stake, old_shares, new_shares, false)
+}
+
+/// Settle every reward pool around a share decrease on an exit path
+/// A pool the staker never opened a state for may pass the state's
+/// uninitialized PDA instead; it is skipped and the state, opened later,
+/// only earns on the shares held from then on. Exits never depend on the
+/// staker having opened every pool.
+fn sync_reward_states_on_exit<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: &Account<KernelConfig>,
+    user_stake: Pubkey,
+    old_shares: u64,
+    new_shares: u64,
+) -> Result<()> {
+    sync_reward_pairs(remaining, config, user_stake, old_shares, new_shares, true)
+}
+
+fn sync_reward_pairs<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: &Account<KernelConfig>,
+    user_stake: Pubkey,
+    old_shares: u64,
+    new_shares: u64,
+    allow_missing: bool,
+) -> Result<()> {
+    for_each_reward_state(
+        remaining,
+        config,
+        user_stake,
+        allow_missing,
+        |state, reward_pool| {
+            settle_reward_state(
+                state,
+                reward_pool.accumulated_per_share,
+                old_shares,
+                new_shares,
+            )
+        },
+    )
+}
+
+/// Zero every reward pool state of a forfeited position
+/// Pairs are passed as on the exit paths; no pool accumulator is read, so
+/// the exit never depends on reward math.
+fn forfeit_reward_states<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: &Account<KernelConfig>,
+    user_stake: Pubkey,
+) -> Result<()> {
+    for_each_reward_state(remaining, config, user_stake, true, |state, _| {
+        forfeit_reward_state(state);
+        Ok(())
+    })
+}
+
+/// Check `[reward_pool, user_reward_state]` pairs against the config's pools and
+/// apply `update` to each state, writing it back
+fn for_each_reward_state<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: &Account<KernelConfig>,
+    user_stake: Pubkey,
+    allow_missing: bool,
+    mut update: impl FnMut(&mut UserRewardState, &RewardPool) -> Result<()>,
+) -> Result<()> {
+    require!(
+        remaining.len() == 2 * config.reward_pool_count as usize,
+        KernelError::InvalidRewardAccounts
+    );
+
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(config.reward_pool_count as usize);
+    for pair in remaining.chunks(2) {
+        let reward_pool = Account::<RewardPool>::try_from(&pair[0])?;
+        require_keys_eq!(
+            reward_pool.config,
+            config.key(),
+            KernelError::InvalidRewardAccounts
+        );
+        require!(
+            !seen.contains(&reward_pool.key()),
+            KernelError::InvalidRewardAccounts
+        );
+        seen.push(reward_pool.key());
+
+        if allow_missing && pair[1].data_is_empty() {
+            let (missing_state, _) = Pubkey::find_program_address(
+                &[
+                    b"user_reward",
+                    reward_pool.key().as_ref(),
+                    user_stake.as_ref(),
+                ],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                pair[1].key(),
+                missing_state,
+                KernelError::InvalidRewardAccounts
+            );
+            continue;
+        }
+
+        let mut state = Account::<UserRewardState>::try_from(&pair[1])?;
+        require_keys_eq!(
+            state.reward_pool,
+            reward_pool.key(),
+            KernelError::InvalidRewardAccounts
+        );
+        require_keys_eq!(
+            state.user_stake,
+            user_stake,
+            KernelError::InvalidRewardAccounts
+        );
+
+        update(&mut state, &reward_pool)?;
+        state.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+/// Pay out every reward pool for a stake position
+/// `remaining` holds `[reward_pool, user_reward_state, reward_vault, reward_mint,
+/// owner_reward_account]` for each pool to claim. Returns (reward_mint, amount)
+/// for every pool that paid something.
+fn claim_reward_pools<'info>(
+    remaining: &'info [AccountInfo<'info>],
+    config: Pubkey,
+    user_stake: Pubkey,
+    shares: u64,
+    token_program: &Interface<'info, TokenInterface>,
+    reward_token_program: &Interface<'info, TokenInterface>,
+) -> Result<Vec<(Pubkey, u64)>> {
+    let groups = remaining.chunks_exact(REWARD_CLAIM_ACCOUNTS);
+    require!(
+        groups.remainder().is_empty(),
+        KernelError::InvalidRewardAccounts
+    );
+
+    let mut claims = Vec::with_capacity(groups.len());
+    for group in groups {
+        let mut reward_pool = Account::<RewardPool>::try_from(&group[0])?;
+        let mut state = Account::<UserRewardState>::try_from(&group[1])?;
+        require_keys_eq!(
+            reward_pool.config,
+            config,
+            KernelError::InvalidRewardAccounts
+        );
+        require_keys_eq!(
+            state.reward_pool,
+            reward_pool.key(),
+            KernelError::InvalidRewardAccounts
+        );
+        require_keys_eq!(
+            state.user_stake,
+            user_stake,
+            KernelError::InvalidRewardAccounts
+        );
+        require_keys_eq!(
+            group[2].key(),
+            reward_pool.reward_vault,
+            KernelError::InvalidRewardAccounts
+        );
+        require_keys_eq!(
+            group[3].key(),
+            reward_pool.reward_mint,
+            KernelError::InvalidRewardAccounts
+        );
+
+        let amount = settle_reward_claimable(&mut reward_pool, &mut state, shares)?;
+        if amount == 0 {
+            continue;
+        }
+
+        // Reward mints may live on either token program
+        let reward_mint = InterfaceAccount::<Mint>::try_from(&group[3])?;
+        let program = if group[3].owner == &token_program.key() {
+            token_program.to_account_info()
+        } else {
+            require_keys_eq!(
+                *group[3].owner,
+                reward_token_program.key(),
+                KernelError::InvalidRewardAccounts
+            );
+            reward_token_program.to_account_info()
+        };
+
+        let reward_pool_key = reward_pool.key();
+        let seeds = &[
+            b"reward_vault",
+            reward_pool_key.as_ref(),
+            &[reward_pool.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                program,
+                TransferChecked {
+                    from: group[2].clone(),
+                    mint: group[3].clone(),
+                    to: group[4].clone(),
+                    authority: group[2].clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            reward_mint.decimals,
+        )?;
+
+        // Persist before the next group so a repeated pool settles to zero
+        reward_pool.exit(&crate::ID)?;
+        state.exit(&crate::ID)?;
+
+        msg!("Claimed {} reward tokens", amount);
+        claims.push((reward_pool.reward_mint, amount));
+    }
+
+    Ok(claims)
+}
+
+/// Count a withdrawal against the LP vault's limit for the current period
+/// A period starts with the first withdrawal after the previous one ended.
+fn record_lp_withdrawal(lp_vault: &mut LPVault, amount: u64, current_time: i64) -> Result<()> {
+    if current_time >= lp_vault.period_start.safe_add(LP_WITHDRAW_PERIOD)? {
+        lp_vault.period_start = current_time;
+        lp_vault.withdrawn_in_period = 0;
+    }
+
+    let withdrawn = lp_vault.withdrawn_in_period.safe_add(amount)?;
+    require!(
+        lp_vault.withdraw_limit == 0 || withdrawn <= lp_vault.withdraw_limit,
+        KernelError::LpWithdrawLimitExceeded
+    );
+    lp_vault.withdrawn_in_period = withdrawn;
+    Ok(())
+}
+
+/// Split harvested fees into (reflections, LP, burn) by config shares
+/// Rounding dust goes to the burn share so the split always sums to `amount`
+fn split_fees(amount: u64, reflection_bps: u16, lp_bps: u16, burn_bps: u16) -> (u64, u64, u64) {
+    let total_bps = reflection_bps as u128 + lp_bps as u128 + burn_bps as u128;
+    if total_bps == 0 {
+        return (0, 0, amount);
+    }
+
+    let reflection = ((amount as u128) * (reflection_bps as u128) / total_bps) as u64;
+    let lp = ((amount as u128) * (lp_bps as u128) / total_bps) as u64;
+    (reflection, lp, amount - reflection - lp)
+}
+
+/// Split a crank compound into (cranker tip, amount staked)
+fn split_crank_tip(amount: u64, tip_bps: u16) -> (u64, u64) {
+    let tip = ((amount as u128) * (tip_bps.min(MAX_CRANK_TIP_BPS) as u128) / 10_000) as u64;
+    (tip, amount - tip)
+}
+
+fn unbonding_complete(ticket: &UnbondingTicket, current_time: i64) -> bool {
+    current_time >= ticket.release_time
+}
+
+/// Tiers must be non-empty, bounded, strictly increasing in duration and
+/// never boost below 1x or above the multiplier cap
+fn validate_lock_tiers(tiers: &[LockTier]) -> bool {
+    !tiers.is_empty()
+        && tiers.len() <= MAX_LOCK_TIERS
+        && tiers[0].lock_duration > 0
+        && tiers
+            .windows(2)
+            .all(|w| w[0].lock_duration < w[1].lock_duration)
+        && tiers.iter().all(|t| {
+            t.multiplier_bps >= BASE_MULTIPLIER_BPS && t.multiplier_bps <= MAX_MULTIPLIER_BPS
+        })
+}
+
+/// Guardians must be non-empty, bounded and unique, with 1 <= M <= N
+fn validate_guardian_set(guardians: &[Pubkey], threshold: u8) -> bool {
+    !guardians.is_empty()
+        && guardians.len() <= MAX_GUARDIANS
+        && threshold >= 1
+        && threshold as usize <= guardians.len()
+        && guardians
+            .iter()
+            .enumerate()
+            .all(|(i, g)| !guardians[..i].contains(g))
+}
+
+/// Reject governance payloads that could never be applied
+fn validate_governance_payload(payload: &GovernancePayload) -> Result<()> {
+    match payload {
+        GovernancePayload::UpdateFees {
+            reflection_share_bps,
+            lp_share_bps,
+            burn_share_bps,
+        } => require!(
+            valid_fee_split(*reflection_share_bps, *lp_share_bps, *burn_share_bps),
+            KernelError::InvalidFeeConfig
+        ),
+        GovernancePayload::UpdateTiers { tiers } => {
+            require!(validate_lock_tiers(tiers), KernelError::InvalidTierConfig)
+        }
+        GovernancePayload::UpdateGuardianSet {
+            guardians,
+            threshold,
+        } => require!(
+            validate_guardian_set(guardians, *threshold),
+            KernelError::InvalidGuardianSet
+        ),
+        GovernancePayload::SetActionDelay { delay, .. } => {
+            require!(valid_action_delay(*delay), KernelError::InvalidActionDelay)
+        }
+        GovernancePayload::GrantRole { holder, .. } => {
+            require!(*holder != Pubkey::default(), KernelError::InvalidRoleHolder)
+        }
+        GovernancePayload::SetPauseFlags {
+            flags,
+            paused_until,
+        } => require!(
+            *flags & !PAUSE_ALL == 0 && *paused_until >= 0,
+            KernelError::InvalidPause
+        ),
+        GovernancePayload::SetUnbondingPeriod { unbonding_period } => require!(
+            (0..=MAX_UNBONDING_PERIOD).contains(unbonding_period),
+            KernelError::InvalidUnbondingPeriod
+        ),
+        GovernancePayload::SetRewardStreamDuration {
+            reward_stream_duration,
+        } => require!(
+            (0..=MAX_REWARD_STREAM_DURATION).contains(reward_stream_duration),
+            KernelError::InvalidStreamDuration
+        ),
+        GovernancePayload::TransferAuthority { .. }
+        | GovernancePayload::SetPaused { .. }
+        | GovernancePayload::SetLpWithdrawLimit { .. }
+        | GovernancePayload::RevokeRole { .. } => {}
+    }
+    Ok(())
+}
+
+/// Fee shares must add up to the 5% fee, summed wide so u16 wrapping cannot fake it
+fn valid_fee_split(reflection_share_bps: u16, lp_share_bps: u16, burn_share_bps: u16) -> bool {
+    reflection_share_bps as u32 + lp_share_bps as u32 + burn_share_bps as u32 == 500
+}
+
+/// Delays must stay between MIN_ACTION_DELAY and MAX_ACTION_DELAY
+fn valid_action_delay(delay: i64) -> bool {
+    (MIN_ACTION_DELAY..=MAX_ACTION_DELAY).contains(&delay)
+}
+
+/// Which configured delay an action waits out
+/// A delay change waits out the current delay of the type it changes
+fn action_kind(payload: &GovernancePayload) -> ActionKind {
+    match payload {
+        GovernancePayload::UpdateFees { .. } => ActionKind::Fees,
+        GovernancePayload::TransferAuthority { .. } => ActionKind::Authority,
+        GovernancePayload::SetPaused { .. } | GovernancePayload::SetPauseFlags { .. } => {
+            ActionKind::Pause
+        }
+        GovernancePayload::UpdateTiers { .. } => ActionKind::Tiers,
+        GovernancePayload::UpdateGuardianSet { .. } => ActionKind::Guardians,
+        GovernancePayload::SetLpWithdrawLimit { .. } => ActionKind::LpWithdrawLimit,
+        GovernancePayload::GrantRole { .. } | GovernancePayload::RevokeRole { .. } => {
+            ActionKind::Roles
+        }
+        GovernancePayload::SetActionDelay { kind, .. } => *kind,
+        GovernancePayload::SetUnbondingPeriod { .. } => ActionKind::Unbonding,
+        GovernancePayload::SetRewardStreamDuration { .. } => ActionKind::RewardStream,
+    }
+}
+
+/// Timelock for a payload under the config's current delays
+/// Configs from before delays were stored read zero and keep the 24h default
+fn action_delay(config: &KernelConfig, payload: &GovernancePayload) -> i64 {
+    match config.action_delays[action_kind(payload) as usize] {
+        0 => DEFAULT_ACTION_DELAY,
+        delay => delay,
+    }
+}
+
+/// Pause flag guarding a scope
+fn pause_flag(scope: PauseScope) -> u8 {
+    match scope {
+        PauseScope::Stake => PAUSE_STAKE,
+        PauseScope::RewardDeposits => PAUSE_REWARD_DEPOSITS,
+        PauseScope::AirdropClaims => PAUSE_AIRDROP_CLAIMS,
+        PauseScope::LpAllocation => PAUSE_LP_ALLOCATION,
+        PauseScope::Burns => PAUSE_BURNS,
+    }
+}
+
+/// Mask the old pause bool stands for; it only ever gated staking
+fn legacy_pause_flags(paused: bool) -> u8 {
+    if paused {
+        PAUSE_STAKE
+    } else {
+        0
+    }
+}
+
+/// Whether a scope is paused at `current_time`
+/// A pause with paused_until set lifts itself once that time is reached
+fn is_paused(config: &KernelConfig, scope: PauseScope, current_time: i64) -> bool {
+    config.pause_flags & pause_flag(scope) != 0
+        && (config.paused_until == 0 || current_time < config.paused_until)
+}
+
+/// Pause masks may only use known flags and expire in the future (0 = never)
+fn valid_pause(flags: u8, paused_until: i64, current_time: i64) -> bool {
+    flags & !PAUSE_ALL == 0 && (paused_until == 0 || paused_until > current_time)
+}
+
+/// Replace the pause mask and its expiry
+fn set_pause(config: &mut KernelConfig, flags: u8, paused_until: i64) {
+    config.pause_flags = flags;
+    config.paused_until = paused_until;
+}
+
+/// Hand a role to a new key; Pubkey::default() leaves it unheld
+fn set_role(roles: &mut Roles, role: Role, holder: Pubkey) {
+    let slot = match role {
+        Role::Admin => &mut roles.admin,
+        Role::Pauser => &mut roles.pauser,
+        Role::ReflectionDepositor => &mut roles.reflection_depositor,
+        Role::LpOperator => &mut roles.lp_operator,
+        Role::Burner => &mut roles.burner,
+        Role::AirdropManager => &mut roles.airdrop_manager,
+    };
+    *slot = holder;
+}
+
+/// Hand every role `from` holds to `to`; returns whether anything changed
+fn rotate_roles(roles: &mut Roles, from: Pubkey, to: Pubkey) -> bool {
+    let mut rotated = false;
+    for slot in [
+        &mut roles.admin,
+        &mut roles.pauser,
+        &mut roles.reflection_depositor,
+        &mut roles.lp_operator,
+        &mut roles.burner,
+        &mut roles.airdrop_manager,
+    ] {
+        if *slot == from {
+            *slot = to;
+            rotated = true;
+        }
+    }
+    rotated
+}
+
+/// Snapshot of every role holder for the RolesUpdated event
+fn roles_updated(roles: &Roles) -> RolesUpdated {
+    RolesUpdated {
+        admin: roles.admin,
+        pauser: roles.pauser,
+        reflection_depositor: roles.reflection_depositor,
+        lp_operator: roles.lp_operator,
+        burner: roles.burner,
+        airdrop_manager: roles.airdrop_manager,
+    }
+}
+
+/// An action executes once, between its eta and expiry, unless cancelled
+fn check_action_executable(action: &GovernanceAction, current_time: i64) -> Result<()> {
+    require!(!action.executed, KernelError::ProposalAlreadyExecuted);
+    require!(!action.cancelled, KernelError::ProposalCancelled);
+    require!(current_time >= action.eta, KernelError::TimelockNotExpired);
+    require!(
+        current_time <= action.expires_at,
+        KernelError::ActionExpired
+    );
+    Ok(())
+}
+
+/// Only the proposed authority can accept a transfer, inside its window
+fn check_authority_acceptance(
+    action: &GovernanceAction,
+    signer: &Pubkey,
+    current_time: i64,
+) -> Result<()> {
+    let pending = GovernancePayload::TransferAuthority {
+        new_authority: *signer,
+    };
+    require!(action.payload == pending, KernelError::NotPendingAuthority);
+    check_action_executable(action, current_time)
+}
+
+/// Executed, cancelled and expired actions can never run again
+fn action_settled(action: &GovernanceAction, current_time: i64) -> bool {
+    action.executed || action.cancelled || current_time > action.expires_at
+}
+
+/// Payloads that stakers vote on before they can execute
+fn is_voted_payload(payload: &GovernancePayload) -> bool {
+    matches!(payload, GovernancePayload::UpdateFees { .. })
+}
+
+/// Add a stake position's vote to an open action and return its weight
+/// The weight is the lower of the stake held when the action was proposed
+/// and the stake held now: stake added later never counts, and adding stake
+/// never takes weight away.
+fn record_vote(
+    action: &mut GovernanceAction,
+    user_stake: &UserStake,
+    support: bool,
+    current_time: i64,
+) -> Result<u64> {
+    require!(
+        is_voted_payload(&action.payload) && !action.executed && !action.cancelled,
+        KernelError::VotingNotOpen
+    );
+    require!(current_time < action.eta, KernelError::VotingClosed);
+
+    let weight = vote_weight(user_stake, action.proposed_at);
+    require!(weight > 0, KernelError::NoVotingPower);
+
+    if support {
+        action.votes_for = action.votes_for.safe_add(weight)?;
+    } else {
+        action.votes_against = action.votes_against.safe_add(weight)?;
+    }
+    Ok(weight)
+}
+
+/// Stake a position can vote with on an action proposed at `proposed_at`
+/// A stake changed since then votes with its checkpoint, which never exceeds
+/// what it held at proposal time
+fn vote_weight(user_stake: &UserStake, proposed_at: i64) -> u64 {
+    if user_stake.stake_updated_at < proposed_at {
+        user_stake.staked_amount
+    } else {
+        user_stake
+            .vote_checkpoint_amount
+            .min(user_stake.staked_amount)
+    }
+}
+
+/// Record the stake held before a change, for votes opened since the last one
+/// Only the first change after a vote opens takes a checkpoint. While votes
+/// from an earlier checkpoint are still open the lower of the two is kept,
+/// so the checkpoint stays a floor for every open vote.
+fn checkpoint_vote_weight(config: &KernelConfig, user_stake: &mut UserStake, current_time: i64) {
+    if user_stake.vote_checkpoint_at < config.last_vote_opened_at {
+        user_stake.vote_checkpoint_amount = if current_time < user_stake.vote_checkpoint_expires {
+            user_stake
+                .vote_checkpoint_amount
+                .min(user_stake.staked_amount)
+        } else {
+            user_stake.staked_amount
+        };
+        user_stake.vote_checkpoint_at = current_time;
+        user_stake.vote_checkpoint_expires = config.vote_closes_at;
+    }
+    user_stake.stake_updated_at = current_time;
+}
+
+/// Quorum of the staked supply at proposal time, then a strict majority
+fn check_vote_passed(action: &GovernanceAction) -> Result<()> {
+    let turnout = action.votes_for as u128 + action.votes_against as u128;
+    let quorum = action.total_staked_snapshot as u128 * QUORUM_BPS as u128 / 10_000;
+    require!(
+        turnout > 0 && turnout >= quorum,
+        KernelError::QuorumNotReached
+    );
+    require!(
+        action.votes_for > action.votes_against,
+        KernelError::VoteNotPassed
+    );
+    Ok(())
+}
+
+/// Set the approval bit for a guardian on a proposal from the current epoch
+/// An emergency action takes approvals and executes only until it expires
+fn check_guardian_action_open(proposal: &GuardianAction, current_time: i64) -> Result<()> {
+    require!(!proposal.executed, KernelError::ProposalAlreadyExecuted);
+    require!(
+        current_time <= proposal.expires_at,
+        KernelError::ActionExpired
+    );
+    Ok(())
+}
+
+fn record_guardian_approval(
+    guardian_set: &GuardianSet,
+    proposal: &mut GuardianAction,
+    guardian: &Pubkey,
+) -> Result<()> {
+    require!(
+        proposal.epoch == guardian_set.epoch,
+        KernelError::StaleGuardianProposal
+    );
+    let index = guardian_set
+        .guardians
+        .iter()
+        .position(|g| g == guardian)
+        .ok_or(KernelError::NotGuardian)?;
+    let bit = 1u16 << index;
+    require!(proposal.approvals & bit == 0, KernelError::AlreadyApproved);
+    proposal.approvals |= bit;
+    Ok(())
+}
+
+fn calculate_pending_rewards(
+    shares: u64,
+    accumulated_per_share: u128,
+    reward_debt: u128,
+) -> Result<u64> {
+    if shares == 0 {
+        return Ok(0);
+    }
+
+    let accumulated = (shares as u128)
+        .safe_mul(accumulated_per_share)?
+        .safe_div(PRECISION)?;
+
+    to_u64(accumulated.saturating_sub(reward_debt))
+}
+
+fn calculate_reward_debt(shares: u64, accumulated_per_share: u128) -> Result<u128> {
+    (shares as u128)
+        .safe_mul(accumulated_per_share)?
+        .safe_div(PRECISION)
+}
+
+/// Merkle leaf for an airdrop allocation (domain-separated from inner nodes)
+fn airdrop_leaf(claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[&[0u8], claimant.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// A campaign accepts claims only while Active and inside its window
+fn campaign_accepts_claims(campaign: &Campaign, current_time: i64) -> bool {
+    campaign.status == CampaignStatus::Active
+        && current_time >= campaign.start_time
+        && current_time < campaign.end_time
+}
+
+/// Hash two Merkle nodes in sorted order (domain-separated from leaves)
+fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1u8], lo, hi]).to_bytes()
+}
+
+/// Verify a Merkle proof against the committed root
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_node(&node, sibling));
+    computed == root
+}
+
+/// Upgrade a version 0 config
+/// Version 0 had no lock tiers, so every share is one staked token
+fn migrate_config_layout(legacy: &LegacyKernelConfig) -> KernelConfig {
+    KernelConfig {
+        version: ACCOUNT_VERSION,
+        authority: legacy.authority,
+        token_mint: legacy.token_mint,
+        staking_vault: legacy.staking_vault,
+        reflection_pool: legacy.reflection_pool,
+        reflection_share_bps: legacy.reflection_share_bps,
+        lp_share_bps: legacy.lp_share_bps,
+        burn_share_bps: legacy.burn_share_bps,
+        total_staked: legacy.total_staked,
+        total_shares: legacy.total_staked,
+        total_reflections_distributed: legacy.total_reflections_distributed,
+        pending_reflections: legacy.pending_reflections,
+        accumulated_per_share: legacy.accumulated_per_share,
+        pause_flags: legacy_pause_flags(legacy.is_paused),
+        paused_until: 0,
+        unbonding_period: 0,
+        total_unbonding: 0,
+        bump: legacy.bump,
+        vault_bump: legacy.vault_bump,
+        reward_pool_count: 0,
+        reward_stream_duration: 0,
+        reward_rate: 0,
+        reward_period_end: 0,
+        last_update_time: 0,
+        governance_action_count: 0,
+        action_delays: [DEFAULT_ACTION_DELAY; ACTION_KIND_COUNT],
+        reflection_surplus: 0,
+        reflection_deficit: 0,
+        reflection_owed: 0,
+        undistributed_reflections: 0,
+        reflection_remainder: 0,
+        position_count: 0,
+        vesting_count: 0,
+        last_vote_opened_at: 0,
+        vote_closes_at: 0,
+        reserved: [0; 55],
+    }
+}
+
+/// Upgrade a version 0 stake position as an unlocked, base-multiplier stake
+/// reward_debt carries over unchanged because shares == staked_amount
+fn migrate_user_stake_layout(legacy: &LegacyUserStake) -> UserStake {
+    UserStake {
+        version: ACCOUNT_VERSION,
+        owner: legacy.owner,
+        staked_amount: legacy.staked_amount,
+        stake_time: legacy.stake_time,
+        pending_rewards: legacy.pending_rewards,
+        total_claimed: legacy.total_claimed,
+        reward_debt: legacy.reward_debt,
+        shares: legacy.staked_amount,
+        lock_multiplier_bps: BASE_MULTIPLIER_BPS,
+        lock_until: 0,
+        unbonding_nonce: 0,
+        unbonding_amount: 0,
+        auto_compound: false,
+        compound_tip_bps: 0,
+        bump: legacy.bump,
+        stake_updated_at: legacy.stake_time,
+        delegate: Pubkey::default(),
+        reflection_owed: 0,
+        reward_fraction: 0,
+        vote_checkpoint_at: 0,
+        vote_checkpoint_amount: 0,
+        vote_checkpoint_expires: 0,
+        reserved: [0; 48],
+    }
+}
+
+/// Upgrade a version 0 burn record
+fn migrate_burn_record_layout(legacy: &LegacyBurnRecord) -> BurnRecord {
+    BurnRecord {
+        version: ACCOUNT_VERSION,
+        total_burned: legacy.total_burned,
+        burn_count: legacy.burn_count,
+        last_burn_time: legacy.last_burn_time,
+        bump: legacy.bump,
+        reserved: [0; 64],
+    }
+}
+
+/// Upgrade a version 0 LP vault
+fn migrate_lp_vault_layout(legacy: &LegacyLPVault) -> LPVault {
+    LPVault {
+        version: ACCOUNT_VERSION,
+        authority: legacy.authority,
+        token_mint: legacy.token_mint,
+        total_allocated: legacy.total_allocated,
+        total_deployed: legacy.total_deployed,
+        pending_deployment: legacy.pending_deployment,
+        last_deployment_time: legacy.last_deployment_time,
+        bump: legacy.bump,
+        vault_token_bump: legacy.vault_token_bump,
+        withdraw_limit: 0,
+        withdrawn_in_period: 0,
+        period_start: 0,
+        reserved: [0; 40],
+    }
+}
+
+/// True if the account is program-owned and still has its version 0 size
+fn is_legacy_layout(info: &AccountInfo, discriminator: &[u8], legacy_len: usize) -> bool {
+    info.owner == &crate::ID
+        && info.data_len() == legacy_len
+        && info
+            .try_borrow_data()
+            .is_ok_and(|data| data.starts_with(discriminator))
+}
+
+/// Deserialize a version 0 account, rejecting ones already upgraded
+fn read_legacy_account<T: AnchorDeserialize + Space>(
+    info: &AccountInfo,
+    discriminator: &[u8],
+    current_len: usize,
+) -> Result<T> {
+    require!(info.data_len() != current_len, KernelError::AlreadyMigrated);
+    require!(
+        is_legacy_layout(info, discriminator, 8 + T::INIT_SPACE),
+        KernelError::UnknownAccountLayout
+    );
+
+    let data = info.try_borrow_data()?;
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+/// Realloc an account to its upgraded layout and write it
+/// The payer tops the account up to the new rent-exempt minimum
+fn write_upgraded_account<'info, T: AccountSerialize + Space>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    upgraded: &T,
+) -> Result<()> {
+    let new_len = 8 + T::INIT_SPACE;
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(info.lamports());
+    if rent_due > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    info.resize(new_len)?;
+    let mut data = info.try_borrow_mut_data()?;
+    upgraded.try_serialize(&mut &mut data[..])
+}
+
+// === ACCOUNTS ===
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Staking vault - PDA that holds staked tokens
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_vault,
+        token::token_program = token_program,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool - PDA that holds pending reflection rewards
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = reflection_pool,
+        token::token_program = token_program,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + KernelConfig::INIT_SPACE,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Lock-up tier table (only needed when staking with a lock tier)
+    #[account(
+        seeds = [b"staking_tiers", config.key().as_ref()],
+        bump = staking_tiers.bump
+    )]
+    pub staking_tiers: Option<Account<'info, StakingTiers>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Stake from a funder's wallet into a beneficiary's position
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct StakeFor<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// Beneficiary, signing for a delegated or locked stake
+    pub beneficiary_signer: Option<Signer<'info>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Funder's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = funder,
+        associated_token::token_program = token_program,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Beneficiary's position, opened by the funder if needed
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), beneficiary.as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Lock-up tier table (only needed when staking with a lock tier)
+    #[account(
+        seeds = [b"staking_tiers", config.key().as_ref()],
+        bump = staking_tiers.bump
+    )]
+    pub staking_tiers: Option<Account<'info, StakingTiers>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's position, or one delegated to the signer by `stake_for`
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+        constraint = withdraw_authority(&user_stake) == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's position, or one delegated to the signer by `stake_for`
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+        constraint = withdraw_authority(&user_stake) == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Ticket the principal unbonds through; required while a cooldown is set
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UnbondingTicket::INIT_SPACE,
+        seeds = [
+            b"unbonding",
+            user_stake.key().as_ref(),
+            user_stake.unbonding_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub unbonding_ticket: Option<Account<'info, UnbondingTicket>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Owner's position, or one delegated to the signer by `stake_for`
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+        constraint = withdraw_authority(&user_stake) == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// One ticket per request, so a user can hold several at once
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UnbondingTicket::INIT_SPACE,
+        seeds = [
+            b"unbonding",
+            user_stake.key().as_ref(),
+            user_stake.unbonding_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawUnbonded<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The ticket's position; a delegate withdraws tickets it requested
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Ticket is closed and its rent returned to the owner
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"unbonding",
+            user_stake.key().as_ref(),
+            unbonding_ticket.nonce.to_le_bytes().as_ref()
+        ],
+        bump = unbonding_ticket.bump,
+        constraint = unbonding_ticket.owner == owner.key() @ KernelError::NotOwner
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReflections<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompoundReflections<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    pub owner: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+}
+
+/// Anyone can crank a staker who opted into auto-compounding
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankCompound<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Cranker's token account (receives the tip)
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program,
+    )]
+    pub cranker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Anyone can rebase a position whose lock has expired
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefreshBoost<'info> {
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user_stake.owner.as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositReflections<'info> {
+    /// Holder of the reflection depositor role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.reflection_depositor == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// Authority's token account (source of reflection funds)
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Reconcile the reflection pool (anyone)
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncReflectionPool<'info> {
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Reflection pool
+    #[account(
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Register an extra reward mint (admin only)
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddRewardPool<'info> {
+    /// Holder of the admin role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.admin == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mint::token_program = reward_token_program
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", config.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// Reward vault - PDA that holds the pool's undistributed rewards
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund a reward pool (anyone)
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
+    pub funder: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mint::token_program = reward_token_program
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", config.key().as_ref(), reward_mint.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.vault_bump,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = funder,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Open a staker's reward state for one pool
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitUserRewardState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any staker key; only used to derive its stake PDA
+    pub owner: UncheckedAccount<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// CHECK: Owner's stake PDA; may not exist yet for a first-time staker
+    #[account(
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub user_stake: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = reward_pool.config == config.key() @ KernelError::InvalidRewardAccounts
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + UserRewardState::INIT_SPACE,
+        seeds = [b"user_reward", reward_pool.key().as_ref(), user_stake.key().as_ref()],
+        bump
+    )]
+    pub user_reward_state: Account<'info, UserRewardState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim reflections plus every reward pool passed as remaining accounts
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAllRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program for reward mints not on `token_program`
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Open a stake position under the next position id
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// User's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &config.position_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// The position's stake, owned by the position PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Lock-up tier table (only needed when staking with a lock tier)
+    #[account(
+        seeds = [b"staking_tiers", config.key().as_ref()],
+        bump = staking_tiers.bump
+    )]
+    pub staking_tiers: Option<Account<'info, StakingTiers>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint a position's Token-2022 receipt NFT to its owner
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MintPositionReceipt<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position.owner == owner.key() @ KernelError::NotOwner,
+        constraint = position.receipt_mint == Pubkey::default() @ KernelError::ReceiptAlreadyMinted
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Receipt mint - PDA with 0 decimals, mint authority revoked after minting
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"position_receipt", position.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = position,
+        mint::token_program = receipt_token_program,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner's receipt account
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = receipt_token_program,
+    )]
+    pub receipt_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub receipt_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a position without a receipt to a new owner
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    pub owner: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position.owner == owner.key() @ KernelError::NotOwner,
+        constraint = position.receipt_mint == Pubkey::default() @ KernelError::ReceiptAlreadyMinted
+    )]
+    pub position: Account<'info, StakePosition>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnstakePosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Holder's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Position controlled by the signer
+    #[account(
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position_holder(&position, receipt_account.as_ref().map(|r| r.owner))
+            == Some(holder.key()) @ KernelError::NotOwner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Holder's receipt account (only for positions with a receipt)
+    #[account(
+        constraint = receipt_account.mint == position.receipt_mint @ KernelError::InvalidReceipt,
+        constraint = receipt_account.amount == 1 @ KernelError::InvalidReceipt
+    )]
+    pub receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position's stake
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.delegate == Pubkey::default() @ KernelError::DelegationMismatch
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Withdraw a whole position without settling rewards
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EmergencyUnstakePosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Holder's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Position controlled by the signer
+    #[account(
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position_holder(&position, receipt_account.as_ref().map(|r| r.owner))
+            == Some(holder.key()) @ KernelError::NotOwner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Holder's receipt account (only for positions with a receipt)
+    #[account(
+        constraint = receipt_account.mint == position.receipt_mint @ KernelError::InvalidReceipt,
+        constraint = receipt_account.amount == 1 @ KernelError::InvalidReceipt
+    )]
+    pub receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position's stake
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.delegate == Pubkey::default() @ KernelError::DelegationMismatch
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Ticket the principal unbonds through; required while a cooldown is set
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + UnbondingTicket::INIT_SPACE,
+        seeds = [
+            b"unbonding",
+            user_stake.key().as_ref(),
+            user_stake.unbonding_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub unbonding_ticket: Option<Account<'info, UnbondingTicket>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestPositionUnstake<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Position controlled by the signer
+    #[account(
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position_holder(&position, receipt_account.as_ref().map(|r| r.owner))
+            == Some(holder.key()) @ KernelError::NotOwner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Holder's receipt account (only for positions with a receipt)
+    #[account(
+        constraint = receipt_account.mint == position.receipt_mint @ KernelError::InvalidReceipt,
+        constraint = receipt_account.amount == 1 @ KernelError::InvalidReceipt
+    )]
+    pub receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position's stake
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.delegate == Pubkey::default() @ KernelError::DelegationMismatch
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// One ticket per request, owned by the requesting holder
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + UnbondingTicket::INIT_SPACE,
+        seeds = [
+            b"unbonding",
+            user_stake.key().as_ref(),
+            user_stake.unbonding_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Open a reward state for a position, which may not exist yet
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct InitPositionRewardState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// CHECK: Position PDA for `position_id`; may not be opened yet
+    #[account(
+        seeds = [b"position", config.key().as_ref(), &position_id.to_le_bytes()],
+        bump
+    )]
+    pub position: UncheckedAccount<'info>,
+
+    /// CHECK: The position's stake PDA; may not exist yet
+    #[account(
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump
+    )]
+    pub user_stake: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = reward_pool.config == config.key() @ KernelError::InvalidRewardAccounts
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + UserRewardState::INIT_SPACE,
+        seeds = [b"user_reward", reward_pool.key().as_ref(), user_stake.key().as_ref()],
+        bump
+    )]
+    pub user_reward_state: Account<'info, UserRewardState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim a position's reflections plus every reward pool passed as remaining accounts
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimPositionRewards<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Holder's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    /// Position controlled by the signer
+    #[account(
+        seeds = [
+            b"position",
+            config.key().as_ref(),
+            &position.position_id.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position_holder(&position, receipt_account.as_ref().map(|r| r.owner))
+            == Some(holder.key()) @ KernelError::NotOwner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Holder's receipt account (only for positions with a receipt)
+    #[account(
+        constraint = receipt_account.mint == position.receipt_mint @ KernelError::InvalidReceipt,
+        constraint = receipt_account.amount == 1 @ KernelError::InvalidReceipt
+    )]
+    pub receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position's stake
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), position.key().as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program for reward mints not on `token_program`
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Lock a grant in a new vesting schedule and vault
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Grantor's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = grantor,
+        associated_token::token_program = token_program,
+    )]
+    pub grantor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = grantor,
+        space = 8 + VestingSchedule::INIT_SPACE,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &config.vesting_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    /// Vesting vault - PDA that holds the schedule's unreleased tokens
+    #[account(
+        init,
+        payer = grantor,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vesting_vault,
+        token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.vault_bump,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Beneficiary's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Stake a schedule's locked tokens on the schedule's own stake
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.vault_bump,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The schedule's stake, delegated to the schedule
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), vesting_schedule.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Unstake a schedule's tokens back into its vesting vault
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnstakeVesting<'info> {
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.vault_bump,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The schedule's stake, delegated to the schedule
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), vesting_schedule.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.delegate == vesting_schedule.key() @ KernelError::DelegationMismatch
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Return a schedule's whole stake to its vault without settling rewards
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EmergencyUnstakeVesting<'info> {
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.vault_bump,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The schedule's stake, delegated to the schedule
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), vesting_schedule.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.delegate == vesting_schedule.key() @ KernelError::DelegationMismatch
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Claim a vesting stake's rewards to the beneficiary
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVestingRewards<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Beneficiary's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    /// The schedule's stake
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), vesting_schedule.key().as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program for reward mints not on `token_program`
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Revoke a schedule, unstaking it first if needed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub grantor: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            config.key().as_ref(),
+            &vesting_schedule.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+        constraint = vesting_schedule.grantor == grantor.key() @ KernelError::NotOwner
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.vault_bump,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Grantor's token account, credited with the unvested tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = grantor,
+        associated_token::token_program = token_program,
+    )]
+    pub grantor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Staking vault
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump = config.vault_bump,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The schedule's stake (only needed while the schedule has tokens staked)
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), vesting_schedule.key().as_ref()],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Option<Account<'info, UserStake>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    /// Holder of the burner role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.burner == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// Authority's token account (source of tokens to burn)
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BurnRecord::INIT_SPACE,
+        seeds = [b"burn", config.key().as_ref()],
+        bump
+    )]
+    pub burn_record: Account<'info, BurnRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-fn calculate_reward_debt(user_staked: u64, accumulated_per_share: u128) -> u128 {
-    (user_staked as u128)
-        .checked_mul(accumulated_per_share)
-        .unwrap()
-        .checked_div(PRECISION)
-        .unwrap()
+/// Create the fee vault
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    /// Holder of the admin role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.admin == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// Fee vault PDA (also the mint's withdraw-withheld authority)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"fee_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = fee_vault,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-// === ACCOUNTS ===
+/// Harvest withheld fees and split them (permissionless)
+#[event_cpi]
+#[derive(Accounts)]
+pub struct HarvestAndDistribute<'info> {
+    #[account(mut)]
+    pub harvester: Signer<'info>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    /// Fee vault
+    #[account(
+        mut,
+        seeds = [b"fee_vault", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Reflection pool
+    #[account(
+        mut,
+        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_vault", token_mint.key().as_ref()],
+        bump = lp_vault.bump
+    )]
+    pub lp_vault: Account<'info, LPVault>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_vault_token", token_mint.key().as_ref()],
+        bump = lp_vault.vault_token_bump,
+    )]
+    pub lp_vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = harvester,
+        space = 8 + BurnRecord::INIT_SPACE,
+        seeds = [b"burn", config.key().as_ref()],
+        bump
+    )]
+    pub burn_record: Account<'info, BurnRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
 
+/// Create an airdrop campaign
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+#[instruction(campaign_id: u64)]
+pub struct CreateCampaign<'info> {
+    /// Holder of the airdrop manager role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -728,47 +6914,189 @@ pub struct Initialize<'info> {
     )]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// Staking vault - PDA that holds staked tokens
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.airdrop_manager == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         init,
         payer = authority,
-        seeds = [b"staking_vault", token_mint.key().as_ref()],
-        bump,
-        token::mint = token_mint,
-        token::authority = staking_vault,
-        token::token_program = token_program,
+        space = 8 + Campaign::INIT_SPACE,
+        seeds = [b"campaign", config.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump
     )]
-    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+    pub campaign: Account<'info, Campaign>,
 
-    /// Reflection pool - PDA that holds pending reflection rewards
+    /// Campaign vault - PDA that holds the campaign's unclaimed tokens
     #[account(
         init,
         payer = authority,
-        seeds = [b"reflection_pool", token_mint.key().as_ref()],
+        seeds = [b"campaign_vault", campaign.key().as_ref()],
         bump,
         token::mint = token_mint,
-        token::authority = reflection_pool,
+        token::authority = campaign_vault,
         token::token_program = token_program,
     )]
-    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund an airdrop campaign
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundCampaign<'info> {
+    /// Holder of the airdrop manager role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        init,
-        payer = authority,
-        space = 8 + KernelConfig::INIT_SPACE,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.airdrop_manager == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", config.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign_vault", campaign.key().as_ref()],
+        bump = campaign.vault_bump,
+    )]
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority's token account (source of campaign funds)
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Claim an airdrop allocation
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        mut,
+        seeds = [b"campaign", config.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign_vault", campaign.key().as_ref()],
+        bump = campaign.vault_bump,
+    )]
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Claimant's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Claim receipt - `init` fails if this wallet already claimed
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + AirdropClaim::INIT_SPACE,
+        seeds = [b"airdrop_claim", campaign.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub claim_receipt: Account<'info, AirdropClaim>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+/// Close an airdrop campaign
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Stake<'info> {
+pub struct CloseCampaign<'info> {
+    /// Holder of the airdrop manager role
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.airdrop_manager == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", config.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+/// Sweep unclaimed tokens from a closed campaign
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepCampaign<'info> {
+    /// Holder of the airdrop manager role
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
     #[account(
         mint::token_program = token_program
@@ -776,50 +7104,144 @@ pub struct Stake<'info> {
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mut,
         seeds = [b"config", token_mint.key().as_ref()],
         bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
-    /// User's token account
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.airdrop_manager == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", config.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign_vault", campaign.key().as_ref()],
+        bump = campaign.vault_bump,
+    )]
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority's token account
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = owner,
+        associated_token::authority = authority,
         associated_token::token_program = token_program,
     )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Queue a governance action (starts the action type's timelock)
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GovernanceAction::INIT_SPACE,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &config.governance_action_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub governance_action: Account<'info, GovernanceAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Execute a governance action inside its execution window
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+    )]
+    pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &governance_action.nonce.to_le_bytes()
+        ],
+        bump = governance_action.bump
+    )]
+    pub governance_action: Account<'info, GovernanceAction>,
+
+    /// Required for UpdateTiers
+    #[account(
+        mut,
+        seeds = [b"staking_tiers", config.key().as_ref()],
+        bump = staking_tiers.bump
+    )]
+    pub staking_tiers: Option<Account<'info, StakingTiers>>,
+
+    /// Required for UpdateGuardianSet
+    #[account(
+        mut,
+        seeds = [b"guardian_set", config.key().as_ref()],
+        bump = guardian_set.bump
+    )]
+    pub guardian_set: Option<Account<'info, GuardianSet>>,
 
-    /// Staking vault
+    /// Required for SetLpWithdrawLimit
     #[account(
         mut,
-        seeds = [b"staking_vault", token_mint.key().as_ref()],
-        bump = config.vault_bump,
+        seeds = [b"lp_vault", token_mint.key().as_ref()],
+        bump = lp_vault.bump
     )]
-    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+    pub lp_vault: Option<Account<'info, LPVault>>,
 
+    /// Required for GrantRole and RevokeRole
     #[account(
-        init_if_needed,
-        payer = owner,
-        space = 8 + UserStake::INIT_SPACE,
-        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump
     )]
-    pub user_stake: Account<'info, UserStake>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-    pub system_program: Program<'info, System>,
+    pub roles: Option<Account<'info, Roles>>,
 }
 
+/// Accept a queued authority transfer as the new authority
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
 
-    #[account(
-        mint::token_program = token_program
-    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -829,126 +7251,161 @@ pub struct Unstake<'info> {
     )]
     pub config: Account<'info, KernelConfig>,
 
-    /// User's token account
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = owner,
-        associated_token::token_program = token_program,
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// Staking vault
     #[account(
         mut,
-        seeds = [b"staking_vault", token_mint.key().as_ref()],
-        bump = config.vault_bump,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &governance_action.nonce.to_le_bytes()
+        ],
+        bump = governance_action.bump
     )]
-    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+    pub governance_action: Account<'info, GovernanceAction>,
 
+    /// CHECK: Role table PDA; may not exist yet if initialize_roles never ran
     #[account(
         mut,
-        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
-        bump = user_stake.bump,
-        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+        seeds = [b"roles", config.key().as_ref()],
+        bump
     )]
-    pub user_stake: Account<'info, UserStake>,
-
-    pub token_program: Interface<'info, TokenInterface>,
+    pub roles: UncheckedAccount<'info>,
 }
 
+/// Cancel a queued governance action
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ClaimReflections<'info> {
+pub struct CancelAction<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    #[account(
-        mint::token_program = token_program
-    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ KernelError::NotAuthority
     )]
     pub config: Account<'info, KernelConfig>,
 
-    /// User's token account
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = owner,
-        associated_token::token_program = token_program,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &governance_action.nonce.to_le_bytes()
+        ],
+        bump = governance_action.bump
     )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub governance_action: Account<'info, GovernanceAction>,
+}
+
+/// Close a settled governance action
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseAction<'info> {
+    /// Anyone can close a settled action
+    pub closer: Signer<'info>,
+
+    /// CHECK: Rent recipient, must match governance_action.proposer
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// Reflection pool
     #[account(
-        mut,
-        seeds = [b"reflection_pool", token_mint.key().as_ref()],
-        bump,
+        seeds = [b"config", token_mint.key().as_ref()],
+        bump = config.bump
     )]
-    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+    pub config: Account<'info, KernelConfig>,
 
     #[account(
         mut,
-        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
-        bump = user_stake.bump,
-        constraint = user_stake.owner == owner.key() @ KernelError::NotOwner
+        close = proposer,
+        has_one = proposer @ KernelError::NotProposer,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &governance_action.nonce.to_le_bytes()
+        ],
+        bump = governance_action.bump
     )]
-    pub user_stake: Account<'info, UserStake>,
-
-    pub token_program: Interface<'info, TokenInterface>,
+    pub governance_action: Account<'info, GovernanceAction>,
 }
 
+/// Vote on a queued fee change with a stake position
+#[event_cpi]
 #[derive(Accounts)]
-pub struct DepositReflections<'info> {
+pub struct CastVote<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub voter: Signer<'info>,
 
-    #[account(
-        mint::token_program = token_program
-    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
-    /// Authority's token account (source of reflection funds)
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = authority,
-        associated_token::token_program = token_program,
+        seeds = [
+            b"governance_action",
+            config.key().as_ref(),
+            &governance_action.nonce.to_le_bytes()
+        ],
+        bump = governance_action.bump
     )]
-    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub governance_action: Account<'info, GovernanceAction>,
 
-    /// Reflection pool
     #[account(
-        mut,
-        seeds = [b"reflection_pool", token_mint.key().as_ref()],
-        bump,
+        seeds = [b"stake", config.key().as_ref(), voter.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == voter.key() @ KernelError::NotOwner
     )]
-    pub reflection_pool: InterfaceAccount<'info, TokenAccount>,
+    pub user_stake: Account<'info, UserStake>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteReceipt::INIT_SPACE,
+        seeds = [b"vote", governance_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+
+    pub system_program: Program<'info, System>,
 }
 
+/// Close a vote receipt after voting ends
+#[event_cpi]
 #[derive(Accounts)]
-pub struct BurnTokens<'info> {
+pub struct CloseVoteReceipt<'info> {
+    /// Anyone can close a receipt once voting is over
+    pub closer: Signer<'info>,
+
+    /// CHECK: Rent recipient, must match vote_receipt.voter
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub voter: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        mint::token_program = token_program
+        close = voter,
+        has_one = voter @ KernelError::NotOwner,
+        seeds = [b"vote", vote_receipt.governance_action.as_ref(), voter.key().as_ref()],
+        bump = vote_receipt.bump
     )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+}
+
+/// Initialize the lock-up tier table
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeStakingTiers<'info> {
+    /// Holder of the admin role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -957,30 +7414,29 @@ pub struct BurnTokens<'info> {
     )]
     pub config: Account<'info, KernelConfig>,
 
-    /// Authority's token account (source of tokens to burn)
     #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = authority,
-        associated_token::token_program = token_program,
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.admin == authority.key() @ KernelError::MissingRole
     )]
-    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub roles: Account<'info, Roles>,
 
     #[account(
-        init_if_needed,
+        init,
         payer = authority,
-        space = 8 + BurnRecord::INIT_SPACE,
-        seeds = [b"burn", config.key().as_ref()],
+        space = 8 + StakingTiers::INIT_SPACE,
+        seeds = [b"staking_tiers", config.key().as_ref()],
         bump
     )]
-    pub burn_record: Account<'info, BurnRecord>,
+    pub staking_tiers: Account<'info, StakingTiers>,
 
-    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+/// Create the role table (authority only)
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Airdrop<'info> {
+pub struct InitializeRoles<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -994,20 +7450,21 @@ pub struct Airdrop<'info> {
     pub config: Account<'info, KernelConfig>,
 
     #[account(
-        init_if_needed,
+        init,
         payer = authority,
-        space = 8 + AirdropState::INIT_SPACE,
-        seeds = [b"airdrop", config.key().as_ref()],
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [b"roles", config.key().as_ref()],
         bump
     )]
-    pub airdrop_state: Account<'info, AirdropState>,
+    pub roles: Account<'info, Roles>,
 
     pub system_program: Program<'info, System>,
 }
 
-/// Propose a fee update (starts 24-hour timelock)
+/// Register the initial guardian set
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ProposeFeeUpdate<'info> {
+pub struct InitializeGuardianSet<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1023,86 +7480,123 @@ pub struct ProposeFeeUpdate<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + FeeProposal::INIT_SPACE,
-        seeds = [b"fee_proposal", config.key().as_ref()],
+        space = 8 + GuardianSet::INIT_SPACE,
+        seeds = [b"guardian_set", config.key().as_ref()],
         bump
     )]
-    pub fee_proposal: Account<'info, FeeProposal>,
+    pub guardian_set: Account<'info, GuardianSet>,
 
     pub system_program: Program<'info, System>,
 }
 
-/// Execute a fee update after timelock expires
+/// Propose an emergency action (any registered guardian)
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ExecuteFeeUpdate<'info> {
+pub struct ProposeGuardianAction<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub guardian: Signer<'info>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
     #[account(
         mut,
-        seeds = [b"fee_proposal", config.key().as_ref()],
-        bump = fee_proposal.bump,
-        constraint = fee_proposal.proposer == authority.key() @ KernelError::NotAuthority
+        seeds = [b"guardian_set", config.key().as_ref()],
+        bump = guardian_set.bump
     )]
-    pub fee_proposal: Account<'info, FeeProposal>,
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        init,
+        payer = guardian,
+        space = 8 + GuardianAction::INIT_SPACE,
+        seeds = [
+            b"guardian_action",
+            guardian_set.key().as_ref(),
+            &guardian_set.action_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub guardian_action: Account<'info, GuardianAction>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Cancel a pending fee proposal
+/// Approve a pending emergency action (any registered guardian)
+#[event_cpi]
 #[derive(Accounts)]
-pub struct CancelFeeProposal<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+pub struct ApproveGuardianAction<'info> {
+    pub guardian: Signer<'info>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        seeds = [b"guardian_set", config.key().as_ref()],
+        bump = guardian_set.bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
     #[account(
         mut,
-        seeds = [b"fee_proposal", config.key().as_ref()],
-        bump = fee_proposal.bump
+        seeds = [
+            b"guardian_action",
+            guardian_set.key().as_ref(),
+            &guardian_action.nonce.to_le_bytes()
+        ],
+        bump = guardian_action.bump
     )]
-    pub fee_proposal: Account<'info, FeeProposal>,
+    pub guardian_action: Account<'info, GuardianAction>,
 }
 
-/// Emergency fee update - requires both authority AND guardian signature (multisig)
+/// Execute an approved emergency action (permissionless once M-of-N)
+#[event_cpi]
 #[derive(Accounts)]
-pub struct UpdateFees<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    /// Guardian must also sign for emergency updates (multisig pattern)
-    pub guardian: Signer<'info>,
+pub struct ExecuteGuardianAction<'info> {
+    pub executor: Signer<'info>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"guardian_set", config.key().as_ref()],
+        bump = guardian_set.bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"guardian_action",
+            guardian_set.key().as_ref(),
+            &guardian_action.nonce.to_le_bytes()
+        ],
+        bump = guardian_action.bump
+    )]
+    pub guardian_action: Account<'info, GuardianAction>,
 }
 
 /// Initialize LP vault for tracking fee allocations
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeLPVault<'info> {
+    /// Holder of the admin role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1113,11 +7607,17 @@ pub struct InitializeLPVault<'info> {
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.admin == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         init,
         payer = authority,
@@ -1143,8 +7643,10 @@ pub struct InitializeLPVault<'info> {
 }
 
 /// Allocate tokens to LP vault
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AllocateToLP<'info> {
+    /// Holder of the LP operator role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1155,11 +7657,17 @@ pub struct AllocateToLP<'info> {
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.lp_operator == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         seeds = [b"lp_vault", token_mint.key().as_ref()],
@@ -1186,9 +7694,11 @@ pub struct AllocateToLP<'info> {
 }
 
 /// Record an LP deployment (after off-chain Raydium interaction)
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(amount: u64, lp_tokens_received: u64, pool_address: Pubkey)]
 pub struct RecordLPDeployment<'info> {
+    /// Holder of the LP operator role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1196,11 +7706,17 @@ pub struct RecordLPDeployment<'info> {
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.lp_operator == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         seeds = [b"lp_vault", token_mint.key().as_ref()],
@@ -1221,8 +7737,10 @@ pub struct RecordLPDeployment<'info> {
 }
 
 /// Withdraw from LP vault (emergency)
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawFromLPVault<'info> {
+    /// Holder of the LP operator role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1233,11 +7751,17 @@ pub struct WithdrawFromLPVault<'info> {
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.lp_operator == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         seeds = [b"lp_vault", token_mint.key().as_ref()],
@@ -1263,8 +7787,10 @@ pub struct WithdrawFromLPVault<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SetPaused<'info> {
+    /// Holder of the pauser role
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -1273,85 +7799,81 @@ pub struct SetPaused<'info> {
     #[account(
         mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
+
+    #[account(
+        seeds = [b"roles", config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.pauser == authority.key() @ KernelError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
-/// Propose authority transfer (starts 24-hour timelock)
+/// Migrate the config (and its burn record and LP vault) to the current layout
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ProposeAuthorityTransfer<'info> {
+pub struct MigrateConfig<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: Version 0 config, validated and deserialized in migrate_config
     #[account(
+        mut,
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
-    )]
-    pub config: Account<'info, KernelConfig>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + PendingAuthorityTransfer::INIT_SPACE,
-        seeds = [b"pending_authority_transfer", config.key().as_ref()],
         bump
     )]
-    pub pending_transfer: Account<'info, PendingAuthorityTransfer>,
-
-    pub system_program: Program<'info, System>,
-}
-
-/// Execute authority transfer after timelock expires
-#[derive(Accounts)]
-pub struct ExecuteAuthorityTransfer<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub config: UncheckedAccount<'info>,
 
+    /// CHECK: Only migrated if it exists with the version 0 layout
     #[account(
         mut,
-        seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        seeds = [b"burn", config.key().as_ref()],
+        bump
     )]
-    pub config: Account<'info, KernelConfig>,
+    pub burn_record: UncheckedAccount<'info>,
 
+    /// CHECK: Only migrated if it exists with the version 0 layout
     #[account(
         mut,
-        seeds = [b"pending_authority_transfer", config.key().as_ref()],
-        bump = pending_transfer.bump,
-        constraint = pending_transfer.proposer == authority.key() @ KernelError::NotAuthority
+        seeds = [b"lp_vault", token_mint.key().as_ref()],
+        bump
     )]
-    pub pending_transfer: Account<'info, PendingAuthorityTransfer>,
+    pub lp_vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Cancel a pending authority transfer
+/// Migrate a stake position to the current layout (permissionless)
+#[event_cpi]
 #[derive(Accounts)]
-pub struct CancelAuthorityTransfer<'info> {
+pub struct MigrateUserStake<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         seeds = [b"config", token_mint.key().as_ref()],
-        bump = config.bump,
-        constraint = config.authority == authority.key() @ KernelError::NotAuthority
+        bump = config.bump
     )]
     pub config: Account<'info, KernelConfig>,
 
+    /// CHECK: Only used to derive the stake PDA
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Version 0 stake, validated and deserialized in migrate_user_stake
     #[account(
         mut,
-        seeds = [b"pending_authority_transfer", config.key().as_ref()],
-        bump = pending_transfer.bump
+        seeds = [b"stake", config.key().as_ref(), owner.key().as_ref()],
+        bump
     )]
-    pub pending_transfer: Account<'info, PendingAuthorityTransfer>,
+    pub user_stake: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 // === STATE ===
@@ -1359,6 +7881,7 @@ pub struct CancelAuthorityTransfer<'info> {
 #[account]
 #[derive(InitSpace)]
 pub struct KernelConfig {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
     pub authority: Pubkey,
     pub token_mint: Pubkey,
     pub staking_vault: Pubkey,
@@ -1367,73 +7890,413 @@ pub struct KernelConfig {
     pub lp_share_bps: u16,          // 200 = 2%
     pub burn_share_bps: u16,        // 100 = 1%
     pub total_staked: u64,
+    pub total_shares: u64, // Sum of lock-boosted stake shares
     pub total_reflections_distributed: u64,
     pub pending_reflections: u64,
     pub accumulated_per_share: u128, // Scaled by PRECISION for accuracy
-    pub is_paused: bool,
+    pub pause_flags: u8,             // PAUSE_* bits (legacy is_paused = PAUSE_STAKE)
+    pub unbonding_period: i64,       // Unstake cooldown in seconds (0 = instant unstake)
+    pub total_unbonding: u64,        // Held in the staking vault, no longer earning
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub reward_pool_count: u8, // Extra reward pools registered for this config
+    pub reward_stream_duration: i64, // Seconds each deposit streams over (0 = lump sum)
+    pub reward_rate: u128,     // Streamed tokens per second, scaled by PRECISION
+    pub reward_period_end: i64, // When the current stream runs dry
+    pub last_update_time: i64, // Stream accrued into accumulated_per_share up to here
+    pub governance_action_count: u64, // Nonce for the next GovernanceAction PDA
+    pub action_delays: [i64; ACTION_KIND_COUNT], // Timelock per ActionKind, in seconds
+    pub paused_until: i64,     // pause_flags lift at this time (0 = until unpaused)
+    pub reflection_surplus: u64, // Pool balance above what it owed at the last sync
+    pub reflection_deficit: u64, // What the pool owed but could not cover at the last sync
+    pub reflection_owed: u64,  // Claims left unpaid by pro-rata payouts, repaid from surplus
+    pub undistributed_reflections: u64, // Deposited while nobody was staked, credited later
+    pub reflection_remainder: u64, // accumulated_per_share rounding dust, scaled by PRECISION
+    pub position_count: u64,   // Id of the next StakePosition
+    pub vesting_count: u64,    // Id of the next VestingSchedule
+    pub last_vote_opened_at: i64, // proposed_at of the newest voted action
+    pub vote_closes_at: i64,   // Latest eta of any voted action
+    pub reserved: [u8; 55],    // Zeroed room for new fields
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserStake {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub owner: Pubkey,
+    pub staked_amount: u64,
+    pub stake_time: i64,
+    pub pending_rewards: u64,
+    pub total_claimed: u64,
+    pub reward_debt: u128,  // Changed to u128 for precision
+    pub shares: u64,               // staked_amount boosted by lock_multiplier_bps
+    pub lock_multiplier_bps: u16,  // 10000 = 1x (unlocked)
+    pub lock_until: i64,           // Unstake is blocked until this timestamp
+    pub unbonding_nonce: u64,      // Seed for the next unbonding ticket
+    pub unbonding_amount: u64,     // Sum of this user's open tickets
+    pub auto_compound: bool,       // Lets anyone crank compound_reflections
+    pub compound_tip_bps: u16,     // Cranker's cut, capped at MAX_CRANK_TIP_BPS
+    pub bump: u8,
+    pub stake_updated_at: i64, // Last change to staked_amount
+    pub delegate: Pubkey,      // stake_for funder holding withdrawals (default = owner)
+    pub reflection_owed: u64,  // Unpaid part of pro-rata claims, repaid once the pool recovers
+    pub reward_fraction: u64,  // Sub-unit reflections owed on top of the debt, PRECISION-scaled
+    pub vote_checkpoint_at: i64, // First stake change after the newest vote opened
+    pub vote_checkpoint_amount: u64, // Lowest stake held when open votes were proposed
+    pub vote_checkpoint_expires: i64, // Votes open at the checkpoint have all closed by then
+    pub reserved: [u8; 48],   // Zeroed room for new fields
+}
+
+/// Transferable stake position, keyed by a config-wide id
+/// Its stake is accounted in a UserStake owned by this PDA
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub position_id: u64,
+    pub owner: Pubkey,        // Controls the position while it has no receipt
+    pub receipt_mint: Pubkey, // Token-2022 NFT whose holder controls it (default = none)
+    pub user_stake: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Token grant vesting linearly after a cliff, held in its own vault PDA
+#[account]
+#[derive(InitSpace)]
+pub struct VestingSchedule {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub vesting_id: u64,
+    pub grantor: Pubkey, // Funded the grant; gets unvested tokens back on revoke
+    pub beneficiary: Pubkey,
+    pub total_amount: u64, // Frozen at the vested amount once revoked
+    pub released_amount: u64,
+    pub staked_amount: u64, // In staking_vault on the beneficiary's stake
+    pub start_time: i64,
+    pub cliff: i64,    // Seconds after start_time before anything vests
+    pub duration: i64, // Seconds after start_time until fully vested
+    pub revocable: bool,
+    pub revoked_at: i64,      // 0 = not revoked
+    pub unbonding_until: i64, // Releases wait out the cooldown after an unstake
     pub bump: u8,
     pub vault_bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
 }
 
+/// Extra reward mint distributed to stakers by boosted shares
 #[account]
 #[derive(InitSpace)]
-pub struct UserStake {
+pub struct RewardPool {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    pub accumulated_per_share: u128, // Scaled by PRECISION, like the reflection pool
+    pub total_funded: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// A staker's position in one reward pool
+#[account]
+#[derive(InitSpace)]
+pub struct UserRewardState {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
     pub owner: Pubkey,
-    pub staked_amount: u64,
-    pub stake_time: i64,
+    pub user_stake: Pubkey,
+    pub reward_pool: Pubkey,
+    pub reward_debt: u128, // Against the pool's accumulated_per_share
     pub pending_rewards: u64,
     pub total_claimed: u64,
-    pub reward_debt: u128,  // Changed to u128 for precision
     pub bump: u8,
+    pub reserved: [u8; 32], // Zeroed room for new fields
+}
+
+/// Pending withdrawal created by request_unstake
+#[account]
+#[derive(InitSpace)]
+pub struct UnbondingTicket {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub owner: Pubkey,
+    pub user_stake: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub requested_at: i64,
+    pub release_time: i64,
+    pub bump: u8,
+    pub reserved: [u8; 32], // Zeroed room for new fields
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct BurnRecord {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
     pub total_burned: u64,
     pub burn_count: u64,
     pub last_burn_time: i64,
     pub bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Airdrop campaign lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CampaignStatus {
+    Draft,  // Created, waiting for its budget to be funded
+    Active, // Fully funded, claimable within its window
+    Closed, // No further claims; leftovers can be swept
+}
+
+/// Merkle-distributor airdrop campaign
+#[account]
+#[derive(InitSpace)]
+pub struct Campaign {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    pub start_time: i64,
+    pub end_time: i64,
+    pub budget: u64,        // Total tokens the campaign may distribute
+    pub total_funded: u64,  // Tokens deposited into the campaign vault
+    pub total_claimed: u64, // Tokens claimed by recipients
+    pub claim_count: u64,   // Number of successful claims
+    pub status: CampaignStatus,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Airdrop claim receipt (one per claimant per campaign)
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropClaim {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+    pub reserved: [u8; 32], // Zeroed room for new fields
+}
+
+/// A lock-up duration and the reward multiplier it earns
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct LockTier {
+    pub lock_duration: i64,  // Seconds
+    pub multiplier_bps: u16, // 10000 = 1x
 }
 
+/// Lock-up tier table for boosted staking
 #[account]
 #[derive(InitSpace)]
-pub struct AirdropState {
-    pub total_airdropped: u64,
-    pub recipient_count: u64,
+pub struct StakingTiers {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    #[max_len(8)]
+    pub tiers: Vec<LockTier>,
     pub bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Changes that go through the governance timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum GovernancePayload {
+    UpdateFees {
+        reflection_share_bps: u16,
+        lp_share_bps: u16,
+        burn_share_bps: u16,
+    },
+    TransferAuthority {
+        new_authority: Pubkey,
+    },
+    /// Scheduled pause of staking alone (pre-flags form, replaces the whole mask)
+    SetPaused {
+        paused: bool,
+    },
+    UpdateTiers {
+        #[max_len(8)]
+        tiers: Vec<LockTier>,
+    },
+    UpdateGuardianSet {
+        #[max_len(10)]
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    },
+    SetLpWithdrawLimit {
+        withdraw_limit: u64,
+    },
+    SetActionDelay {
+        kind: ActionKind,
+        delay: i64,
+    },
+    GrantRole {
+        role: Role,
+        holder: Pubkey,
+    },
+    RevokeRole {
+        role: Role,
+    },
+    SetUnbondingPeriod {
+        unbonding_period: i64,
+    },
+    SetRewardStreamDuration {
+        reward_stream_duration: i64,
+    },
+    /// Scheduled pause; the immediate path is `set_paused`
+    SetPauseFlags {
+        flags: u8,
+        paused_until: i64,
+    },
+}
+
+/// Governance action types that each carry their own timelock delay
+/// Indexes config.action_delays
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ActionKind {
+    Fees,
+    Authority,
+    Pause,
+    Tiers,
+    Guardians,
+    LpWithdrawLimit,
+    Roles,
+    Unbonding,
+    RewardStream,
 }
 
-/// Fee proposal for timelock mechanism
+/// Queued governance action, one PDA per nonce
 #[account]
 #[derive(InitSpace)]
-pub struct FeeProposal {
+pub struct GovernanceAction {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub nonce: u64,
     pub proposer: Pubkey,
-    pub reflection_share_bps: u16,
-    pub lp_share_bps: u16,
-    pub burn_share_bps: u16,
+    pub payload: GovernancePayload,
     pub proposed_at: i64,
+    pub eta: i64,        // Earliest execution time, also when voting closes
+    pub expires_at: i64, // Latest execution time
     pub executed: bool,
     pub cancelled: bool,
     pub bump: u8,
+    pub votes_for: u64,             // Staked amount voting for (fee changes only)
+    pub votes_against: u64,         // Staked amount voting against
+    pub total_staked_snapshot: u64, // config.total_staked at proposal, for quorum
+    pub reserved: [u8; 8],          // Zeroed room for new fields
+}
+
+/// Receipt for one stake position's vote on a governance action
+#[account]
+#[derive(InitSpace)]
+pub struct VoteReceipt {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub governance_action: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub voted_at: i64,
+    pub bump: u8,
+    pub voting_ends_at: i64, // The action's eta; the receipt can close after it
+    pub reserved: [u8; 24],  // Zeroed room for new fields
+}
+
+/// M-of-N guardians that approve emergency actions
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianSet {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    #[max_len(10)]
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,     // Approvals needed to execute (M)
+    pub epoch: u32,        // Bumped on every membership change
+    pub action_count: u64, // Nonce for the next GuardianAction PDA
+    pub bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Key holding each operational role; Pubkey::default() means unheld
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub config: Pubkey,
+    pub admin: Pubkey,  // Vault/LP setup, reward pools, unbonding and streams
+    pub pauser: Pubkey, // set_paused
+    pub reflection_depositor: Pubkey, // deposit_reflections
+    pub lp_operator: Pubkey, // Allocate, record and withdraw LP funds
+    pub burner: Pubkey, // burn_tokens
+    pub airdrop_manager: Pubkey, // Create, fund, close and sweep campaigns
+    pub bump: u8,
+    pub reserved: [u8; 64], // Zeroed room for new fields
+}
+
+/// Operational roles in the Roles account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Admin,
+    Pauser,
+    ReflectionDepositor,
+    LpOperator,
+    Burner,
+    AirdropManager,
+}
+
+/// Emergency changes the guardians can make without the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum EmergencyAction {
+    UpdateFees {
+        reflection_share_bps: u16,
+        lp_share_bps: u16,
+        burn_share_bps: u16,
+    },
+    /// Pauses or unpauses staking alone (pre-flags form, replaces the whole mask)
+    SetPaused {
+        paused: bool,
+    },
+    SetPauseFlags {
+        flags: u8,
+        paused_until: i64,
+    },
+}
+
+/// What a pause check guards
+/// Unstake and claims are never paused, so they have no scope
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseScope {
+    Stake,
+    RewardDeposits,
+    AirdropClaims,
+    LpAllocation,
+    Burns,
 }
 
-/// Pending authority transfer for timelock mechanism
+/// Emergency action awaiting guardian approvals
 #[account]
 #[derive(InitSpace)]
-pub struct PendingAuthorityTransfer {
+pub struct GuardianAction {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
+    pub guardian_set: Pubkey,
+    pub nonce: u64,
     pub proposer: Pubkey,
-    pub new_authority: Pubkey,
+    pub action: EmergencyAction,
+    pub epoch: u32,     // Guardian set epoch the approvals belong to
+    pub approvals: u16, // Bitmap indexed by guardian position
     pub proposed_at: i64,
     pub executed: bool,
-    pub cancelled: bool,
     pub bump: u8,
+    pub expires_at: i64,    // Approvals and execution stop after this
+    pub reserved: [u8; 21], // Zeroed room for new fields
 }
 
 /// LP Vault for tracking fee allocations to liquidity
 #[account]
 #[derive(InitSpace)]
 pub struct LPVault {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
     pub authority: Pubkey,
     pub token_mint: Pubkey,
     pub total_allocated: u64,      // Total tokens ever allocated to LP
@@ -1442,18 +8305,539 @@ pub struct LPVault {
     pub last_deployment_time: i64,
     pub bump: u8,
     pub vault_token_bump: u8,
+    pub withdraw_limit: u64, // Max withdrawn per LP_WITHDRAW_PERIOD (0 = no limit)
+    pub withdrawn_in_period: u64, // Withdrawn since period_start
+    pub period_start: i64,   // Start of the current withdraw limit period
+    pub reserved: [u8; 40],  // Zeroed room for new fields
 }
 
 /// Individual LP deployment record
 #[account]
 #[derive(InitSpace)]
 pub struct LPDeployment {
+    pub version: u8, // Layout version, see ACCOUNT_VERSION
     pub pool_address: Pubkey,      // Raydium pool address
     pub kernel_amount: u64,        // KERNEL tokens deployed
     pub lp_tokens_received: u64,   // LP tokens received
     pub deployed_at: i64,
     pub withdrawn: bool,
     pub bump: u8,
+    pub reserved: [u8; 32], // Zeroed room for new fields
+}
+
+// Version 0 layouts, only read by the migrate_* instructions
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyKernelConfig {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub staking_vault: Pubkey,
+    pub reflection_pool: Pubkey,
+    pub reflection_share_bps: u16,
+    pub lp_share_bps: u16,
+    pub burn_share_bps: u16,
+    pub total_staked: u64,
+    pub total_reflections_distributed: u64,
+    pub pending_reflections: u64,
+    pub accumulated_per_share: u128,
+    pub is_paused: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyUserStake {
+    pub owner: Pubkey,
+    pub staked_amount: u64,
+    pub stake_time: i64,
+    pub pending_rewards: u64,
+    pub total_claimed: u64,
+    pub reward_debt: u128,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyBurnRecord {
+    pub total_burned: u64,
+    pub burn_count: u64,
+    pub last_burn_time: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyLPVault {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_allocated: u64,
+    pub total_deployed: u64,
+    pub pending_deployment: u64,
+    pub last_deployment_time: i64,
+    pub bump: u8,
+    pub vault_token_bump: u8,
+}
+
+// === EVENTS ===
+// Emitted via emit_cpi! so indexers can read them from inner instructions
+// even when program logs are truncated.
+
+#[event]
+pub struct ProgramInitialized {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub reflection_share_bps: u16,
+    pub lp_share_bps: u16,
+    pub burn_share_bps: u16,
+}
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub lock_multiplier_bps: u16,
+    pub lock_until: i64,
+    pub total_staked: u64,
+}
+
+/// `delegate` is the funder when it kept withdrawals, None for a gift
+#[event]
+pub struct StakedFor {
+    pub funder: Pubkey,
+    pub beneficiary: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub amount: u64,
+    pub shares: u64,
+    pub lock_until: i64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct EmergencyUnstaked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub forfeited_rewards: u64, // Settled rewards given up; unsettled ones are not computed
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub owner: Pubkey,
+    pub ticket: Pubkey,
+    pub amount: u64,
+    pub release_time: i64,
+}
+
+#[event]
+pub struct UnbondedWithdrawn {
+    pub owner: Pubkey,
+    pub ticket: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionOpened {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub lock_until: i64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct PositionReceiptMinted {
+    pub position: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct PositionTransferred {
+    pub position: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+}
+
+#[event]
+pub struct PositionUnstaked {
+    pub position: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct PositionEmergencyUnstaked {
+    pub position: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub forfeited_rewards: u64, // Settled rewards given up; unsettled ones are not computed
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub schedule: Pubkey,
+    pub vesting_id: u64,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub duration: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestingReleased {
+    pub schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+}
+
+#[event]
+pub struct VestingStaked {
+    pub schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct VestingUnstaked {
+    pub schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct VestingEmergencyUnstaked {
+    pub schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,            // What reached the vesting vault
+    pub forfeited_rewards: u64, // Settled rewards given up; unsettled ones are not computed
+    pub total_staked: u64,
+}
+
+/// `vested` stays with the beneficiary; `returned` went back to the grantor
+#[event]
+pub struct VestingRevoked {
+    pub schedule: Pubkey,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub vested: u64,
+    pub returned: u64,
+}
+
+/// `deferred` is the part of the claim a short reflection pool could not pay yet
+#[event]
+pub struct ReflectionsClaimed {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub deferred: u64,
+}
+
+/// `cranker` is None when the owner compounds their own position
+/// `deferred` is the part of the claim a short reflection pool could not pay yet
+#[event]
+pub struct ReflectionsCompounded {
+    pub owner: Pubkey,
+    pub cranker: Option<Pubkey>,
+    pub amount: u64,
+    pub tip: u64,
+    pub deferred: u64,
+}
+
+#[event]
+pub struct AutoCompoundUpdated {
+    pub owner: Pubkey,
+    pub enabled: bool,
+    pub tip_bps: u16,
+}
+
+#[event]
+pub struct BoostRefreshed {
+    pub owner: Pubkey,
+    pub old_shares: u64,
+    pub shares: u64,
+    pub total_shares: u64,
+}
+
+#[event]
+pub struct RewardPoolAdded {
+    pub reward_pool: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_pool_count: u8,
+}
+
+#[event]
+pub struct RewardPoolFunded {
+    pub reward_pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub accumulated_per_share: u128,
+}
+
+#[event]
+pub struct RewardStateOpened {
+    pub reward_pool: Pubkey,
+    pub user_stake: Pubkey,
+    pub owner: Pubkey, // Staker, or the position PDA for position stakes
+    pub payer: Pubkey,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub owner: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+}
+
+/// `liability` is pending_reflections plus reflection_owed; at most one of surplus and
+/// deficit is non-zero
+#[event]
+pub struct ReflectionPoolSynced {
+    pub balance: u64,
+    pub liability: u64,
+    pub surplus: u64,
+    pub deficit: u64,
+}
+
+#[event]
+pub struct ReflectionsDeposited {
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub accumulated_per_share: u128,
+    pub reward_rate: u128, // 0 for lump-sum deposits
+    pub reward_period_end: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub burner: Pubkey,
+    pub amount: u64,
+    pub total_burned: u64,
+}
+
+#[event]
+pub struct FeeVaultInitialized {
+    pub fee_vault: Pubkey,
+}
+
+#[event]
+pub struct FeesHarvested {
+    pub harvester: Pubkey,
+    pub amount: u64,
+    pub reflection_amount: u64,
+    pub lp_amount: u64,
+    pub burn_amount: u64,
+}
+
+#[event]
+pub struct CampaignCreated {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    pub budget: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct CampaignFunded {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub amount: u64,
+    pub total_funded: u64,
+    pub active: bool,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CampaignClosed {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+}
+
+#[event]
+pub struct CampaignSwept {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GovernanceActionProposed {
+    pub action: Pubkey,
+    pub nonce: u64,
+    pub proposer: Pubkey,
+    pub payload: GovernancePayload,
+    pub eta: i64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct GovernanceActionExecuted {
+    pub action: Pubkey,
+    pub nonce: u64,
+    pub payload: GovernancePayload,
+}
+
+#[event]
+pub struct AuthorityTransferExecuted {
+    pub action: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct VoteCast {
+    pub action: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+}
+
+#[event]
+pub struct VoteReceiptClosed {
+    pub action: Pubkey,
+    pub voter: Pubkey,
+}
+
+#[event]
+pub struct GovernanceActionCancelled {
+    pub action: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct GovernanceActionClosed {
+    pub action: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ActionDelayUpdated {
+    pub kind: ActionKind,
+    pub delay: i64,
+}
+
+/// Emitted with every role holder whenever a role changes
+#[event]
+pub struct RolesUpdated {
+    pub admin: Pubkey,
+    pub pauser: Pubkey,
+    pub reflection_depositor: Pubkey,
+    pub lp_operator: Pubkey,
+    pub burner: Pubkey,
+    pub airdrop_manager: Pubkey,
+}
+
+/// Emitted with the full membership whenever the guardian set changes
+#[event]
+pub struct GuardianSetUpdated {
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    pub epoch: u32,
+}
+
+#[event]
+pub struct GuardianActionProposed {
+    pub proposal: Pubkey,
+    pub nonce: u64,
+    pub proposer: Pubkey,
+    pub action: EmergencyAction,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct GuardianActionApproved {
+    pub proposal: Pubkey,
+    pub guardian: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct GuardianActionExecuted {
+    pub proposal: Pubkey,
+    pub nonce: u64,
+    pub action: EmergencyAction,
+}
+
+/// Emitted with the full tier table whenever it is set
+#[event]
+pub struct StakingTiersUpdated {
+    pub tiers: Vec<LockTier>,
+}
+
+#[event]
+pub struct Paused {
+    pub flags: u8,
+    pub paused_until: i64,
+}
+
+#[event]
+pub struct UnbondingPeriodUpdated {
+    pub unbonding_period: i64,
+}
+
+#[event]
+pub struct RewardStreamDurationUpdated {
+    pub reward_stream_duration: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct LpVaultInitialized {
+    pub lp_vault: Pubkey,
+    pub lp_vault_token: Pubkey,
+}
+
+#[event]
+pub struct LpAllocated {
+    pub amount: u64,
+    pub pending_deployment: u64,
+}
+
+#[event]
+pub struct LpDeployed {
+    pub deployment: Pubkey,
+    pub pool_address: Pubkey,
+    pub amount: u64,
+    pub lp_tokens_received: u64,
+}
+
+#[event]
+pub struct LpWithdrawn {
+    pub amount: u64,
+    pub pending_deployment: u64,
 }
 
 // === ERRORS ===
@@ -1476,7 +8860,7 @@ pub enum KernelError {
     TooManyRecipients,
     #[msg("Program is paused")]
     ProgramPaused,
-    #[msg("Timelock period not expired - wait 24 hours")]
+    #[msg("Timelock not expired - wait out the delay configured for this action type")]
     TimelockNotExpired,
     #[msg("Proposal already executed")]
     ProposalAlreadyExecuted,
@@ -1490,6 +8874,120 @@ pub enum KernelError {
     AuthorityTransferAlreadyExecuted,
     #[msg("Authority transfer was cancelled")]
     AuthorityTransferCancelled,
+    #[msg("Invalid Merkle proof for airdrop claim")]
+    InvalidMerkleProof,
+    #[msg("Campaign end time must be after its start time")]
+    InvalidCampaignWindow,
+    #[msg("Campaign is not active")]
+    CampaignNotActive,
+    #[msg("Campaign is closed")]
+    CampaignClosed,
+    #[msg("Campaign is not closed")]
+    CampaignNotClosed,
+    #[msg("Campaign budget exceeded")]
+    CampaignBudgetExceeded,
+    #[msg("Nothing to sweep")]
+    NothingToSweep,
+    #[msg("Stake is locked - Colonel Kernel says wait!")]
+    StakeLocked,
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+    #[msg("Invalid tier configuration")]
+    InvalidTierConfig,
+    #[msg("Unbonding period active - use request_unstake")]
+    UnbondingRequired,
+    #[msg("Unbonding not complete - the popcorn is still popping!")]
+    UnbondingNotReady,
+    #[msg("Invalid unbonding period")]
+    InvalidUnbondingPeriod,
+    #[msg("Auto-compound not enabled for this stake")]
+    AutoCompoundDisabled,
+    #[msg("Crank tip exceeds maximum")]
+    CrankTipTooHigh,
+    #[msg("No withheld fees to harvest")]
+    NothingToHarvest,
+    #[msg("Account already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Account does not match a known layout")]
+    UnknownAccountLayout,
+    #[msg("Maximum reward pools reached")]
+    TooManyRewardPools,
+    #[msg("Reward pool accounts missing or invalid")]
+    InvalidRewardAccounts,
+    #[msg("No stakers to distribute rewards to")]
+    NoStakers,
+    #[msg("Invalid reward stream duration")]
+    InvalidStreamDuration,
+    #[msg("Invalid guardian set - need 1 <= threshold <= unique guardians (max 10)")]
+    InvalidGuardianSet,
+    #[msg("Signer is not a registered guardian")]
+    NotGuardian,
+    #[msg("Guardian already approved this action")]
+    AlreadyApproved,
+    #[msg("Not enough guardian approvals")]
+    GuardianThresholdNotMet,
+    #[msg("Guardian set changed since this action was proposed")]
+    StaleGuardianProposal,
+    #[msg("Governance action expired - propose it again")]
+    ActionExpired,
+    #[msg("Account required by this governance action is missing")]
+    MissingActionAccount,
+    #[msg("Withdrawal exceeds the LP vault withdraw limit for this period")]
+    LpWithdrawLimitExceeded,
+    #[msg("This governance action is not open for voting")]
+    VotingNotOpen,
+    #[msg("Voting has closed for this action")]
+    VotingClosed,
+    #[msg("No staked balance to vote with")]
+    NoVotingPower,
+    #[msg("Vote did not reach quorum")]
+    QuorumNotReached,
+    #[msg("Vote did not pass")]
+    VoteNotPassed,
+    #[msg("Action delay must be between 5 minutes and 30 days")]
+    InvalidActionDelay,
+    #[msg("Governance action can still execute - cancel it first")]
+    ActionStillPending,
+    #[msg("Rent must go back to the action's proposer")]
+    NotProposer,
+    #[msg("Authority transfers complete when the new authority accepts")]
+    AuthorityAcceptanceRequired,
+    #[msg("Only the proposed new authority can accept this transfer")]
+    NotPendingAuthority,
+    #[msg("Signer does not hold the role for this instruction")]
+    MissingRole,
+    #[msg("Roles must be granted to a real key - use RevokeRole instead")]
+    InvalidRoleHolder,
+    #[msg("Unknown pause flag or pause expiry already passed")]
+    InvalidPause,
+    #[msg("Math overflow - the numbers got too big for the kernel")]
+    MathOverflow,
+    #[msg("Division by zero")]
+    DivisionByZero,
+    #[msg("Stake position is delegated to another key, or already funded")]
+    DelegationMismatch,
+    #[msg("Position already has a receipt NFT")]
+    ReceiptAlreadyMinted,
+    #[msg("Token account does not hold this position's receipt")]
+    InvalidReceipt,
+    #[msg("Vesting needs a positive total and duration, with the cliff inside the duration")]
+    InvalidVestingSchedule,
+    #[msg("Nothing vested to release - patience, kernel")]
+    NothingVested,
+    #[msg("Vesting schedule is not revocable")]
+    VestingNotRevocable,
+    #[msg("Vesting schedule has been revoked")]
+    VestingRevoked,
+    #[msg("Not enough unstaked tokens left in the vesting vault")]
+    InsufficientVesting,
+    #[msg("No expired lock to refresh")]
+    NoExpiredLock,
+    #[msg("Voting is still open for this action")]
+    VotingStillOpen,
+    #[msg("Delegated or locked stake needs the beneficiary's signature")]
+    BeneficiaryNotSigner,
+    #[msg("Positions can't be handed to the default address")]
+    InvalidPositionOwner,
 }
 
 #[cfg(test)]
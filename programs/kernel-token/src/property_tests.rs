@@ -13,22 +13,46 @@
 #![cfg(all(test, not(target_arch = "bpf")))]
 #![allow(clippy::assertions_on_constants)]
 
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, AnchorDeserialize, AnchorSerialize, Space};
 use proptest::prelude::*;
 
+use crate::{
+    accrue_reflection_stream, action_delay, action_kind, action_settled, add_compounded_stake,
+    airdrop_leaf, calculate_pending_rewards, calculate_reward_debt, calculate_shares,
+    campaign_accepts_claims, charge_vesting_fee, check_action_executable,
+    check_authority_acceptance, check_beneficiary_consent, check_delegation,
+    check_guardian_action_open, check_vote_passed, credit_stake, distribute_reflections,
+    draw_reflections, expire_lock, forfeit_reward_state, forfeit_stake, freeze_vesting, is_paused,
+    legacy_pause_flags, merkle_node, migrate_config_layout, migrate_user_stake_layout,
+    open_unbonding_ticket, position_holder, reconcile_reflection_pool, record_guardian_approval,
+    record_lp_withdrawal, record_reflection_deposit, record_reward_deposit, record_vote,
+    releasable_amount, remove_stake, return_vesting_stake, rotate_roles, set_role,
+    settle_claimable, settle_reward_claimable, settle_reward_state, split_crank_tip, split_fees,
+    to_u64, unbonding_complete, valid_fee_split, valid_pause, valid_vesting,
+    validate_governance_payload, validate_guardian_set, validate_lock_tiers, verify_merkle_proof,
+    vested_amount, vesting_held, vote_weight, withdraw_authority, ActionKind, Campaign,
+    CampaignStatus, EmergencyAction, GovernanceAction, GovernancePayload, GuardianAction,
+    GuardianSet, KernelConfig, KernelError, LPVault, LegacyKernelConfig, LegacyUserStake, LockTier,
+    PauseScope, RewardPool, Role, Roles, SafeMath, StakePosition, UnbondingTicket, UserRewardState,
+    UserStake, VestingSchedule, ACCOUNT_VERSION, ACTION_KIND_COUNT, BASE_MULTIPLIER_BPS,
+    DEFAULT_LOCK_TIERS, EXECUTION_WINDOW, GUARDIAN_ACTION_WINDOW, LP_WITHDRAW_PERIOD,
+    MAX_ACTION_DELAY, MAX_CRANK_TIP_BPS, MAX_GUARDIANS, MAX_MULTIPLIER_BPS, MAX_UNBONDING_PERIOD,
+    MIN_ACTION_DELAY, PAUSE_AIRDROP_CLAIMS, PAUSE_ALL, PAUSE_BURNS, PAUSE_LP_ALLOCATION,
+    PAUSE_REWARD_DEPOSITS, PAUSE_STAKE, QUORUM_BPS,
+};
+
 /// Precision constant for reflection calculations (10^12)
 const PRECISION: u128 = 1_000_000_000_000;
 
 /// Timelock duration in seconds (24 hours)
 const TIMELOCK_DURATION: i64 = 86400;
 
-/// Maximum airdrop recipients per call
-const MAX_AIRDROP_RECIPIENTS: usize = 50;
-
 /// Total fee in basis points (5%)
 const TOTAL_FEE_BPS: u16 = 500;
 
 // ============================================================================
-// Staking Invariants (ST-1 through ST-5)
+// Staking Invariants (ST-1 through ST-9)
 // ============================================================================
 
 proptest! {
@@ -75,10 +99,747 @@ proptest! {
         let remaining = stake_amount.saturating_sub(unstake_amount);
         prop_assert!(remaining >= 0); // Always true for u64
     }
+
+    /// ST-6: Unlocked stake earns exactly 1x shares
+    /// INVARIANT: calculate_shares(a, BASE_MULTIPLIER_BPS).unwrap() = a
+    #[test]
+    fn st6_base_multiplier_is_identity(amount in 0u64..=u64::MAX) {
+        prop_assert_eq!(calculate_shares(amount, BASE_MULTIPLIER_BPS).unwrap(), amount);
+    }
+
+    /// ST-7: Shares are monotonic in both amount and multiplier
+    /// INVARIANT: a1 ≤ a2 ∧ m1 ≤ m2 → shares(a1, m1) ≤ shares(a2, m2)
+    #[test]
+    fn st7_shares_monotonic(
+        a1 in 0u64..=1_000_000_000_000_000,
+        a2 in 0u64..=1_000_000_000_000_000,
+        m1 in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        m2 in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+    ) {
+        let (lo_a, hi_a) = (a1.min(a2), a1.max(a2));
+        let (lo_m, hi_m) = (m1.min(m2), m1.max(m2));
+
+        let lo = calculate_shares(lo_a, lo_m).unwrap();
+        let hi = calculate_shares(hi_a, hi_m).unwrap();
+        prop_assert!(lo <= hi);
+        prop_assert!(calculate_shares(hi_a, lo_m).unwrap() >= hi_a);
+    }
+
+    /// ST-8: Locked stake cannot be withdrawn early
+    /// INVARIANT: unstake at time t succeeds → t ≥ user_stake.lock_until
+    #[test]
+    fn st8_lock_enforced(
+        stake_time in 0i64..=1_000_000_000,
+        tier_index in 0usize..DEFAULT_LOCK_TIERS.len(),
+        elapsed in 0i64..=400 * 24 * 60 * 60,
+    ) {
+        let (lock_duration, _) = DEFAULT_LOCK_TIERS[tier_index];
+        let lock_until = stake_time + lock_duration;
+        let current_time = stake_time + elapsed;
+
+        let can_unstake = current_time >= lock_until;
+        prop_assert_eq!(can_unstake, elapsed >= lock_duration);
+    }
+
+    /// ST-9: Tier tables are ordered and multipliers bounded
+    /// INVARIANT: validate_lock_tiers(t) → durations strictly increasing ∧
+    ///   ∀ tier: BASE_MULTIPLIER_BPS ≤ multiplier_bps ≤ MAX_MULTIPLIER_BPS
+    #[test]
+    fn st9_tier_validation(
+        raw in prop::collection::vec((-10i64..=1_000_000, 0u16..=40_000), 0..10),
+    ) {
+        let tiers: Vec<LockTier> = raw
+            .iter()
+            .map(|&(lock_duration, multiplier_bps)| LockTier { lock_duration, multiplier_bps })
+            .collect();
+
+        if validate_lock_tiers(&tiers) {
+            prop_assert!(tiers[0].lock_duration > 0);
+            for pair in tiers.windows(2) {
+                prop_assert!(pair[0].lock_duration < pair[1].lock_duration);
+            }
+            for tier in &tiers {
+                prop_assert!(tier.multiplier_bps >= BASE_MULTIPLIER_BPS);
+                prop_assert!(tier.multiplier_bps <= MAX_MULTIPLIER_BPS);
+            }
+        }
+    }
+
+    /// ST-10: An expired lock can be rebased to 1x without losing earned rewards
+    /// INVARIANT: t ≥ lock_until > 0 → expire_lock at t leaves
+    ///   shares = staked_amount ∧ claimable unchanged ∧ total_shares = Σ shares
+    ///   while t < lock_until → expire_lock fails
+    #[test]
+    fn st10_expired_boost_rebased(
+        staked_amount in 1u64..=1_000_000_000_000,
+        other_staked in 0u64..=1_000_000_000_000,
+        multiplier_bps in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        lock_until in 1i64..=2_000,
+        current_time in 0i64..=4_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, multiplier_bps, lock_until);
+        let mut config = config_fixture(
+            staked_amount + other_staked,
+            user_stake.shares + other_staked,
+            accumulated_per_share,
+        );
+        let claimable = calculate_pending_rewards(
+            user_stake.shares,
+            accumulated_per_share,
+            user_stake.reward_debt,
+        ).unwrap();
+
+        let result = expire_lock(&mut config, &mut user_stake, current_time);
+        if current_time < lock_until {
+            prop_assert!(result.is_err());
+            return Ok(());
+        }
+
+        prop_assert_eq!(result.unwrap(), calculate_shares(staked_amount, multiplier_bps).unwrap());
+        prop_assert_eq!(user_stake.shares, staked_amount);
+        prop_assert_eq!(user_stake.lock_until, 0);
+        prop_assert_eq!(config.total_shares, staked_amount + other_staked);
+        prop_assert_eq!(user_stake.pending_rewards, claimable);
+        prop_assert_eq!(
+            calculate_pending_rewards(
+                user_stake.shares,
+                accumulated_per_share,
+                user_stake.reward_debt
+            ).unwrap(),
+            0
+        );
+        prop_assert!(expire_lock(&mut config, &mut user_stake, current_time).is_err());
+    }
+
+    /// ST-11: A multiplier is only held under the lock of the tier that grants it
+    /// INVARIANT: credit_stake(u, tier_i) at t → u.lock_multiplier_bps = m_j ⇒
+    ///   u.lock_until = t_j + d_j, where tier j was taken at t_j ≤ t ∧
+    ///   a top-up never shortens a live lock
+    #[test]
+    fn st11_multiplier_paired_with_lock(
+        deposits in prop::collection::vec(
+            (
+                1u64..=1_000_000_000,
+                0i64..=200 * 24 * 60 * 60,
+                prop::option::of(0usize..DEFAULT_LOCK_TIERS.len()),
+            ),
+            1..8,
+        ),
+    ) {
+        let mut config = config_fixture(0, 0, 0);
+        let mut user_stake = stake_fixture(0, BASE_MULTIPLIER_BPS, 0);
+        // When the lock currently in place was taken, and under which tier
+        let mut taken: Option<(i64, usize)> = None;
+        let mut now = 0i64;
+
+        for (amount, gap, tier_index) in deposits {
+            now += gap;
+            let live_until = if user_stake.lock_until > now { user_stake.lock_until } else { 0 };
+            let tier = tier_index.map(|i| {
+                let (lock_duration, multiplier_bps) = DEFAULT_LOCK_TIERS[i];
+                LockTier { lock_duration, multiplier_bps }
+            });
+            credit_stake(&mut config, &mut user_stake, amount, tier, now).unwrap();
+
+            prop_assert!(user_stake.lock_until >= live_until);
+            if user_stake.lock_until != live_until {
+                taken = Some((now, tier_index.unwrap()));
+            } else if live_until == 0 {
+                taken = None;
+            }
+
+            match taken {
+                Some((taken_at, i)) => {
+                    let (lock_duration, multiplier_bps) = DEFAULT_LOCK_TIERS[i];
+                    prop_assert_eq!(user_stake.lock_multiplier_bps, multiplier_bps);
+                    prop_assert_eq!(user_stake.lock_until, taken_at + lock_duration);
+                    prop_assert!(user_stake.lock_until - now <= lock_duration);
+                }
+                None => prop_assert_eq!(user_stake.lock_multiplier_bps, BASE_MULTIPLIER_BPS),
+            }
+        }
+    }
+}
+
+#[test]
+fn st9_default_tiers_are_valid() {
+    let tiers: Vec<LockTier> = DEFAULT_LOCK_TIERS
+        .iter()
+        .map(|&(lock_duration, multiplier_bps)| LockTier {
+            lock_duration,
+            multiplier_bps,
+        })
+        .collect();
+    assert!(validate_lock_tiers(&tiers));
+}
+
+// ============================================================================
+// Unbonding Invariants (UB-1 through UB-3)
+// ============================================================================
+
+fn ticket_fixture(amount: u64, requested_at: i64, unbonding_period: i64) -> UnbondingTicket {
+    UnbondingTicket {
+        version: ACCOUNT_VERSION,
+        owner: Pubkey::new_unique(),
+        user_stake: Pubkey::new_unique(),
+        nonce: 0,
+        amount,
+        requested_at,
+        release_time: requested_at + unbonding_period,
+        bump: 255,
+        reserved: [0; 32],
+    }
+}
+
+proptest! {
+    /// UB-1: Tickets cannot be withdrawn before the cooldown ends
+    /// INVARIANT: withdraw_unbonded(t) at time now succeeds →
+    ///   now ≥ t.requested_at + unbonding_period
+    #[test]
+    fn ub1_cooldown_enforced(
+        requested_at in 0i64..=1_000_000_000,
+        unbonding_period in 0i64..=MAX_UNBONDING_PERIOD,
+        elapsed in 0i64..=2 * MAX_UNBONDING_PERIOD,
+    ) {
+        let ticket = ticket_fixture(1, requested_at, unbonding_period);
+        let current_time = requested_at + elapsed;
+
+        prop_assert_eq!(
+            unbonding_complete(&ticket, current_time),
+            elapsed >= unbonding_period
+        );
+    }
+
+    /// UB-2: Unbonding moves tokens out of the reward base, not out of the vault
+    /// INVARIANT: vault_balance = config.total_staked + config.total_unbonding
+    #[test]
+    fn ub2_vault_conservation(
+        initial_stake in 1u64..=1_000_000_000_000,
+        requests in prop::collection::vec(1u64..=1_000_000_000, 1..20),
+        withdraw_mask in prop::collection::vec(any::<bool>(), 20),
+    ) {
+        let mut vault_balance = initial_stake;
+        let mut total_staked = initial_stake;
+        let mut total_unbonding = 0u64;
+        let mut tickets = Vec::new();
+
+        for amount in requests {
+            if amount > total_staked {
+                continue;
+            }
+            total_staked -= amount;
+            total_unbonding += amount;
+            tickets.push(amount);
+            prop_assert_eq!(vault_balance, total_staked + total_unbonding);
+        }
+
+        for (amount, withdraw) in tickets.iter().zip(withdraw_mask) {
+            if withdraw {
+                vault_balance -= amount;
+                total_unbonding -= amount;
+            }
+            prop_assert_eq!(vault_balance, total_staked + total_unbonding);
+        }
+    }
+
+    /// UB-3: Changing the cooldown never moves an existing ticket
+    /// INVARIANT: ticket.release_time is fixed at request_unstake
+    #[test]
+    fn ub3_release_time_fixed(
+        requested_at in 0i64..=1_000_000_000,
+        old_period in 0i64..=MAX_UNBONDING_PERIOD,
+        new_period in 0i64..=MAX_UNBONDING_PERIOD,
+    ) {
+        let ticket = ticket_fixture(1, requested_at, old_period);
+        let later_ticket = ticket_fixture(1, requested_at, new_period);
+
+        prop_assert_eq!(ticket.release_time, requested_at + old_period);
+        prop_assert!(unbonding_complete(&ticket, requested_at + old_period));
+        prop_assert_eq!(
+            unbonding_complete(&later_ticket, requested_at + old_period),
+            old_period >= new_period
+        );
+    }
+}
+
+// ============================================================================
+// Emergency Unstake Invariants (EU-1 through EU-3)
+// ============================================================================
+
+proptest! {
+    /// EU-1: Principal comes back whatever state the reward math is in
+    /// INVARIANT: forfeit_stake(c, u) = u.staked_amount ∧ u emptied ∧
+    ///   c.total_staked, c.total_shares drop by u's stake and shares ∧
+    ///   c.accumulated_per_share, c.pending_reflections unchanged ∧
+    ///   every reward pool state zeroed without reading its pool
+    #[test]
+    fn eu1_principal_independent_of_rewards(
+        staked_amount in 1u64..=1_000_000_000_000_000,
+        multiplier_bps in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        other_staked in 0u64..=1_000_000_000_000_000,
+        accumulated_per_share in prop::num::u128::ANY,
+        reward_debt in prop::num::u128::ANY,
+        pending_rewards in prop::num::u64::ANY,
+        pending_reflections in prop::num::u64::ANY,
+        pool_debt in prop::num::u128::ANY,
+        pool_pending in prop::num::u64::ANY,
+        now in 0i64..=1_000_000_000,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, multiplier_bps, 0);
+        let mut state = reward_state_fixture(pool_debt);
+        state.pending_rewards = pool_pending;
+        user_stake.reward_debt = reward_debt;
+        user_stake.pending_rewards = pending_rewards;
+        let mut config = config_fixture(
+            staked_amount + other_staked,
+            user_stake.shares + other_staked,
+            accumulated_per_share,
+        );
+        config.pending_reflections = pending_reflections;
+        user_stake.reflection_owed = pending_rewards / 2;
+        config.reflection_owed = pending_rewards;
+
+        let principal = forfeit_stake(&mut config, &mut user_stake, now).unwrap();
+        forfeit_reward_state(&mut state);
+
+        prop_assert_eq!(principal, staked_amount);
+        prop_assert_eq!(user_stake.staked_amount, 0);
+        prop_assert_eq!(user_stake.shares, 0);
+        prop_assert_eq!(user_stake.pending_rewards, 0);
+        prop_assert_eq!(user_stake.reward_debt, 0);
+        prop_assert_eq!(user_stake.reflection_owed, 0);
+        prop_assert_eq!(config.reflection_owed, pending_rewards - pending_rewards / 2);
+        prop_assert_eq!(user_stake.stake_updated_at, now);
+        prop_assert_eq!(config.total_staked, other_staked);
+        prop_assert_eq!(config.total_shares, other_staked);
+        prop_assert_eq!(config.accumulated_per_share, accumulated_per_share);
+        prop_assert_eq!(config.pending_reflections, pending_reflections);
+        prop_assert_eq!(state.pending_rewards, 0);
+        prop_assert_eq!(state.reward_debt, 0);
+    }
+
+    /// EU-2: Emergency unstake still honours locks and needs a stake
+    /// INVARIANT: t < u.lock_until ∨ u.staked_amount = 0 → forfeit_stake fails, u unchanged
+    #[test]
+    fn eu2_locks_still_apply(
+        staked_amount in 0u64..=1_000_000_000_000,
+        lock_until in 0i64..=1_000_000_000,
+        now in 0i64..=1_000_000_000,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, BASE_MULTIPLIER_BPS, lock_until);
+        let mut config = config_fixture(staked_amount, user_stake.shares, PRECISION);
+
+        let result = forfeit_stake(&mut config, &mut user_stake, now);
+
+        if staked_amount > 0 && now >= lock_until {
+            prop_assert_eq!(result.unwrap(), staked_amount);
+            return Ok(());
+        }
+        let expected = if staked_amount == 0 {
+            KernelError::InsufficientStake
+        } else {
+            KernelError::StakeLocked
+        };
+        prop_assert_eq!(result.unwrap_err(), expected.into());
+        prop_assert_eq!(user_stake.staked_amount, staked_amount);
+        prop_assert_eq!(config.total_staked, staked_amount);
+    }
+
+    /// EU-3: Emergency unstake still waits out the unbonding cooldown
+    /// INVARIANT: unbonding_period > 0 → the forfeited principal goes into a ticket
+    ///   released at t + unbonding_period ∧ total_staked + total_unbonding unchanged
+    #[test]
+    fn eu3_cooldown_still_applies(
+        staked_amount in 1u64..=1_000_000_000_000,
+        other_staked in 0u64..=1_000_000_000_000,
+        unbonding_period in 1i64..=MAX_UNBONDING_PERIOD,
+        now in 0i64..=1_000_000_000,
+        elapsed in 0i64..=2 * MAX_UNBONDING_PERIOD,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, BASE_MULTIPLIER_BPS, 0);
+        let mut config = config_fixture(
+            staked_amount + other_staked,
+            user_stake.shares + other_staked,
+            PRECISION,
+        );
+        config.unbonding_period = unbonding_period;
+        let mut ticket = ticket_fixture(0, 0, 0);
+
+        let principal = forfeit_stake(&mut config, &mut user_stake, now).unwrap();
+        open_unbonding_ticket(&mut config, &mut user_stake, &mut ticket, principal, now).unwrap();
+
+        prop_assert_eq!(ticket.amount, staked_amount);
+        prop_assert_eq!(ticket.release_time, now + unbonding_period);
+        prop_assert_eq!(
+            unbonding_complete(&ticket, now + elapsed),
+            elapsed >= unbonding_period
+        );
+        prop_assert_eq!(user_stake.unbonding_amount, staked_amount);
+        prop_assert_eq!(
+            config.total_staked + config.total_unbonding,
+            staked_amount + other_staked
+        );
+    }
+}
+
+// ============================================================================
+// Delegation Invariants (DL-1 through DL-4)
+// ============================================================================
+
+proptest! {
+    /// DL-1: Only the withdraw authority can take principal out
+    /// INVARIANT: u.delegate ≠ default → withdraw_authority(u) = u.delegate,
+    ///   otherwise u.owner
+    #[test]
+    fn dl1_delegate_holds_withdrawals(delegated in any::<bool>()) {
+        let mut user_stake = stake_fixture(1_000, BASE_MULTIPLIER_BPS, 0);
+        let delegate = Pubkey::new_unique();
+        if delegated {
+            user_stake.delegate = delegate;
+        }
+
+        let expected = if delegated { delegate } else { user_stake.owner };
+        prop_assert_eq!(withdraw_authority(&user_stake), expected);
+    }
+
+    /// DL-2: Positions are never mixed between a delegate and their owner
+    /// INVARIANT: gift → delegate stays default and needs an undelegated position ∧
+    ///   delegated stake → position empty or already delegated to the same funder
+    #[test]
+    fn dl2_no_mixed_positions(
+        staked_amount in 0u64..=1_000_000,
+        unbonding_amount in 0u64..=1_000_000,
+        existing in 0u8..3, // 0 = none, 1 = same funder, 2 = another funder
+        beneficiary_only in any::<bool>(),
+    ) {
+        let funder = Pubkey::new_unique();
+        let mut user_stake = stake_fixture(staked_amount, BASE_MULTIPLIER_BPS, 0);
+        user_stake.unbonding_amount = unbonding_amount;
+        user_stake.delegate = match existing {
+            0 => Pubkey::default(),
+            1 => funder,
+            _ => Pubkey::new_unique(),
+        };
+
+        let result = check_delegation(&user_stake, funder, beneficiary_only);
+
+        let allowed = if beneficiary_only {
+            existing == 0
+        } else {
+            existing == 1 || (existing == 0 && staked_amount == 0 && unbonding_amount == 0)
+        };
+        if allowed {
+            let expected = if beneficiary_only { Pubkey::default() } else { funder };
+            prop_assert_eq!(result.unwrap(), expected);
+        } else {
+            prop_assert_eq!(result.unwrap_err(), KernelError::DelegationMismatch.into());
+        }
+    }
+
+    /// DL-3: A delegated stake is credited like a normal one and lapses when emptied
+    /// INVARIANT: credit_stake(a) → Δstaked_amount = Δtotal_staked = a ∧
+    ///   total_shares = Σ shares ∧ staked_amount = 0 after unstake → delegate = default
+    #[test]
+    fn dl3_delegated_stake_accounting(
+        amount in 1u64..=1_000_000_000_000,
+        other_staked in 0u64..=1_000_000_000_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+        now in 0i64..=1_000_000,
+    ) {
+        let mut user_stake = stake_fixture(0, BASE_MULTIPLIER_BPS, 0);
+        user_stake.delegate = Pubkey::new_unique();
+        let mut config = config_fixture(other_staked, other_staked, accumulated_per_share);
+
+        let old_shares = credit_stake(&mut config, &mut user_stake, amount, None, now).unwrap();
+
+        prop_assert_eq!(old_shares, 0);
+        prop_assert_eq!(user_stake.staked_amount, amount);
+        prop_assert_eq!(config.total_staked, other_staked + amount);
+        prop_assert_eq!(config.total_shares, other_staked + user_stake.shares);
+        prop_assert_eq!(user_stake.stake_updated_at, now);
+        prop_assert_eq!(owed_reflections_scaled(&user_stake, accumulated_per_share), 0);
+
+        remove_stake(&mut config, &mut user_stake, amount, now).unwrap();
+        prop_assert_eq!(user_stake.delegate, Pubkey::default());
+        prop_assert_eq!(config.total_staked, other_staked);
+    }
+
+    /// DL-4: Only an unlocked gift skips the beneficiary's consent
+    /// INVARIANT: stake_for succeeds → (beneficiary_only ∧ ¬locked)
+    ///   ∨ signer = beneficiary
+    #[test]
+    fn dl4_beneficiary_consents_to_delegation_and_locks(
+        signer in 0u8..3, // 0 = none, 1 = beneficiary, 2 = someone else
+        beneficiary_only in any::<bool>(),
+        locked in any::<bool>(),
+    ) {
+        let beneficiary = Pubkey::new_unique();
+        let signer_key = match signer {
+            0 => None,
+            1 => Some(beneficiary),
+            _ => Some(Pubkey::new_unique()),
+        };
+
+        let result = check_beneficiary_consent(beneficiary, signer_key, beneficiary_only, locked);
+
+        if (beneficiary_only && !locked) || signer == 1 {
+            prop_assert!(result.is_ok());
+        } else {
+            prop_assert_eq!(result.unwrap_err(), KernelError::BeneficiaryNotSigner.into());
+        }
+    }
+}
+
+// ============================================================================
+// Stake Position Invariants (SP-1 through SP-2)
+// ============================================================================
+
+fn position_fixture(receipt_mint: Pubkey) -> StakePosition {
+    StakePosition {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        position_id: 0,
+        owner: Pubkey::new_unique(),
+        receipt_mint,
+        user_stake: Pubkey::new_unique(),
+        created_at: 0,
+        bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+proptest! {
+    /// SP-1: A position is controlled by its owner until it has a receipt,
+    /// then only by the receipt holder
+    /// INVARIANT: p.receipt_mint = default → holder(p) = p.owner,
+    ///   otherwise holder(p) = owner of the receipt account passed (None without one)
+    #[test]
+    fn sp1_receipt_holder_controls_position(
+        has_receipt in any::<bool>(),
+        receipt_passed in any::<bool>(),
+    ) {
+        let receipt_mint = if has_receipt { Pubkey::new_unique() } else { Pubkey::default() };
+        let position = position_fixture(receipt_mint);
+        let receipt_holder = Pubkey::new_unique();
+        let receipt_owner = receipt_passed.then_some(receipt_holder);
+
+        let holder = position_holder(&position, receipt_owner);
+
+        if has_receipt {
+            prop_assert_eq!(holder, receipt_owner);
+            prop_assert_ne!(holder, Some(position.owner));
+        } else {
+            prop_assert_eq!(holder, Some(position.owner));
+        }
+    }
+
+    /// SP-2: Changing a position's holder settles nothing; the next holder
+    /// claims everything the position accrued, before and after the move
+    /// INVARIANT: claim after a move = shares * (acc_now - acc_at_stake) / PRECISION
+    #[test]
+    fn sp2_rewards_move_with_position(
+        amount in 1u64..=1_000_000_000_000,
+        acc_at_stake in 0u128..=PRECISION * 1000,
+        acc_before_move in 0u128..=PRECISION * 1000,
+        acc_after_move in 0u128..=PRECISION * 1000,
+    ) {
+        let mut config = config_fixture(0, 0, acc_at_stake);
+        let mut position = position_fixture(Pubkey::default());
+        let mut user_stake = stake_fixture(0, BASE_MULTIPLIER_BPS, 0);
+        credit_stake(&mut config, &mut user_stake, amount, None, 0).unwrap();
+
+        config.accumulated_per_share = acc_at_stake + acc_before_move;
+        position.owner = Pubkey::new_unique();
+        config.accumulated_per_share += acc_after_move;
+
+        let claimable = settle_claimable(&mut config, &mut user_stake).unwrap();
+        let expected =
+            user_stake.shares as u128 * (acc_before_move + acc_after_move) / PRECISION;
+        prop_assert_eq!(position_holder(&position, None), Some(position.owner));
+        prop_assert_eq!(claimable as u128, expected);
+    }
+}
+
+// ============================================================================
+// Vesting Invariants (VS-1 through VS-5)
+// ============================================================================
+
+fn vesting_fixture(total_amount: u64, cliff: i64, duration: i64) -> VestingSchedule {
+    VestingSchedule {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        vesting_id: 0,
+        grantor: Pubkey::new_unique(),
+        beneficiary: Pubkey::new_unique(),
+        total_amount,
+        released_amount: 0,
+        staked_amount: 0,
+        start_time: 1_000,
+        cliff,
+        duration,
+        revocable: true,
+        revoked_at: 0,
+        unbonding_until: 0,
+        bump: 255,
+        vault_bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+proptest! {
+    /// VS-1: Vesting is zero before the cliff, monotonic, and complete at the end
+    /// INVARIANT: t < start + cliff → vested(t) = 0 ∧
+    ///   t1 ≤ t2 → vested(t1) ≤ vested(t2) ≤ total ∧
+    ///   t ≥ start + duration → vested(t) = total
+    #[test]
+    fn vs1_vesting_monotonic_and_bounded(
+        total in 1u64..=u64::MAX,
+        duration in 1i64..=10 * 365 * 24 * 60 * 60,
+        cliff_pct in 0i64..=100,
+        t1 in 0i64..=400_000_000,
+        dt in 0i64..=400_000_000,
+    ) {
+        let cliff = duration * cliff_pct / 100;
+        prop_assert!(valid_vesting(total, cliff, 1_000, duration));
+        let schedule = vesting_fixture(total, cliff, duration);
+        let t2 = t1 + dt;
+
+        let v1 = vested_amount(&schedule, t1).unwrap();
+        let v2 = vested_amount(&schedule, t2).unwrap();
+
+        prop_assert!(v1 <= v2 && v2 <= total);
+        if t1 < schedule.start_time + cliff {
+            prop_assert_eq!(v1, 0);
+        }
+        if t2 >= schedule.start_time + duration {
+            prop_assert_eq!(v2, total);
+        }
+    }
+
+    /// VS-2: Releases never exceed what has vested or what the vault holds
+    /// INVARIANT: releasable(t) ≤ vested(t) - released ∧ releasable(t) ≤ held ∧
+    ///   held + released + staked = total
+    #[test]
+    fn vs2_release_bounded(
+        total in 1u64..=1_000_000_000_000,
+        duration in 1i64..=1_000_000,
+        released_pct in 0u64..=100,
+        staked_pct in 0u64..=100,
+        now in 0i64..=2_000_000,
+    ) {
+        let mut schedule = vesting_fixture(total, 0, duration);
+        let vested = vested_amount(&schedule, now).unwrap();
+        schedule.released_amount = vested * released_pct / 100;
+        schedule.staked_amount = (total - schedule.released_amount) * staked_pct / 100;
+
+        let releasable = releasable_amount(&schedule, now).unwrap();
+        let held = vesting_held(&schedule).unwrap();
+
+        prop_assert!(releasable <= vested - schedule.released_amount);
+        prop_assert!(releasable <= held);
+        prop_assert_eq!(held + schedule.released_amount + schedule.staked_amount, total);
+    }
+
+    /// VS-3: A revoke splits the grant exactly and freezes it
+    /// INVARIANT: vested(revoke) + returned = total ∧
+    ///   ∀ t ≥ revoke: vested(t) = vested(revoke)
+    #[test]
+    fn vs3_revoke_freezes_schedule(
+        total in 1u64..=u64::MAX,
+        duration in 1i64..=1_000_000,
+        revoke_at in 1i64..=2_000_000,
+        later in 0i64..=10_000_000,
+    ) {
+        let mut schedule = vesting_fixture(total, 0, duration);
+        let vested = vested_amount(&schedule, revoke_at).unwrap();
+
+        let returned = freeze_vesting(&mut schedule, revoke_at).unwrap();
+
+        prop_assert_eq!(vested + returned, total);
+        prop_assert_eq!(schedule.total_amount, vested);
+        prop_assert_eq!(vested_amount(&schedule, revoke_at + later).unwrap(), vested);
+    }
+
+    /// VS-4: Transfer fees come out of the grant, never out of the vault
+    /// INVARIANT: any stake / unstake / release sequence with a transfer fee,
+    ///   then a revoke → held = vesting vault balance after every step ∧
+    ///   returned ≤ held ∧ vault = total - released after the revoke
+    #[test]
+    fn vs4_fees_keep_vault_and_grant_in_step(
+        total in 1u64..=1_000_000_000_000,
+        duration in 1i64..=1_000_000,
+        fee_bps in 0u64..=1_000,
+        steps in prop::collection::vec((0u8..3, 0u64..=100, 0i64..=2_000_000), 0..12),
+        revoke_at in 0i64..=2_000_000,
+    ) {
+        let fee = |amount: u64| amount * fee_bps / 10_000;
+        let mut schedule = vesting_fixture(total, 0, duration);
+        let mut vault = total;
+
+        for (kind, pct, now) in steps {
+            match kind {
+                0 => {
+                    let amount = vesting_held(&schedule).unwrap() * pct / 100;
+                    let received = amount - fee(amount);
+                    vault -= amount;
+                    charge_vesting_fee(&mut schedule, amount, received).unwrap();
+                    schedule.staked_amount += received;
+                }
+                1 => {
+                    let amount = schedule.staked_amount * pct / 100;
+                    let received = amount - fee(amount);
+                    vault += received;
+                    schedule.staked_amount -= amount;
+                    charge_vesting_fee(&mut schedule, amount, received).unwrap();
+                }
+                _ => {
+                    let amount = releasable_amount(&schedule, now).unwrap();
+                    vault -= amount;
+                    schedule.released_amount += amount;
+                }
+            }
+            prop_assert_eq!(vesting_held(&schedule).unwrap(), vault);
+        }
+
+        // Revoke pulls the stake back first
+        let staked = schedule.staked_amount;
+        let received = staked - fee(staked);
+        vault += received;
+        return_vesting_stake(&mut schedule, received).unwrap();
+
+        let returned = freeze_vesting(&mut schedule, revoke_at).unwrap();
+        prop_assert!(returned <= vault);
+        vault -= returned;
+        prop_assert_eq!(vesting_held(&schedule).unwrap(), vault);
+        prop_assert_eq!(releasable_amount(&schedule, revoke_at).unwrap(), vault);
+    }
+
+    /// VS-5: An emergency exit brings the whole stake back into the grant
+    /// INVARIANT: stake holding the schedule's s plus a gift g, vault receives
+    ///   r = (s + g) - fee → staked = 0 ∧ held' = held + r
+    #[test]
+    fn vs5_emergency_exit_rebases_grant(
+        total in 1u64..=1_000_000_000_000,
+        staked_pct in 1u64..=100,
+        gift in 0u64..=1_000_000,
+        fee_bps in 0u64..=1_000,
+    ) {
+        let mut schedule = vesting_fixture(total, 0, 1_000);
+        schedule.staked_amount = total * staked_pct / 100;
+        let held = vesting_held(&schedule).unwrap();
+
+        let sent = schedule.staked_amount + gift;
+        let received = sent - sent * fee_bps / 10_000;
+        return_vesting_stake(&mut schedule, received).unwrap();
+
+        prop_assert_eq!(schedule.staked_amount, 0);
+        prop_assert_eq!(vesting_held(&schedule).unwrap(), held + received);
+    }
 }
 
 // ============================================================================
-// Fee Configuration Invariants (FC-1, FC-2)
+// Fee Configuration Invariants (FC-1 through FC-3)
 // ============================================================================
 
 proptest! {
@@ -133,10 +894,33 @@ proptest! {
             prop_assert!(burn_bps <= TOTAL_FEE_BPS);
         }
     }
+
+    /// FC-3: Harvested fees are split exactly by the config shares
+    /// INVARIANT: split_fees(a) = (r, l, b) →
+    ///   r + l + b = a ∧ r = ⌊a * reflection_bps / 500⌋ ∧ l = ⌊a * lp_bps / 500⌋
+    #[test]
+    fn fc3_harvest_split_conserves_fees(
+        amount in 0u64..=u64::MAX,
+        reflection_bps in 0u16..=TOTAL_FEE_BPS,
+        lp_bps in 0u16..=TOTAL_FEE_BPS,
+    ) {
+        if reflection_bps + lp_bps <= TOTAL_FEE_BPS {
+            let burn_bps = TOTAL_FEE_BPS - reflection_bps - lp_bps;
+            let (reflection, lp, burn) = split_fees(amount, reflection_bps, lp_bps, burn_bps);
+
+            prop_assert_eq!(reflection as u128 + lp as u128 + burn as u128, amount as u128);
+            prop_assert_eq!(
+                reflection as u128,
+                amount as u128 * reflection_bps as u128 / TOTAL_FEE_BPS as u128
+            );
+            prop_assert_eq!(lp as u128, amount as u128 * lp_bps as u128 / TOTAL_FEE_BPS as u128);
+            prop_assert!(burn as u128 >= amount as u128 * burn_bps as u128 / TOTAL_FEE_BPS as u128);
+        }
+    }
 }
 
 // ============================================================================
-// Timelock Invariants (TL-1 through TL-4)
+// Timelock Invariants (TL-1 through TL-8)
 // ============================================================================
 
 proptest! {
@@ -160,125 +944,1272 @@ proptest! {
         }
     }
 
-    /// TL-3: Proposal single execution
-    /// INVARIANT: execute(p) succeeds → ¬p.executed_before ∧ p.executed_after
+    /// TL-3: Proposal single execution
+    /// INVARIANT: execute(p) succeeds → ¬p.executed_before ∧ p.executed_after
+    #[test]
+    fn tl3_proposal_single_execution(
+        executed_before in prop::bool::ANY,
+        attempt_execute in prop::bool::ANY,
+    ) {
+        // Simulate proposal state
+        let mut is_executed = executed_before;
+
+        if attempt_execute {
+            // Can only execute if not already executed
+            let execute_succeeds = !is_executed;
+
+            if execute_succeeds {
+                is_executed = true;
+                prop_assert!(is_executed);
+            }
+        }
+
+        // After execution attempt, if it was already executed, still executed
+        if executed_before {
+            prop_assert!(is_executed);
+        }
+    }
+
+    /// TL-4: Cancelled proposals cannot execute
+    /// INVARIANT: p.cancelled → ¬can_execute(p)
+    #[test]
+    fn tl4_cancelled_not_executable(
+        is_cancelled in prop::bool::ANY,
+        elapsed_time in 0i64..=3 * TIMELOCK_DURATION,
+    ) {
+        // Even if timelock has passed, cancelled proposals cannot execute
+        let timelock_passed = elapsed_time >= TIMELOCK_DURATION;
+        let can_execute = !is_cancelled && timelock_passed;
+
+        if is_cancelled {
+            prop_assert!(!can_execute, "TL-4: Cancelled proposals must not execute");
+        }
+    }
+}
+
+fn governance_action_fixture(proposed_at: i64, payload: GovernancePayload) -> GovernanceAction {
+    GovernanceAction {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        nonce: 0,
+        proposer: Pubkey::new_unique(),
+        payload,
+        proposed_at,
+        eta: proposed_at + TIMELOCK_DURATION,
+        expires_at: proposed_at + TIMELOCK_DURATION + EXECUTION_WINDOW,
+        executed: false,
+        cancelled: false,
+        bump: 255,
+        votes_for: 0,
+        votes_against: 0,
+        total_staked_snapshot: 0,
+        reserved: [0; 8],
+    }
+}
+
+proptest! {
+    /// TL-5: Governance actions only execute inside their window
+    /// INVARIANT: execute(a) succeeds →
+    ///   a.eta ≤ now ≤ a.expires_at ∧ ¬a.executed ∧ ¬a.cancelled
+    #[test]
+    fn tl5_action_execution_window(
+        proposed_at in 0i64..=1_000_000_000,
+        offset in 0i64..=2 * (TIMELOCK_DURATION + EXECUTION_WINDOW),
+        executed in prop::bool::ANY,
+        cancelled in prop::bool::ANY,
+    ) {
+        let mut action = governance_action_fixture(proposed_at, GovernancePayload::SetPauseFlags {
+            flags: PAUSE_ALL,
+            paused_until: 0,
+        });
+        action.executed = executed;
+        action.cancelled = cancelled;
+        let now = proposed_at + offset;
+
+        let result = check_action_executable(&action, now);
+        let window = TIMELOCK_DURATION..=TIMELOCK_DURATION + EXECUTION_WINDOW;
+        let in_window = window.contains(&offset);
+        prop_assert_eq!(result.is_ok(), in_window && !executed && !cancelled);
+        if !executed && !cancelled && offset > TIMELOCK_DURATION + EXECUTION_WINDOW {
+            prop_assert_eq!(result.unwrap_err(), KernelError::ActionExpired.into());
+        }
+    }
+
+    /// TL-6: Queued payloads are valid when they are proposed
+    /// INVARIANT: propose(UpdateFees(r, l, b)) succeeds ↔ r + l + b = 500 (no u16 wrap)
+    #[test]
+    fn tl6_fee_payload_validated(
+        reflection_share_bps in 0u16..=u16::MAX,
+        lp_share_bps in 0u16..=u16::MAX,
+        burn_share_bps in 0u16..=u16::MAX,
+    ) {
+        let payload = GovernancePayload::UpdateFees {
+            reflection_share_bps,
+            lp_share_bps,
+            burn_share_bps,
+        };
+        let total = reflection_share_bps as u32 + lp_share_bps as u32 + burn_share_bps as u32;
+        prop_assert_eq!(
+            validate_governance_payload(&payload).is_ok(),
+            total == TOTAL_FEE_BPS as u32
+        );
+
+        // A burn share picked so the u16 sum wraps around to exactly 500
+        let wrapping_burn = TOTAL_FEE_BPS
+            .wrapping_sub(reflection_share_bps)
+            .wrapping_sub(lp_share_bps);
+        prop_assert_eq!(
+            valid_fee_split(reflection_share_bps, lp_share_bps, wrapping_burn),
+            reflection_share_bps as u32 + lp_share_bps as u32 <= TOTAL_FEE_BPS as u32
+        );
+    }
+
+    /// TL-7: Each action waits out the current delay for its type
+    /// INVARIANT: propose(a) → a.eta - a.proposed_at = config.action_delays[kind(a)] ∧
+    ///   propose(SetActionDelay(k, d)) succeeds ↔ MIN_ACTION_DELAY ≤ d ≤ MAX_ACTION_DELAY
+    #[test]
+    fn tl7_per_action_delays(
+        delays in prop::array::uniform9(MIN_ACTION_DELAY..=MAX_ACTION_DELAY),
+        kind_index in 0usize..ACTION_KIND_COUNT,
+        new_delay in -MAX_ACTION_DELAY..=2 * MAX_ACTION_DELAY,
+    ) {
+        let mut config = config_fixture(0, 0, 0);
+        config.action_delays = delays;
+        let kind = ACTION_KINDS[kind_index];
+
+        prop_assert_eq!(action_delay(&config, &payload_for(kind)), delays[kind_index]);
+
+        // Unset delays on older configs fall back to 24 hours
+        let mut unset = config_fixture(0, 0, 0);
+        unset.action_delays[kind_index] = 0;
+        prop_assert_eq!(action_delay(&unset, &payload_for(kind)), TIMELOCK_DURATION);
+
+        // Changing a delay is timelocked by the delay being changed, not the new one
+        let change = GovernancePayload::SetActionDelay {
+            kind,
+            delay: new_delay,
+        };
+        prop_assert_eq!(action_delay(&config, &change), delays[kind_index]);
+        prop_assert_eq!(
+            validate_governance_payload(&change).is_ok(),
+            (MIN_ACTION_DELAY..=MAX_ACTION_DELAY).contains(&new_delay)
+        );
+    }
+
+    /// TL-8: Only actions that can never execute again can be closed
+    /// INVARIANT: close(a) succeeds ↔ a.executed ∨ a.cancelled ∨ now > a.expires_at
+    ///   ∧ close(a) succeeds → ¬can_execute(a)
+    #[test]
+    fn tl8_only_settled_actions_close(
+        proposed_at in 0i64..=1_000_000_000,
+        offset in 0i64..=2 * (TIMELOCK_DURATION + EXECUTION_WINDOW),
+        executed in prop::bool::ANY,
+        cancelled in prop::bool::ANY,
+    ) {
+        let mut action = governance_action_fixture(proposed_at, GovernancePayload::SetPauseFlags {
+            flags: PAUSE_ALL,
+            paused_until: 0,
+        });
+        action.executed = executed;
+        action.cancelled = cancelled;
+        let now = proposed_at + offset;
+
+        let settled = action_settled(&action, now);
+        let expired = offset > TIMELOCK_DURATION + EXECUTION_WINDOW;
+        prop_assert_eq!(settled, executed || cancelled || expired);
+        if settled {
+            prop_assert!(check_action_executable(&action, now).is_err());
+        }
+    }
+}
+
+/// Every ActionKind, in config.action_delays order
+const ACTION_KINDS: [ActionKind; ACTION_KIND_COUNT] = [
+    ActionKind::Fees,
+    ActionKind::Authority,
+    ActionKind::Pause,
+    ActionKind::Tiers,
+    ActionKind::Guardians,
+    ActionKind::LpWithdrawLimit,
+    ActionKind::Roles,
+    ActionKind::Unbonding,
+    ActionKind::RewardStream,
+];
+
+/// A representative payload of each action type
+fn payload_for(kind: ActionKind) -> GovernancePayload {
+    match kind {
+        ActionKind::Fees => GovernancePayload::UpdateFees {
+            reflection_share_bps: 200,
+            lp_share_bps: 200,
+            burn_share_bps: 100,
+        },
+        ActionKind::Authority => GovernancePayload::TransferAuthority {
+            new_authority: Pubkey::new_unique(),
+        },
+        ActionKind::Pause => GovernancePayload::SetPauseFlags {
+            flags: PAUSE_ALL,
+            paused_until: 0,
+        },
+        ActionKind::Tiers => GovernancePayload::UpdateTiers { tiers: vec![] },
+        ActionKind::Guardians => GovernancePayload::UpdateGuardianSet {
+            guardians: vec![Pubkey::new_unique()],
+            threshold: 1,
+        },
+        ActionKind::LpWithdrawLimit => GovernancePayload::SetLpWithdrawLimit { withdraw_limit: 0 },
+        ActionKind::Roles => GovernancePayload::RevokeRole { role: Role::Burner },
+        ActionKind::Unbonding => GovernancePayload::SetUnbondingPeriod {
+            unbonding_period: 0,
+        },
+        ActionKind::RewardStream => GovernancePayload::SetRewardStreamDuration {
+            reward_stream_duration: 0,
+        },
+    }
+}
+
+// ============================================================================
+// Governance Voting Invariants (GV-1 through GV-4)
+// ============================================================================
+
+fn fee_action_fixture(proposed_at: i64) -> GovernanceAction {
+    governance_action_fixture(
+        proposed_at,
+        GovernancePayload::UpdateFees {
+            reflection_share_bps: 300,
+            lp_share_bps: 100,
+            burn_share_bps: 100,
+        },
+    )
+}
+
+proptest! {
+    /// GV-1: Tallies are the sum of recorded stake weights
+    /// INVARIANT: votes_for + votes_against = Σ receipt.weight ∧ receipt.weight = staked_amount
+    #[test]
+    fn gv1_tally_matches_weights(
+        votes in prop::collection::vec((1u64..=1_000_000_000_000_000, prop::bool::ANY), 0..20),
+    ) {
+        let mut action = fee_action_fixture(1_000);
+        let mut expected_for = 0u64;
+        let mut expected_against = 0u64;
+
+        for (staked_amount, support) in votes {
+            let user_stake = stake_fixture(staked_amount, BASE_MULTIPLIER_BPS, 0);
+            let weight = record_vote(&mut action, &user_stake, support, 1_000).unwrap();
+            prop_assert_eq!(weight, staked_amount);
+            if support {
+                expected_for += weight;
+            } else {
+                expected_against += weight;
+            }
+        }
+
+        prop_assert_eq!(action.votes_for, expected_for);
+        prop_assert_eq!(action.votes_against, expected_against);
+    }
+
+    /// GV-2: Votes are taken only on fee changes, only before eta
+    /// INVARIANT: record_vote(a, s, now) succeeds → now < a.eta ∧ a.payload = UpdateFees
+    ///   ∧ weight = s.staked_amount when s is unchanged since a.proposed_at
+    #[test]
+    fn gv2_vote_window(
+        proposed_at in 1i64..=1_000_000_000,
+        updated_offset in 1i64..=TIMELOCK_DURATION,
+        vote_offset in 0i64..=2 * TIMELOCK_DURATION,
+        fee_payload in prop::bool::ANY,
+    ) {
+        let mut action = if fee_payload {
+            fee_action_fixture(proposed_at)
+        } else {
+            let pause = GovernancePayload::SetPauseFlags {
+                flags: PAUSE_ALL,
+                paused_until: 0,
+            };
+            governance_action_fixture(proposed_at, pause)
+        };
+        let mut user_stake = stake_fixture(1_000, BASE_MULTIPLIER_BPS, 0);
+        user_stake.stake_updated_at = proposed_at - updated_offset;
+        let now = proposed_at + vote_offset;
+
+        let result = record_vote(&mut action, &user_stake, true, now);
+        if !fee_payload {
+            prop_assert_eq!(result.unwrap_err(), KernelError::VotingNotOpen.into());
+        } else if now >= action.eta {
+            prop_assert_eq!(result.unwrap_err(), KernelError::VotingClosed.into());
+        } else {
+            prop_assert_eq!(result.unwrap(), 1_000);
+        }
+        if fee_payload && now >= action.eta {
+            prop_assert_eq!(action.votes_for, 0);
+        }
+    }
+
+    /// GV-3: Fee changes need quorum and a strict majority
+    /// INVARIANT: passed(a) ↔ for + against ≥ max(1, snapshot * QUORUM_BPS / 10000)
+    ///   ∧ for > against
+    #[test]
+    fn gv3_quorum_and_majority(
+        votes_for in 0u64..=1_000_000,
+        votes_against in 0u64..=1_000_000,
+        total_staked_snapshot in 0u64..=20_000_000,
+    ) {
+        let mut action = fee_action_fixture(0);
+        action.votes_for = votes_for;
+        action.votes_against = votes_against;
+        action.total_staked_snapshot = total_staked_snapshot;
+
+        let turnout = votes_for + votes_against;
+        let quorum = total_staked_snapshot * QUORUM_BPS / 10_000;
+        let result = check_vote_passed(&action);
+
+        if turnout == 0 || turnout < quorum {
+            prop_assert_eq!(result.unwrap_err(), KernelError::QuorumNotReached.into());
+        } else if votes_for <= votes_against {
+            prop_assert_eq!(result.unwrap_err(), KernelError::VoteNotPassed.into());
+        } else {
+            prop_assert!(result.is_ok());
+        }
+    }
+
+    /// GV-4: Vote weight is a snapshot of the stake held at proposal time
+    /// INVARIANT: weight(s, a) ≤ min(stake(s, a.proposed_at), s.staked_amount)
+    ///   and with no other vote open, weight = min(stake at proposal, staked_amount),
+    ///   which adding stake never lowers
+    #[test]
+    fn gv4_vote_weight_snapshot(
+        initial in 1u64..=1_000_000_000_000,
+        before in prop::collection::vec((1u64..=1_000_000_000_000, prop::bool::ANY), 0..4),
+        after in prop::collection::vec((1u64..=1_000_000_000_000, prop::bool::ANY), 0..6),
+        overlap in prop::bool::ANY,
+    ) {
+        let mut user_stake = stake_fixture(initial, BASE_MULTIPLIER_BPS, 0);
+        let mut config = config_fixture(initial, user_stake.shares, 0);
+        let change = |config: &mut KernelConfig, user_stake: &mut UserStake, amount, add, at| {
+            if add {
+                credit_stake(config, user_stake, amount, None, at).unwrap();
+            } else if user_stake.staked_amount > 0 {
+                let amount = amount.min(user_stake.staked_amount);
+                remove_stake(config, user_stake, amount, at).unwrap();
+            }
+        };
+
+        // An earlier vote still open when the second one is proposed
+        let earlier_at = 50;
+        if overlap {
+            config.last_vote_opened_at = earlier_at;
+            config.vote_closes_at = earlier_at + TIMELOCK_DURATION;
+        }
+        for (i, &(amount, add)) in before.iter().enumerate() {
+            change(&mut config, &mut user_stake, amount, add, earlier_at + 10 + i as i64);
+        }
+        let proposed_at = 100;
+        let at_proposal = user_stake.staked_amount;
+        config.last_vote_opened_at = proposed_at;
+        config.vote_closes_at = proposed_at + TIMELOCK_DURATION;
+
+        let mut weight = vote_weight(&user_stake, proposed_at);
+        prop_assert_eq!(weight, at_proposal);
+        for (i, &(amount, add)) in after.iter().enumerate() {
+            change(&mut config, &mut user_stake, amount, add, proposed_at + i as i64);
+
+            let next = vote_weight(&user_stake, proposed_at);
+            prop_assert!(next <= at_proposal.min(user_stake.staked_amount));
+            if overlap {
+                let earlier = vote_weight(&user_stake, earlier_at);
+                prop_assert!(earlier <= initial.min(user_stake.staked_amount));
+            } else {
+                prop_assert_eq!(next, at_proposal.min(user_stake.staked_amount));
+                if add {
+                    prop_assert!(next >= weight);
+                }
+            }
+            weight = next;
+        }
+    }
+}
+
+// ============================================================================
+// Reflection Invariants (RF-1 through RF-6)
+// ============================================================================
+
+proptest! {
+    /// RF-3: Accumulated per share monotonicity
+    /// INVARIANT: accumulated_per_share never decreases
+    #[test]
+    fn rf3_accumulated_per_share_monotonic(
+        initial_acc in 0u128..=u128::MAX / 2,
+        reflection_amount in 0u64..=1_000_000_000_000,
+        total_staked in 1u64..=u64::MAX,
+    ) {
+        // Calculate new accumulated per share
+        let addition = if total_staked > 0 {
+            (reflection_amount as u128)
+                .saturating_mul(PRECISION)
+                .checked_div(total_staked as u128)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let new_acc = initial_acc.saturating_add(addition);
+
+        // Must be monotonically increasing
+        prop_assert!(new_acc >= initial_acc, "RF-3: Accumulated per share must not decrease");
+    }
+
+    /// RF-5: Pending rewards calculation
+    /// INVARIANT: pending(u) = (staked * accumulated_per_share / PRECISION) - reward_debt
+    #[test]
+    fn rf5_pending_rewards_calculation(
+        staked_amount in 1u64..=1_000_000_000_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+        reward_debt in 0u128..=PRECISION * 1_000_000,
+    ) {
+        // Calculate pending rewards as per the invariant
+        let gross_reward = (staked_amount as u128)
+            .saturating_mul(accumulated_per_share)
+            .checked_div(PRECISION)
+            .unwrap_or(0);
+
+        let pending = gross_reward.saturating_sub(reward_debt);
+
+        // Pending rewards should be non-negative
+        prop_assert!(pending >= 0);
+
+        // Verify calculation is consistent
+        let recalculated = (staked_amount as u128 * accumulated_per_share / PRECISION)
+            .saturating_sub(reward_debt);
+        prop_assert_eq!(pending, recalculated);
+    }
+
+    /// RF-2: Reward debt consistency on stake changes
+    /// INVARIANT: reward_debt = staked_amount * accumulated_per_share / PRECISION
+    #[test]
+    fn rf2_reward_debt_consistency(
+        staked_amount in 1u64..=1_000_000_000_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+    ) {
+        // When staking, reward_debt should be set to current accumulated value
+        let expected_reward_debt = (staked_amount as u128)
+            .saturating_mul(accumulated_per_share)
+            .checked_div(PRECISION)
+            .unwrap_or(0);
+
+        // Simulated reward_debt after stake
+        let reward_debt = expected_reward_debt;
+
+        prop_assert_eq!(reward_debt, expected_reward_debt);
+    }
+
+    /// RF-6: Rewards are split by boosted shares
+    /// INVARIANT: ∀ stakers u, v with equal staked amounts:
+    ///   multiplier(u) ≥ multiplier(v) → pending(u) ≥ pending(v)
+    #[test]
+    fn rf6_rewards_follow_shares(
+        staked_amount in 1u64..=1_000_000_000_000,
+        boosted_bps in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        deposit in 1u64..=1_000_000_000_000,
+    ) {
+        let boosted_shares = calculate_shares(staked_amount, boosted_bps).unwrap();
+        let base_shares = calculate_shares(staked_amount, BASE_MULTIPLIER_BPS).unwrap();
+        let total_shares = boosted_shares as u128 + base_shares as u128;
+
+        let accumulated_per_share = (deposit as u128) * PRECISION / total_shares;
+        let boosted_pending = boosted_shares as u128 * accumulated_per_share / PRECISION;
+        let base_pending = base_shares as u128 * accumulated_per_share / PRECISION;
+
+        prop_assert!(boosted_pending >= base_pending);
+        prop_assert!(boosted_pending + base_pending <= deposit as u128);
+    }
+}
+
+// ============================================================================
+// Compounding Invariants (CP-1 through CP-3)
+// ============================================================================
+
+fn config_fixture(
+    total_staked: u64,
+    total_shares: u64,
+    accumulated_per_share: u128,
+) -> KernelConfig {
+    KernelConfig {
+        version: ACCOUNT_VERSION,
+        authority: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        staking_vault: Pubkey::new_unique(),
+        reflection_pool: Pubkey::new_unique(),
+        reflection_share_bps: 200,
+        lp_share_bps: 200,
+        burn_share_bps: 100,
+        total_staked,
+        total_shares,
+        total_reflections_distributed: 0,
+        pending_reflections: 0,
+        accumulated_per_share,
+        pause_flags: 0,
+        unbonding_period: 0,
+        total_unbonding: 0,
+        bump: 255,
+        vault_bump: 255,
+        reward_pool_count: 0,
+        reward_stream_duration: 0,
+        reward_rate: 0,
+        reward_period_end: 0,
+        last_update_time: 0,
+        governance_action_count: 0,
+        action_delays: [TIMELOCK_DURATION; ACTION_KIND_COUNT],
+        paused_until: 0,
+        reflection_surplus: 0,
+        reflection_deficit: 0,
+        reflection_owed: 0,
+        undistributed_reflections: 0,
+        reflection_remainder: 0,
+        position_count: 0,
+        vesting_count: 0,
+        last_vote_opened_at: 0,
+        vote_closes_at: 0,
+        reserved: [0; 55],
+    }
+}
+
+fn stake_fixture(staked_amount: u64, lock_multiplier_bps: u16, lock_until: i64) -> UserStake {
+    UserStake {
+        version: ACCOUNT_VERSION,
+        owner: Pubkey::new_unique(),
+        staked_amount,
+        stake_time: 0,
+        pending_rewards: 0,
+        total_claimed: 0,
+        reward_debt: 0,
+        shares: calculate_shares(staked_amount, lock_multiplier_bps).unwrap(),
+        lock_multiplier_bps,
+        lock_until,
+        unbonding_nonce: 0,
+        unbonding_amount: 0,
+        auto_compound: false,
+        compound_tip_bps: 0,
+        bump: 255,
+        stake_updated_at: 0,
+        delegate: Pubkey::default(),
+        reflection_owed: 0,
+        reward_fraction: 0,
+        vote_checkpoint_at: 0,
+        vote_checkpoint_amount: 0,
+        vote_checkpoint_expires: 0,
+        reserved: [0; 48],
+    }
+}
+
+/// Reflections owed to `user_stake` since its last settlement, PRECISION-scaled
+fn owed_reflections_scaled(user_stake: &UserStake, accumulated_per_share: u128) -> u128 {
+    user_stake.shares as u128 * accumulated_per_share + user_stake.reward_fraction as u128
+        - user_stake.reward_debt * PRECISION
+}
+
+proptest! {
+    /// CP-1: Crank tip is capped and nothing is created or lost in the split
+    /// INVARIANT: split_crank_tip(a, t) = (tip, c) →
+    ///   tip + c = a ∧ tip ≤ a * MAX_CRANK_TIP_BPS / 10000
+    #[test]
+    fn cp1_crank_tip_capped(
+        amount in 0u64..=u64::MAX,
+        tip_bps in 0u16..=u16::MAX,
+    ) {
+        let (tip, compounded) = split_crank_tip(amount, tip_bps);
+
+        prop_assert_eq!(tip as u128 + compounded as u128, amount as u128);
+        prop_assert!(tip as u128 <= amount as u128 * MAX_CRANK_TIP_BPS as u128 / 10_000);
+    }
+
+    /// CP-2: Compounding leaves reward_debt consistent with the new shares
+    /// INVARIANT: after compound_reflections, pending(u) = 0 and
+    ///   shares * accumulated_per_share + reward_fraction - reward_debt * PRECISION < PRECISION
+    #[test]
+    fn cp2_compound_settles_position(
+        staked_amount in 1u64..=1_000_000_000_000,
+        multiplier_bps in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        lock_until in 0i64..=2_000,
+        current_time in 0i64..=2_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, multiplier_bps, lock_until);
+        let mut config = config_fixture(staked_amount, user_stake.shares, accumulated_per_share);
+
+        let claimable = settle_claimable(&mut config, &mut user_stake).unwrap();
+        add_compounded_stake(&mut config, &mut user_stake, claimable, current_time).unwrap();
+
+        // Only the sub-unit fraction stays owed, carried to the next claim
+        prop_assert!(owed_reflections_scaled(&user_stake, accumulated_per_share) < PRECISION);
+        prop_assert_eq!(user_stake.pending_rewards, 0);
+    }
+
+    /// CP-3: Compounded rewards enter stake and totals exactly once
+    /// INVARIANT: compound(u) moving c tokens →
+    ///   Δstaked_amount = Δtotal_staked = c ∧ total_shares = Σ shares
+    ///   ∧ stake_updated_at = t
+    #[test]
+    fn cp3_compound_updates_totals(
+        staked_amount in 1u64..=1_000_000_000_000,
+        other_staked in 0u64..=1_000_000_000_000,
+        multiplier_bps in BASE_MULTIPLIER_BPS..=MAX_MULTIPLIER_BPS,
+        lock_until in 0i64..=2_000,
+        current_time in 0i64..=2_000,
+        accumulated_per_share in 0u128..=PRECISION * 1000,
+    ) {
+        let mut user_stake = stake_fixture(staked_amount, multiplier_bps, lock_until);
+        let mut config = config_fixture(
+            staked_amount + other_staked,
+            user_stake.shares + other_staked,
+            accumulated_per_share,
+        );
+
+        let claimable = settle_claimable(&mut config, &mut user_stake).unwrap();
+        add_compounded_stake(&mut config, &mut user_stake, claimable, current_time).unwrap();
+
+        prop_assert_eq!(user_stake.staked_amount, staked_amount + claimable);
+        prop_assert_eq!(config.total_staked, staked_amount + other_staked + claimable);
+        prop_assert_eq!(config.total_shares, user_stake.shares + other_staked);
+        prop_assert_eq!(user_stake.total_claimed, claimable);
+        // Compounded stake is new stake: it does not vote on earlier proposals
+        prop_assert_eq!(user_stake.stake_updated_at, current_time);
+    }
+}
+
+// ============================================================================
+// Solvency Invariants (SV-1 through SV-5)
+// ============================================================================
+
+/// A settled claim of `claimable` against a config owing `liability`
+fn claim_fixture(claimable: u64, liability: u64) -> (KernelConfig, UserStake) {
+    let mut config = config_fixture(0, 0, 0);
+    config.pending_reflections = liability;
+    let mut user_stake = stake_fixture(0, BASE_MULTIPLIER_BPS, 0);
+    user_stake.total_claimed = claimable;
+    (config, user_stake)
+}
+
+proptest! {
+    /// SV-1: A solvent pool pays every claim in full
+    /// INVARIANT: balance ≥ pending_reflections → paid = claimable ∧
+    ///   Δpending_reflections = -claimable
+    #[test]
+    fn sv1_solvent_pool_pays_in_full(
+        claimable in 1u64..=1_000_000_000_000_000,
+        other in 0u64..=1_000_000_000_000_000,
+        spare in 0u64..=1_000_000_000_000_000,
+    ) {
+        let (mut config, mut user_stake) = claim_fixture(claimable, claimable + other);
+
+        let (paid, deferred) =
+            draw_reflections(&mut config, &mut user_stake, claimable, claimable + other + spare)
+                .unwrap();
+
+        prop_assert_eq!(paid, claimable);
+        prop_assert_eq!(deferred, 0);
+        prop_assert_eq!(config.pending_reflections, other);
+        prop_assert_eq!(config.total_reflections_distributed, claimable);
+        prop_assert_eq!(user_stake.total_claimed, claimable);
+    }
+
+    /// SV-2: A shortfall is shared pro-rata, never worsens the ratio, and stays owed
+    /// INVARIANT: 0 < balance < L → paid + deferred = claimable ∧ Δowed = deferred ∧
+    ///   paid ≤ claimable * balance / L ∧
+    ///   (balance - paid) * L ≥ balance * (L - claimable)
+    #[test]
+    fn sv2_shortfall_paid_pro_rata(
+        claimable in 1u64..=1_000_000_000_000_000,
+        other in 0u64..=1_000_000_000_000_000,
+        balance_bps in 1u64..10_000,
+    ) {
+        let liability = claimable + other;
+        let balance = (liability as u128 * balance_bps as u128 / 10_000).max(1) as u64;
+        let (mut config, mut user_stake) = claim_fixture(claimable, liability);
+
+        let (paid, deferred) =
+            draw_reflections(&mut config, &mut user_stake, claimable, balance).unwrap();
+
+        if paid == 0 {
+            // Rounds to nothing: the claim stays owed
+            prop_assert_eq!(user_stake.pending_rewards, claimable);
+            prop_assert_eq!(config.pending_reflections, liability);
+            return Ok(());
+        }
+        prop_assert_eq!(paid + deferred, claimable);
+        prop_assert!(paid as u128 * liability as u128 <= claimable as u128 * balance as u128);
+        prop_assert!(
+            (balance - paid) as u128 * liability as u128
+                >= balance as u128 * (liability - claimable) as u128
+        );
+        prop_assert_eq!(config.pending_reflections, other);
+        prop_assert_eq!(config.reflection_owed, deferred);
+        prop_assert_eq!(user_stake.reflection_owed, deferred);
+        prop_assert_eq!(user_stake.total_claimed, paid);
+    }
+
+    /// SV-3: An empty pool pays nothing and defers nothing
+    /// INVARIANT: balance = 0 → paid = 0 ∧ claim re-credited to pending_rewards
+    #[test]
+    fn sv3_empty_pool_keeps_claim_owed(
+        claimable in 1u64..=u64::MAX / 2,
+        other in 0u64..=u64::MAX / 2,
+    ) {
+        let (mut config, mut user_stake) = claim_fixture(claimable, claimable + other);
+
+        let drawn = draw_reflections(&mut config, &mut user_stake, claimable, 0).unwrap();
+
+        prop_assert_eq!(drawn, (0, 0));
+        prop_assert_eq!(user_stake.pending_rewards, claimable);
+        prop_assert_eq!(user_stake.total_claimed, 0);
+        prop_assert_eq!(config.pending_reflections, claimable + other);
+        prop_assert_eq!(config.reflection_owed, 0);
+    }
+
+    /// SV-4: Sync records exactly the gap between balance and liability
+    /// INVARIANT: L = pending_reflections + reflection_owed →
+    ///   surplus = max(balance - L, 0) ∧ deficit = max(L - balance, 0)
+    #[test]
+    fn sv4_sync_records_gap(
+        balance in prop::num::u64::ANY,
+        pending in 0u64..=u64::MAX / 2,
+        owed in 0u64..=u64::MAX / 2,
+    ) {
+        let liability = pending + owed;
+        let mut config = config_fixture(0, 0, 0);
+        config.pending_reflections = pending;
+        config.reflection_owed = owed;
+
+        reconcile_reflection_pool(&mut config, balance);
+
+        prop_assert_eq!(
+            balance as i128 - liability as i128,
+            config.reflection_surplus as i128 - config.reflection_deficit as i128
+        );
+        prop_assert!(config.reflection_surplus == 0 || config.reflection_deficit == 0);
+    }
+
+    /// SV-5: A deferred claim is repaid from the surplus once the pool recovers,
+    /// and never out of what current claims are owed
+    /// INVARIANT: paid = min(owed(u), max(balance - pending_reflections, 0)) ∧
+    ///   Δowed(u) = Δreflection_owed = -paid ∧ Δtotal_claimed(u) = paid
+    #[test]
+    fn sv5_deferred_claims_repaid_from_surplus(
+        owed in 1u64..=1_000_000_000_000_000,
+        other_owed in 0u64..=1_000_000_000_000_000,
+        pending in 0u64..=1_000_000_000_000_000,
+        balance in 0u64..=3_000_000_000_000_000,
+    ) {
+        let (mut config, mut user_stake) = claim_fixture(0, pending);
+        config.reflection_owed = owed + other_owed;
+        user_stake.reflection_owed = owed;
+
+        let (paid, deferred) =
+            draw_reflections(&mut config, &mut user_stake, 0, balance).unwrap();
+
+        let expected = owed.min(balance.saturating_sub(pending));
+        prop_assert_eq!(paid, expected);
+        prop_assert_eq!(deferred, 0);
+        prop_assert_eq!(user_stake.reflection_owed, owed - paid);
+        prop_assert_eq!(config.reflection_owed, owed + other_owed - paid);
+        prop_assert_eq!(user_stake.total_claimed, paid);
+        prop_assert_eq!(config.pending_reflections, pending);
+    }
+}
+
+// ============================================================================
+// Dust Invariants (DU-1 through DU-3)
+// ============================================================================
+
+proptest! {
+    /// DU-1: Every deposited reflection is claimed or still owed, exactly
+    /// INVARIANT: deposits before and after stake exists, stakers claiming,
+    ///   adding or removing stake at any point in between →
+    ///   Σ (total_claimed + pending_rewards) * PRECISION
+    ///   + Σ (shares * acc + reward_fraction - reward_debt * PRECISION)
+    ///   + reflection_remainder + undistributed_reflections * PRECISION
+    ///   = Σ deposits * PRECISION
+    #[test]
+    fn du1_claimable_plus_dust_is_deposited(
+        stakes in prop::collection::vec(1u64..=1_000_000_000_000, 1..8),
+        early in prop::collection::vec(0u64..=1_000_000_000_000, 0..4),
+        steps in prop::collection::vec(
+            (0u64..=1_000_000_000_000, any::<u8>(), 0u8..3, 1u64..=1_000_000_000_000),
+            1..8,
+        ),
+    ) {
+        let mut config = config_fixture(0, 0, 0);
+        for &amount in &early {
+            record_reflection_deposit(&mut config, amount).unwrap();
+        }
+        // Nothing was credited yet, so the stakers join with zero debt
+        prop_assert_eq!(config.accumulated_per_share, 0);
+        let mut user_stakes: Vec<UserStake> = stakes
+            .iter()
+            .map(|&amount| stake_fixture(amount, BASE_MULTIPLIER_BPS, 0))
+            .collect();
+        config.total_staked = stakes.iter().sum();
+        config.total_shares = user_stakes.iter().map(|u| u.shares).sum();
+
+        for &(amount, mask, action, delta) in &steps {
+            record_reflection_deposit(&mut config, amount).unwrap();
+            for (i, user_stake) in user_stakes.iter_mut().enumerate() {
+                if mask & (1 << i) == 0 {
+                    continue;
+                }
+                match action {
+                    0 => {
+                        settle_claimable(&mut config, user_stake).unwrap();
+                    }
+                    1 => {
+                        credit_stake(&mut config, user_stake, delta, None, 0).unwrap();
+                    }
+                    _ if user_stake.staked_amount > 0 => {
+                        let amount = delta.min(user_stake.staked_amount);
+                        remove_stake(&mut config, user_stake, amount, 0).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for user_stake in user_stakes.iter_mut() {
+            settle_claimable(&mut config, user_stake).unwrap();
+            // Whatever is still owed is below one base unit
+            prop_assert!(
+                owed_reflections_scaled(user_stake, config.accumulated_per_share) < PRECISION
+            );
+        }
+
+        let deposited: u128 = early
+            .iter()
+            .chain(steps.iter().map(|(d, _, _, _)| d))
+            .map(|&d| d as u128)
+            .sum();
+        let accounted: u128 = user_stakes
+            .iter()
+            .map(|u| {
+                (u.total_claimed as u128 + u.pending_rewards as u128) * PRECISION
+                    + owed_reflections_scaled(u, config.accumulated_per_share)
+            })
+            .sum();
+        prop_assert_eq!(
+            accounted
+                + config.reflection_remainder as u128
+                + config.undistributed_reflections as u128 * PRECISION,
+            deposited * PRECISION
+        );
+    }
+
+    /// DU-2: A finished stream leaves no dust behind the rate
+    /// INVARIANT: constant shares S, any accrual checkpoints, after reward_period_end:
+    ///   S * accumulated_per_share + reflection_remainder = a * PRECISION
+    #[test]
+    fn du2_stream_rounding_is_carried(
+        total_shares in 1u64..=1_000_000_000_000_000,
+        duration in 1i64..=30 * 24 * 60 * 60,
+        amount in 0u64..=1_000_000_000_000_000,
+        start in 0i64..=1_000_000,
+        mut checkpoints in prop::collection::vec(0i64..=30 * 24 * 60 * 60, 0..6),
+        overshoot in 0i64..=1_000_000,
+    ) {
+        let mut config = streaming_config_fixture(total_shares, duration);
+
+        distribute_reflections(&mut config, amount, start).unwrap();
+        checkpoints.sort();
+        for offset in checkpoints {
+            accrue_reflection_stream(&mut config, start + offset).unwrap();
+        }
+        accrue_reflection_stream(&mut config, start + duration + overshoot).unwrap();
+
+        prop_assert_eq!(
+            total_shares as u128 * config.accumulated_per_share
+                + config.reflection_remainder as u128,
+            amount as u128 * PRECISION
+        );
+    }
+
+    /// DU-3: Deposits made with nobody staked reach the first stakers
+    /// INVARIANT: total_shares = 0 at deposit → deposit held in undistributed_reflections
+    ///   ∧ after the next accrual with stake S: claimable ≥ deposits - 1
+    #[test]
+    fn du3_empty_pool_deposits_carried_forward(
+        early in prop::collection::vec(1u64..=1_000_000_000_000, 1..4),
+        total_shares in 1u64..=1_000_000_000_000,
+    ) {
+        let mut config = config_fixture(0, 0, 0);
+        for &amount in &early {
+            record_reflection_deposit(&mut config, amount).unwrap();
+        }
+        let deposited: u64 = early.iter().sum();
+        prop_assert_eq!(config.undistributed_reflections, deposited);
+        prop_assert_eq!(config.accumulated_per_share, 0);
+
+        config.total_shares = total_shares;
+        accrue_reflection_stream(&mut config, 0).unwrap();
+
+        let claimable =
+            calculate_pending_rewards(total_shares, config.accumulated_per_share, 0).unwrap();
+        prop_assert_eq!(config.undistributed_reflections, 0);
+        prop_assert!(claimable <= deposited);
+        prop_assert!(claimable + 1 >= deposited);
+        prop_assert_eq!(config.pending_reflections, deposited);
+    }
+}
+
+// ============================================================================
+// Streaming Invariants (SR-1 through SR-4)
+// ============================================================================
+
+fn streaming_config_fixture(total_shares: u64, reward_stream_duration: i64) -> KernelConfig {
+    let mut config = config_fixture(total_shares, total_shares, 0);
+    config.reward_stream_duration = reward_stream_duration;
+    config
+}
+
+proptest! {
+    /// SR-1: A finished stream pays out its deposit, never more
+    /// INVARIANT: with constant shares S, after reward_period_end:
+    ///   a - (d + S) / PRECISION - 1 ≤ S * acc / PRECISION ≤ a
+    #[test]
+    fn sr1_stream_pays_out_deposit(
+        total_shares in 1u64..=1_000_000_000_000_000,
+        duration in 1i64..=30 * 24 * 60 * 60,
+        amount in 0u64..=1_000_000_000_000_000,
+        start in 0i64..=1_000_000,
+        overshoot in 0i64..=1_000_000,
+    ) {
+        let mut config = streaming_config_fixture(total_shares, duration);
+
+        distribute_reflections(&mut config, amount, start).unwrap();
+        accrue_reflection_stream(&mut config, start + duration + overshoot).unwrap();
+
+        let paid =
+            calculate_pending_rewards(total_shares, config.accumulated_per_share, 0).unwrap();
+        let rounding = (duration as u128 + total_shares as u128) / PRECISION + 1;
+        prop_assert!(paid <= amount);
+        prop_assert!(paid as u128 + rounding >= amount as u128);
+        prop_assert_eq!(config.pending_reflections, amount);
+    }
+
+    /// SR-2: Nothing is credited at deposit time (no sandwich window)
+    /// INVARIANT: deposit at t → acc(t) unchanged ∧
+    ///   emitted(t, t + k) ≤ a * k / d
     #[test]
-    fn tl3_proposal_single_execution(
-        executed_before in prop::bool::ANY,
-        attempt_execute in prop::bool::ANY,
+    fn sr2_deposit_streams_linearly(
+        total_shares in 1u64..=1_000_000_000_000,
+        duration in 1i64..=30 * 24 * 60 * 60,
+        amount in 0u64..=1_000_000_000_000,
+        start in 0i64..=1_000_000,
+        elapsed_bps in 0i64..=10_000,
     ) {
-        // Simulate proposal state
-        let mut is_executed = executed_before;
+        let mut config = streaming_config_fixture(total_shares, duration);
 
-        if attempt_execute {
-            // Can only execute if not already executed
-            let execute_succeeds = !is_executed;
+        distribute_reflections(&mut config, amount, start).unwrap();
+        prop_assert_eq!(config.accumulated_per_share, 0);
 
-            if execute_succeeds {
-                is_executed = true;
-                prop_assert!(is_executed);
-            }
-        }
+        let elapsed = duration * elapsed_bps / 10_000;
+        accrue_reflection_stream(&mut config, start + elapsed).unwrap();
 
-        // After execution attempt, if it was already executed, still executed
-        if executed_before {
-            prop_assert!(is_executed);
-        }
+        let emitted =
+            calculate_pending_rewards(total_shares, config.accumulated_per_share, 0).unwrap();
+        prop_assert!(emitted as u128 <= amount as u128 * elapsed as u128 / duration as u128);
     }
 
-    /// TL-4: Cancelled proposals cannot execute
-    /// INVARIANT: p.cancelled → ¬can_execute(p)
+    /// SR-3: Accrual does not depend on how often it is triggered
+    /// INVARIANT: accrue(t1); accrue(t2) ≤ accrue(t2) ≤ accrue(t1); accrue(t2) + 1
     #[test]
-    fn tl4_cancelled_not_executable(
-        is_cancelled in prop::bool::ANY,
-        elapsed_time in 0i64..=3 * TIMELOCK_DURATION,
+    fn sr3_accrual_path_independent(
+        total_shares in 1u64..=1_000_000_000_000,
+        duration in 1i64..=30 * 24 * 60 * 60,
+        amount in 0u64..=1_000_000_000_000,
+        t1 in 0i64..=3_000_000,
+        t2 in 0i64..=3_000_000,
     ) {
-        // Even if timelock has passed, cancelled proposals cannot execute
-        let timelock_passed = elapsed_time >= TIMELOCK_DURATION;
-        let can_execute = !is_cancelled && timelock_passed;
+        let (t1, t2) = (t1.min(t2), t1.max(t2));
+        let mut stepped = streaming_config_fixture(total_shares, duration);
+        distribute_reflections(&mut stepped, amount, 0).unwrap();
+        let mut direct = stepped.clone();
 
-        if is_cancelled {
-            prop_assert!(!can_execute, "TL-4: Cancelled proposals must not execute");
-        }
+        accrue_reflection_stream(&mut stepped, t1).unwrap();
+        accrue_reflection_stream(&mut stepped, t2).unwrap();
+        accrue_reflection_stream(&mut direct, t2).unwrap();
+
+        prop_assert!(stepped.accumulated_per_share <= direct.accumulated_per_share);
+        prop_assert!(direct.accumulated_per_share <= stepped.accumulated_per_share + 1);
+    }
+
+    /// SR-4: Idle time and re-deposits never drop streamed rewards
+    /// INVARIANT: total_shares = 0 over [t, t + k] → period end moves back by k;
+    ///   re-deposit mid-stream → new_rate * d ≥ leftover + a * PRECISION - d
+    #[test]
+    fn sr4_stream_carries_forward(
+        duration in 1i64..=30 * 24 * 60 * 60,
+        first in 0u64..=1_000_000_000_000,
+        second in 0u64..=1_000_000_000_000,
+        idle in 0i64..=1_000_000,
+        elapsed_bps in 0i64..=10_000,
+    ) {
+        let mut config = streaming_config_fixture(0, duration);
+        distribute_reflections(&mut config, first, 0).unwrap();
+
+        accrue_reflection_stream(&mut config, idle).unwrap();
+        let idle_applied = idle.min(duration);
+        prop_assert_eq!(config.reward_period_end, duration + idle_applied);
+        prop_assert_eq!(config.accumulated_per_share, 0);
+
+        let now = idle_applied + duration * elapsed_bps / 10_000;
+        accrue_reflection_stream(&mut config, now).unwrap();
+        let leftover = config.reward_rate
+            * (config.reward_period_end - now.min(config.reward_period_end)) as u128;
+
+        distribute_reflections(&mut config, second, now).unwrap();
+        prop_assert!(
+            config.reward_rate * duration as u128 + duration as u128
+                >= leftover + second as u128 * PRECISION
+        );
+        prop_assert_eq!(config.reward_period_end, now + duration);
     }
 }
 
 // ============================================================================
-// Reflection Invariants (RF-1 through RF-5)
+// Reward Pool Invariants (MR-1 through MR-3)
 // ============================================================================
 
+fn reward_pool_fixture(accumulated_per_share: u128) -> RewardPool {
+    RewardPool {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        reward_mint: Pubkey::new_unique(),
+        reward_vault: Pubkey::new_unique(),
+        accumulated_per_share,
+        total_funded: 0,
+        total_claimed: 0,
+        bump: 255,
+        vault_bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+fn reward_state_fixture(reward_debt: u128) -> UserRewardState {
+    UserRewardState {
+        version: ACCOUNT_VERSION,
+        owner: Pubkey::new_unique(),
+        user_stake: Pubkey::new_unique(),
+        reward_pool: Pubkey::new_unique(),
+        reward_debt,
+        pending_rewards: 0,
+        total_claimed: 0,
+        bump: 255,
+        reserved: [0; 32],
+    }
+}
+
 proptest! {
-    /// RF-3: Accumulated per share monotonicity
-    /// INVARIANT: accumulated_per_share never decreases
+    /// MR-1: A reward pool never pays out more than it was funded
+    /// INVARIANT: Σ claims(pool) ≤ pool.total_funded ∧
+    ///   claim(u) ≥ d * shares(u) / total_shares - 1
     #[test]
-    fn rf3_accumulated_per_share_monotonic(
-        initial_acc in 0u128..=u128::MAX / 2,
-        reflection_amount in 0u64..=1_000_000_000_000,
-        total_staked in 1u64..=u64::MAX,
+    fn mr1_pool_payouts_bounded(
+        shares_a in 1u64..=1_000_000_000_000,
+        shares_b in 1u64..=1_000_000_000_000,
+        deposit in 0u64..=1_000_000_000_000,
     ) {
-        // Calculate new accumulated per share
-        let addition = if total_staked > 0 {
-            (reflection_amount as u128)
-                .saturating_mul(PRECISION)
-                .checked_div(total_staked as u128)
-                .unwrap_or(0)
-        } else {
-            0
-        };
+        let mut pool = reward_pool_fixture(0);
+        let mut state_a = reward_state_fixture(0);
+        let mut state_b = reward_state_fixture(0);
 
-        let new_acc = initial_acc.saturating_add(addition);
+        record_reward_deposit(&mut pool, shares_a + shares_b, deposit).unwrap();
 
-        // Must be monotonically increasing
-        prop_assert!(new_acc >= initial_acc, "RF-3: Accumulated per share must not decrease");
+        let claim_a = settle_reward_claimable(&mut pool, &mut state_a, shares_a).unwrap();
+        let claim_b = settle_reward_claimable(&mut pool, &mut state_b, shares_b).unwrap();
+
+        let total = (shares_a + shares_b) as u128;
+        prop_assert!(claim_a + claim_b <= pool.total_funded);
+        prop_assert_eq!(pool.total_claimed, claim_a + claim_b);
+        prop_assert!(claim_a as u128 + 1 >= deposit as u128 * shares_a as u128 / total);
+        prop_assert!(claim_b as u128 + 1 >= deposit as u128 * shares_b as u128 / total);
     }
 
-    /// RF-5: Pending rewards calculation
-    /// INVARIANT: pending(u) = (staked * accumulated_per_share / PRECISION) - reward_debt
+    /// MR-2: Syncing around a share change keeps accrued pool rewards
+    /// INVARIANT: rewards before the change accrue on old shares,
+    ///   rewards after it accrue on new shares
     #[test]
-    fn rf5_pending_rewards_calculation(
-        staked_amount in 1u64..=1_000_000_000_000,
+    fn mr2_share_change_sync(
+        old_shares in 0u64..=1_000_000_000_000,
+        new_shares in 0u64..=1_000_000_000_000,
+        acc_before in 0u128..=PRECISION * 1000,
+        acc_growth in 0u128..=PRECISION * 1000,
+    ) {
+        let mut state = reward_state_fixture(0);
+        settle_reward_state(&mut state, acc_before, old_shares, new_shares).unwrap();
+
+        let mut pool = reward_pool_fixture(acc_before + acc_growth);
+        let claimed = settle_reward_claimable(&mut pool, &mut state, new_shares).unwrap();
+
+        let expected = calculate_pending_rewards(old_shares, acc_before, 0).unwrap()
+            + calculate_pending_rewards(
+                new_shares,
+                acc_before + acc_growth,
+                calculate_reward_debt(new_shares, acc_before).unwrap(),
+            ).unwrap();
+        prop_assert_eq!(claimed, expected);
+        prop_assert_eq!(state.pending_rewards, 0);
+    }
+
+    /// MR-3: A state opened late is owed everything since the pool was added
+    /// INVARIANT: shares unchanged since pool creation ∧ debt = 0 →
+    ///   claim = shares * accumulated_per_share / PRECISION
+    #[test]
+    fn mr3_late_state_owed_full_accrual(
+        shares in 0u64..=1_000_000_000_000,
         accumulated_per_share in 0u128..=PRECISION * 1000,
-        reward_debt in 0u128..=PRECISION * 1_000_000,
     ) {
-        // Calculate pending rewards as per the invariant
-        let gross_reward = (staked_amount as u128)
-            .saturating_mul(accumulated_per_share)
-            .checked_div(PRECISION)
-            .unwrap_or(0);
+        let mut pool = reward_pool_fixture(accumulated_per_share);
+        let mut state = reward_state_fixture(0);
 
-        let pending = gross_reward.saturating_sub(reward_debt);
+        let claimed = settle_reward_claimable(&mut pool, &mut state, shares).unwrap();
 
-        // Pending rewards should be non-negative
-        prop_assert!(pending >= 0);
+        prop_assert_eq!(
+            claimed as u128,
+            shares as u128 * accumulated_per_share / PRECISION
+        );
+    }
+}
 
-        // Verify calculation is consistent
-        let recalculated = (staked_amount as u128 * accumulated_per_share / PRECISION)
-            .saturating_sub(reward_debt);
-        prop_assert_eq!(pending, recalculated);
+// ============================================================================
+// Migration Invariants (MG-1 through MG-3)
+// ============================================================================
+
+fn legacy_config_fixture(
+    total_staked: u64,
+    pending_reflections: u64,
+    accumulated_per_share: u128,
+) -> LegacyKernelConfig {
+    LegacyKernelConfig {
+        authority: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        staking_vault: Pubkey::new_unique(),
+        reflection_pool: Pubkey::new_unique(),
+        reflection_share_bps: 200,
+        lp_share_bps: 200,
+        burn_share_bps: 100,
+        total_staked,
+        total_reflections_distributed: 0,
+        pending_reflections,
+        accumulated_per_share,
+        is_paused: false,
+        bump: 255,
+        vault_bump: 255,
     }
+}
 
-    /// RF-2: Reward debt consistency on stake changes
-    /// INVARIANT: reward_debt = staked_amount * accumulated_per_share / PRECISION
+fn legacy_stake_fixture(
+    staked_amount: u64,
+    pending_rewards: u64,
+    reward_debt: u128,
+) -> LegacyUserStake {
+    LegacyUserStake {
+        owner: Pubkey::new_unique(),
+        staked_amount,
+        stake_time: 0,
+        pending_rewards,
+        total_claimed: 0,
+        reward_debt,
+        bump: 255,
+    }
+}
+
+proptest! {
+    /// MG-1: Migration does not change what a staker can claim
+    /// INVARIANT: claimable(migrate(u), migrate(c)) =
+    ///   u.pending_rewards + u.staked_amount * c.acc / PRECISION - u.reward_debt
     #[test]
-    fn rf2_reward_debt_consistency(
+    fn mg1_migration_preserves_claimable(
         staked_amount in 1u64..=1_000_000_000_000,
+        pending_rewards in 0u64..=1_000_000_000,
+        entry_acc in 0u128..=PRECISION * 1000,
+        acc_growth in 0u128..=PRECISION * 1000,
+    ) {
+        let accumulated_per_share = entry_acc + acc_growth;
+        let reward_debt = calculate_reward_debt(staked_amount, entry_acc).unwrap();
+        let legacy_config =
+            legacy_config_fixture(staked_amount, u64::MAX / 2, accumulated_per_share);
+        let legacy_stake = legacy_stake_fixture(staked_amount, pending_rewards, reward_debt);
+
+        // Version 0 accrued on raw staked tokens
+        let legacy_claimable = pending_rewards
+            + calculate_pending_rewards(staked_amount, accumulated_per_share, reward_debt).unwrap();
+
+        let mut config = migrate_config_layout(&legacy_config);
+        let mut user_stake = migrate_user_stake_layout(&legacy_stake);
+
+        prop_assert_eq!(user_stake.shares, staked_amount);
+        prop_assert_eq!(user_stake.lock_multiplier_bps, BASE_MULTIPLIER_BPS);
+        prop_assert_eq!(settle_claimable(&mut config, &mut user_stake).unwrap(), legacy_claimable);
+    }
+
+    /// MG-2: Deposits after migration distribute exactly as before it
+    /// INVARIANT: migrate(c).total_shares = c.total_staked →
+    ///   Δacc(deposit d) = d * PRECISION / c.total_staked
+    #[test]
+    fn mg2_migration_preserves_deposit_math(
+        total_staked in 1u64..=1_000_000_000_000_000,
         accumulated_per_share in 0u128..=PRECISION * 1000,
+        deposit in 0u64..=1_000_000_000_000,
     ) {
-        // When staking, reward_debt should be set to current accumulated value
-        let expected_reward_debt = (staked_amount as u128)
-            .saturating_mul(accumulated_per_share)
-            .checked_div(PRECISION)
-            .unwrap_or(0);
+        let legacy = legacy_config_fixture(total_staked, 0, accumulated_per_share);
+        let mut config = migrate_config_layout(&legacy);
 
-        // Simulated reward_debt after stake
-        let reward_debt = expected_reward_debt;
+        record_reflection_deposit(&mut config, deposit).unwrap();
 
-        prop_assert_eq!(reward_debt, expected_reward_debt);
+        let legacy_acc = accumulated_per_share
+            + deposit as u128 * PRECISION / total_staked as u128;
+        prop_assert_eq!(config.total_shares, total_staked);
+        prop_assert_eq!(config.accumulated_per_share, legacy_acc);
+        prop_assert_eq!(config.pending_reflections, deposit);
+    }
+
+    /// MG-3: Migration carries every version 0 field and fills the new layout
+    /// INVARIANT: serialize(migrate(a)) has length 8 + INIT_SPACE ∧
+    ///   migrate(a).version = ACCOUNT_VERSION ∧ reserved = 0
+    #[test]
+    fn mg3_migration_fills_current_layout(
+        total_staked in 0u64..=u64::MAX,
+        pending_reflections in 0u64..=u64::MAX,
+        accumulated_per_share in 0u128..=u128::MAX,
+        reward_debt in 0u128..=u128::MAX,
+    ) {
+        let legacy_config =
+            legacy_config_fixture(total_staked, pending_reflections, accumulated_per_share);
+        let legacy_bytes = legacy_config.try_to_vec().unwrap();
+        prop_assert_eq!(legacy_bytes.len(), LegacyKernelConfig::INIT_SPACE);
+
+        let decoded = LegacyKernelConfig::deserialize(&mut &legacy_bytes[..]).unwrap();
+        let config = migrate_config_layout(&decoded);
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        prop_assert_eq!(data.len(), 8 + KernelConfig::INIT_SPACE);
+        prop_assert_eq!(config.version, ACCOUNT_VERSION);
+        prop_assert_eq!(config.authority, legacy_config.authority);
+        prop_assert_eq!(config.total_staked, total_staked);
+        prop_assert_eq!(config.pending_reflections, pending_reflections);
+        prop_assert_eq!(config.accumulated_per_share, accumulated_per_share);
+        prop_assert!(config.action_delays.iter().all(|&d| d == TIMELOCK_DURATION));
+        prop_assert!(config.reserved.iter().all(|&b| b == 0));
+
+        let legacy_stake = legacy_stake_fixture(total_staked, 0, reward_debt);
+        prop_assert_eq!(legacy_stake.try_to_vec().unwrap().len(), LegacyUserStake::INIT_SPACE);
+
+        let user_stake = migrate_user_stake_layout(&legacy_stake);
+        let mut data = Vec::new();
+        user_stake.try_serialize(&mut data).unwrap();
+        prop_assert_eq!(data.len(), 8 + UserStake::INIT_SPACE);
+        prop_assert_eq!(user_stake.owner, legacy_stake.owner);
+        prop_assert_eq!(user_stake.reward_debt, reward_debt);
+        prop_assert!(user_stake.reserved.iter().all(|&b| b == 0));
     }
 }
 
 // ============================================================================
-// LP Vault Invariants (LP-1 through LP-3)
+// LP Vault Invariants (LP-1 through LP-4)
 // ============================================================================
 
 proptest! {
@@ -338,6 +2269,71 @@ proptest! {
             }
         }
     }
+
+    /// LP-4: Withdraw limit per period
+    /// INVARIANT: withdraw_limit > 0 → Σ withdrawals within one period ≤ withdraw_limit
+    #[test]
+    fn lp4_withdraw_limit_per_period(
+        withdraw_limit in 1u64..=1_000_000,
+        withdrawals in prop::collection::vec(
+            (1u64..=500_000, 0i64..=LP_WITHDRAW_PERIOD / 2),
+            1..12,
+        ),
+    ) {
+        let mut lp_vault = lp_vault_fixture(withdraw_limit);
+        let mut now = 1_000_000i64;
+        // Successful withdrawals as (time, amount)
+        let mut taken: Vec<(i64, u64)> = Vec::new();
+
+        for (amount, gap) in withdrawals {
+            now += gap;
+            if record_lp_withdrawal(&mut lp_vault, amount, now).is_ok() {
+                taken.push((now, amount));
+            }
+
+            let in_period: u64 = taken
+                .iter()
+                .filter(|(t, _)| *t >= lp_vault.period_start)
+                .map(|(_, a)| a)
+                .sum();
+            prop_assert_eq!(lp_vault.withdrawn_in_period, in_period);
+            prop_assert!(in_period <= withdraw_limit);
+            prop_assert!(now < lp_vault.period_start + LP_WITHDRAW_PERIOD);
+        }
+    }
+}
+
+fn lp_vault_fixture(withdraw_limit: u64) -> LPVault {
+    LPVault {
+        version: ACCOUNT_VERSION,
+        authority: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        total_allocated: 0,
+        total_deployed: 0,
+        pending_deployment: 0,
+        last_deployment_time: 0,
+        bump: 255,
+        vault_token_bump: 255,
+        withdraw_limit,
+        withdrawn_in_period: 0,
+        period_start: 0,
+        reserved: [0; 40],
+    }
+}
+
+#[test]
+fn lp4_two_withdrawals_cannot_exceed_limit() {
+    let mut lp_vault = lp_vault_fixture(100);
+    let start = 1_000_000;
+
+    record_lp_withdrawal(&mut lp_vault, 60, start).unwrap();
+    assert!(record_lp_withdrawal(&mut lp_vault, 60, start + 60 * 60).is_err());
+    record_lp_withdrawal(&mut lp_vault, 40, start + 60 * 60).unwrap();
+    assert!(record_lp_withdrawal(&mut lp_vault, 1, start + LP_WITHDRAW_PERIOD - 1).is_err());
+
+    // The next period starts afresh
+    record_lp_withdrawal(&mut lp_vault, 100, start + LP_WITHDRAW_PERIOD).unwrap();
+    assert_eq!(lp_vault.withdrawn_in_period, 100);
 }
 
 // ============================================================================
@@ -378,45 +2374,161 @@ proptest! {
 }
 
 // ============================================================================
-// Airdrop Invariants (AD-1, AD-2)
+// Airdrop Invariants (AD-1 through AD-5)
 // ============================================================================
 
+/// Build every level of a sorted-pair Merkle tree (odd nodes are promoted)
+fn build_merkle_levels(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves.to_vec()];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels.last().unwrap();
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => merkle_node(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Collect the sibling path for the leaf at `index`
+fn merkle_proof(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+proptest! {
+    /// AD-1: Valid allocations are claimable
+    /// INVARIANT: ∀ leaf l in tree(root): verify(proof(l), root, l)
+    #[test]
+    fn ad1_valid_proof_verifies(
+        allocations in prop::collection::vec((any::<[u8; 32]>(), 1u64..=u64::MAX), 1..64),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        let leaves: Vec<[u8; 32]> = allocations
+            .iter()
+            .map(|(key, amount)| airdrop_leaf(&Pubkey::new_from_array(*key), *amount))
+            .collect();
+        let levels = build_merkle_levels(&leaves);
+        let root = levels.last().unwrap()[0];
+
+        let index = pick.index(leaves.len());
+        let proof = merkle_proof(&levels, index);
+
+        prop_assert!(
+            verify_merkle_proof(&proof, root, leaves[index]),
+            "AD-1: Valid allocation must verify"
+        );
+    }
+
+    /// AD-2: Allocations cannot be inflated
+    /// INVARIANT: verify(proof(claimant, a), root, leaf(claimant, a')) → a = a'
+    #[test]
+    fn ad2_tampered_amount_rejected(
+        allocations in prop::collection::vec((any::<[u8; 32]>(), 1u64..=u64::MAX / 2), 1..64),
+        pick in any::<prop::sample::Index>(),
+        bonus in 1u64..=u64::MAX / 2,
+    ) {
+        let leaves: Vec<[u8; 32]> = allocations
+            .iter()
+            .map(|(key, amount)| airdrop_leaf(&Pubkey::new_from_array(*key), *amount))
+            .collect();
+        let levels = build_merkle_levels(&leaves);
+        let root = levels.last().unwrap()[0];
+
+        let index = pick.index(leaves.len());
+        let proof = merkle_proof(&levels, index);
+        let (key, amount) = allocations[index];
+        let inflated = airdrop_leaf(&Pubkey::new_from_array(key), amount + bonus);
+
+        prop_assert!(
+            !verify_merkle_proof(&proof, root, inflated),
+            "AD-2: Inflated allocation must not verify"
+        );
+    }
+}
+
+/// Campaign fixture with the given lifecycle state
+fn campaign_fixture(status: CampaignStatus, start_time: i64, end_time: i64) -> Campaign {
+    Campaign {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::default(),
+        campaign_id: 0,
+        merkle_root: [0u8; 32],
+        start_time,
+        end_time,
+        budget: 0,
+        total_funded: 0,
+        total_claimed: 0,
+        claim_count: 0,
+        status,
+        bump: 0,
+        vault_bump: 0,
+        reserved: [0; 64],
+    }
+}
+
 proptest! {
-    /// AD-1: Recipient limit
-    /// INVARIANT: airdrop cannot exceed 50 recipients per call
+    /// AD-4: Claims only inside an active campaign window
+    /// INVARIANT: claim(c, t) succeeds → c.status = Active ∧ c.start ≤ t < c.end
     #[test]
-    fn ad1_recipient_limit(
-        num_recipients in 0usize..=100,
+    fn ad4_claim_window_enforced(
+        status in prop::sample::select(vec![
+            CampaignStatus::Draft,
+            CampaignStatus::Active,
+            CampaignStatus::Closed,
+        ]),
+        start_time in 0i64..=1_000_000,
+        duration in 1i64..=1_000_000,
+        current_time in 0i64..=3_000_000,
     ) {
-        let is_valid = num_recipients <= MAX_AIRDROP_RECIPIENTS;
+        let end_time = start_time + duration;
+        let campaign = campaign_fixture(status, start_time, end_time);
 
-        if !is_valid {
-            prop_assert!(num_recipients > MAX_AIRDROP_RECIPIENTS);
+        if campaign_accepts_claims(&campaign, current_time) {
+            prop_assert!(status == CampaignStatus::Active);
+            prop_assert!(current_time >= start_time && current_time < end_time);
         } else {
-            prop_assert!(num_recipients <= MAX_AIRDROP_RECIPIENTS);
+            prop_assert!(
+                status != CampaignStatus::Active
+                    || current_time < start_time
+                    || current_time >= end_time
+            );
         }
     }
 
-    /// AD-2: Accounting only (no actual transfer)
-    /// INVARIANT: airdrop updates accounting but doesn't transfer
+    /// AD-5: Campaign budget bound
+    /// INVARIANT: campaign.total_claimed ≤ campaign.budget
     #[test]
-    fn ad2_airdrop_accounting_only(
-        token_balance_before in 0u64..=u64::MAX,
-        airdrop_amount in 0u64..=u64::MAX,
+    fn ad5_claims_bounded_by_budget(
+        budget in 1u64..=1_000_000_000_000,
+        claims in prop::collection::vec(1u64..=1_000_000_000, 1..100),
     ) {
-        // Token balance should remain unchanged after airdrop
-        let token_balance_after = token_balance_before;
+        let mut total_claimed = 0u64;
 
-        prop_assert_eq!(
-            token_balance_after,
-            token_balance_before,
-            "AD-2: Airdrop should not change token balance"
-        );
+        for amount in claims {
+            // Mirrors the claim_airdrop budget check
+            if total_claimed + amount <= budget {
+                total_claimed += amount;
+            }
+            prop_assert!(total_claimed <= budget, "AD-5: Claims must not exceed budget");
+        }
     }
 }
 
 // ============================================================================
-// Authority Invariants (AU-1 through AU-3)
+// Authority Invariants (AU-1 through AU-4)
 // ============================================================================
 
 proptest! {
@@ -447,55 +2559,351 @@ proptest! {
             }
         }
     }
+
+    /// AU-4: Only the proposed authority can accept a transfer, inside its window
+    /// INVARIANT: accept(a, signer) succeeds →
+    ///   a.payload = TransferAuthority(signer) ∧ a.eta ≤ now ≤ a.expires_at
+    #[test]
+    fn au4_new_authority_must_accept(
+        proposed_at in 0i64..=1_000_000_000,
+        offset in 0i64..=2 * (TIMELOCK_DURATION + EXECUTION_WINDOW),
+        signer_is_new_authority in prop::bool::ANY,
+    ) {
+        let new_authority = Pubkey::new_unique();
+        let action = governance_action_fixture(
+            proposed_at,
+            GovernancePayload::TransferAuthority { new_authority },
+        );
+        let signer = if signer_is_new_authority { new_authority } else { Pubkey::new_unique() };
+        let now = proposed_at + offset;
+
+        let result = check_authority_acceptance(&action, &signer, now);
+        let in_window = (action.eta..=action.expires_at).contains(&now);
+        prop_assert_eq!(result.is_ok(), signer_is_new_authority && in_window);
+        if !signer_is_new_authority {
+            prop_assert_eq!(result.unwrap_err(), KernelError::NotPendingAuthority.into());
+        }
+    }
 }
 
 // ============================================================================
-// Pause Invariants (PA-1 through PA-3)
+// Guardian Invariants (GD-1 through GD-3)
 // ============================================================================
 
+fn guardian_set_fixture(guardians: Vec<Pubkey>, threshold: u8) -> GuardianSet {
+    GuardianSet {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        guardians,
+        threshold,
+        epoch: 0,
+        action_count: 0,
+        bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+fn guardian_action_fixture(epoch: u32) -> GuardianAction {
+    GuardianAction {
+        version: ACCOUNT_VERSION,
+        guardian_set: Pubkey::new_unique(),
+        nonce: 0,
+        proposer: Pubkey::new_unique(),
+        action: EmergencyAction::SetPauseFlags {
+            flags: PAUSE_ALL,
+            paused_until: 0,
+        },
+        epoch,
+        approvals: 0,
+        proposed_at: 0,
+        executed: false,
+        bump: 255,
+        expires_at: GUARDIAN_ACTION_WINDOW,
+        reserved: [0; 21],
+    }
+}
+
 proptest! {
-    /// PA-1: Pause blocks staking
-    /// INVARIANT: config.is_paused → stake() reverts
+    /// GD-1: Guardian set shape
+    /// INVARIANT: 1 ≤ threshold ≤ |guardians| ≤ MAX_GUARDIANS, no duplicates
     #[test]
-    fn pa1_pause_blocks_staking(
-        is_paused in prop::bool::ANY,
-        stake_amount in 1u64..=u64::MAX,
+    fn gd1_guardian_set_shape(
+        n in 0usize..=12,
+        threshold in 0u8..=12,
+        duplicate in prop::bool::ANY,
     ) {
-        let stake_allowed = !is_paused;
+        let mut guardians: Vec<Pubkey> = (0..n).map(|_| Pubkey::new_unique()).collect();
+        if duplicate && n >= 2 {
+            guardians[n - 1] = guardians[0];
+        }
 
-        if is_paused {
-            prop_assert!(!stake_allowed, "PA-1: Staking must fail when paused");
-        } else {
-            prop_assert!(stake_allowed);
+        let expected = (1..=MAX_GUARDIANS).contains(&n)
+            && threshold >= 1
+            && threshold as usize <= n
+            && !(duplicate && n >= 2);
+        prop_assert_eq!(validate_guardian_set(&guardians, threshold), expected);
+    }
+
+    /// GD-2: M distinct guardians
+    /// INVARIANT: approvals counts each registered guardian at most once;
+    ///   outsiders and repeat approvals are rejected
+    #[test]
+    fn gd2_distinct_guardian_approvals(
+        n in 1usize..=MAX_GUARDIANS,
+        order in prop::collection::vec(0usize..MAX_GUARDIANS + 2, 0..30),
+    ) {
+        let guardians: Vec<Pubkey> = (0..n).map(|_| Pubkey::new_unique()).collect();
+        let set = guardian_set_fixture(guardians.clone(), n as u8);
+        let mut action = guardian_action_fixture(0);
+        let mut approved = std::collections::BTreeSet::new();
+
+        for i in order {
+            let signer = guardians.get(i).copied().unwrap_or_else(Pubkey::new_unique);
+            let result = record_guardian_approval(&set, &mut action, &signer);
+            if i >= n {
+                prop_assert_eq!(result.unwrap_err(), KernelError::NotGuardian.into());
+            } else if !approved.insert(i) {
+                prop_assert_eq!(result.unwrap_err(), KernelError::AlreadyApproved.into());
+            } else {
+                prop_assert!(result.is_ok());
+            }
+            prop_assert_eq!(action.approvals.count_ones() as usize, approved.len());
         }
     }
 
-    /// PA-2: Unstake always works
-    /// INVARIANT: Unstaking works regardless of pause
+    /// GD-3: Membership changes void old approvals
+    /// INVARIANT: action.epoch ≠ guardian_set.epoch → approval rejected
     #[test]
-    fn pa2_unstake_always_works(
-        is_paused in prop::bool::ANY,
-        stake_amount in 1u64..=u64::MAX,
-        unstake_amount in 1u64..=u64::MAX,
+    fn gd3_membership_change_voids_approvals(
+        epoch in 0u32..1000,
+        bump in 1u32..10,
+    ) {
+        let guardian = Pubkey::new_unique();
+        let mut set = guardian_set_fixture(vec![guardian], 1);
+        set.epoch = epoch + bump;
+        let mut action = guardian_action_fixture(epoch);
+
+        let result = record_guardian_approval(&set, &mut action, &guardian);
+        prop_assert_eq!(result.unwrap_err(), KernelError::StaleGuardianProposal.into());
+        prop_assert_eq!(action.approvals, 0);
+    }
+
+    /// GD-4: Emergency actions expire
+    /// INVARIANT: approve(a), execute(a) at time t succeed →
+    ///   t ≤ a.expires_at = a.proposed_at + GUARDIAN_ACTION_WINDOW ∧ ¬a.executed
+    #[test]
+    fn gd4_guardian_actions_expire(
+        offset in 0i64..=2 * GUARDIAN_ACTION_WINDOW,
+        executed in prop::bool::ANY,
+    ) {
+        let mut action = guardian_action_fixture(0);
+        action.executed = executed;
+
+        let open = check_guardian_action_open(&action, action.proposed_at + offset).is_ok();
+        prop_assert_eq!(open, !executed && offset <= GUARDIAN_ACTION_WINDOW);
+    }
+}
+
+// ============================================================================
+// Role Invariants (RL-1 through RL-3)
+// ============================================================================
+
+const ROLES: [Role; 6] = [
+    Role::Admin,
+    Role::Pauser,
+    Role::ReflectionDepositor,
+    Role::LpOperator,
+    Role::Burner,
+    Role::AirdropManager,
+];
+
+fn roles_fixture(holder: Pubkey) -> Roles {
+    Roles {
+        version: ACCOUNT_VERSION,
+        config: Pubkey::new_unique(),
+        admin: holder,
+        pauser: holder,
+        reflection_depositor: holder,
+        lp_operator: holder,
+        burner: holder,
+        airdrop_manager: holder,
+        bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+fn holder_of(roles: &Roles, role: Role) -> Pubkey {
+    match role {
+        Role::Admin => roles.admin,
+        Role::Pauser => roles.pauser,
+        Role::ReflectionDepositor => roles.reflection_depositor,
+        Role::LpOperator => roles.lp_operator,
+        Role::Burner => roles.burner,
+        Role::AirdropManager => roles.airdrop_manager,
+    }
+}
+
+proptest! {
+    /// RL-1: A role change moves exactly one role
+    /// INVARIANT: set_role(r, k) → holder(r) = k ∧ ∀ r' ≠ r: holder(r') unchanged
+    #[test]
+    fn rl1_role_change_is_isolated(
+        role_index in 0usize..6,
+        revoke in prop::bool::ANY,
+    ) {
+        let authority = Pubkey::new_unique();
+        let mut roles = roles_fixture(authority);
+        let role = ROLES[role_index];
+        let holder = if revoke { Pubkey::default() } else { Pubkey::new_unique() };
+
+        set_role(&mut roles, role, holder);
+
+        for other in ROLES {
+            let expected = if other == role { holder } else { authority };
+            prop_assert_eq!(holder_of(&roles, other), expected);
+        }
+    }
+
+    /// RL-2: Role grants wait out the roles delay and name a real key
+    /// INVARIANT: propose(GrantRole(r, k)) succeeds ↔ k ≠ Pubkey::default() ∧
+    ///   kind(GrantRole) = kind(RevokeRole) = Roles
+    #[test]
+    fn rl2_role_payloads_timelocked(
+        role_index in 0usize..6,
+        grant_to_default in prop::bool::ANY,
+        roles_delay in MIN_ACTION_DELAY..=MAX_ACTION_DELAY,
+    ) {
+        let role = ROLES[role_index];
+        let holder = if grant_to_default { Pubkey::default() } else { Pubkey::new_unique() };
+        let grant = GovernancePayload::GrantRole { role, holder };
+        let revoke = GovernancePayload::RevokeRole { role };
+
+        prop_assert_eq!(validate_governance_payload(&grant).is_ok(), !grant_to_default);
+        prop_assert!(validate_governance_payload(&revoke).is_ok());
+
+        let mut config = config_fixture(0, 0, 0);
+        config.action_delays[ActionKind::Roles as usize] = roles_delay;
+        prop_assert_eq!(action_delay(&config, &grant), roles_delay);
+        prop_assert_eq!(action_delay(&config, &revoke), roles_delay);
+    }
+
+    /// RL-3: Accepting authority hands over exactly the roles the old authority held
+    /// INVARIANT: rotate_roles(old, new) → ∀ r: holder(r) = (was(r) = old ? new : was(r))
+    #[test]
+    fn rl3_authority_rotation_moves_old_roles(held in prop::array::uniform6(prop::bool::ANY)) {
+        let old_authority = Pubkey::new_unique();
+        let new_authority = Pubkey::new_unique();
+        let mut roles = roles_fixture(old_authority);
+        let others: Vec<Pubkey> = ROLES.iter().map(|_| Pubkey::new_unique()).collect();
+        for (i, role) in ROLES.into_iter().enumerate() {
+            if !held[i] {
+                set_role(&mut roles, role, others[i]);
+            }
+        }
+
+        let rotated = rotate_roles(&mut roles, old_authority, new_authority);
+
+        prop_assert_eq!(rotated, held.iter().any(|h| *h));
+        for (i, role) in ROLES.into_iter().enumerate() {
+            let expected = if held[i] { new_authority } else { others[i] };
+            prop_assert_eq!(holder_of(&roles, role), expected);
+        }
+    }
+}
+
+// ============================================================================
+// Pause Invariants (PA-1 through PA-6)
+// PA-2 and PA-3 (unstake and claims ignore pauses) are covered by tests/kernel-token.ts
+// ============================================================================
+
+const PAUSABLE: [(PauseScope, u8); 5] = [
+    (PauseScope::Stake, PAUSE_STAKE),
+    (PauseScope::RewardDeposits, PAUSE_REWARD_DEPOSITS),
+    (PauseScope::AirdropClaims, PAUSE_AIRDROP_CLAIMS),
+    (PauseScope::LpAllocation, PAUSE_LP_ALLOCATION),
+    (PauseScope::Burns, PAUSE_BURNS),
+];
+
+fn paused_config(pause_flags: u8, paused_until: i64) -> KernelConfig {
+    let mut config = config_fixture(0, 0, 0);
+    config.pause_flags = pause_flags;
+    config.paused_until = paused_until;
+    config
+}
+
+proptest! {
+    /// PA-1: Each flag pauses exactly its own subsystem
+    /// INVARIANT: before paused_until, is_paused(s) ⟺ flag(s) ∈ pause_flags
+    #[test]
+    fn pa1_flags_pause_their_subsystem(
+        pause_flags in 0u8..=PAUSE_ALL,
+        paused_until in prop_oneof![Just(0i64), 1i64..=i64::MAX],
+        now in 0i64..=1_000_000_000_000,
+    ) {
+        let config = paused_config(pause_flags, paused_until);
+        let active = paused_until == 0 || now < paused_until;
+
+        for (scope, flag) in PAUSABLE {
+            prop_assert_eq!(
+                is_paused(&config, scope, now),
+                active && pause_flags & flag != 0,
+                "PA-1: {:?} must follow its own flag", scope
+            );
+        }
+    }
+
+    /// PA-4: A pause with an expiry lifts itself
+    /// INVARIANT: paused_until > 0 ∧ t ≥ paused_until → ∀ s: ¬is_paused(s)
+    #[test]
+    fn pa4_pause_expires(
+        pause_flags in prop::num::u8::ANY,
+        paused_until in 1i64..=1_000_000_000_000,
+        elapsed in 0i64..=1_000_000_000,
+    ) {
+        let config = paused_config(pause_flags, paused_until);
+        let now = paused_until + elapsed;
+
+        for (scope, _) in PAUSABLE {
+            prop_assert!(!is_paused(&config, scope, now), "PA-4: {:?} still paused", scope);
+        }
+    }
+
+    /// PA-5: Only known flags and future expiries can be set
+    /// INVARIANT: valid_pause(f, u, t) ⟺ f ⊆ PAUSE_ALL ∧ (u = 0 ∨ u > t)
+    #[test]
+    fn pa5_pause_mask_validated(
+        flags in prop::num::u8::ANY,
+        paused_until in -1_000i64..=1_000_000,
+        now in 0i64..=1_000_000,
     ) {
-        // Unstake should work regardless of pause state
-        let can_unstake = unstake_amount <= stake_amount;
+        let expected = flags & !PAUSE_ALL == 0 && (paused_until == 0 || paused_until > now);
+        prop_assert_eq!(valid_pause(flags, paused_until, now), expected);
 
-        // Pause state doesn't affect unstake eligibility
-        let unstake_blocked_by_pause = false;
-        prop_assert!(!unstake_blocked_by_pause, "PA-2: Unstake must work when paused");
+        let payload = GovernancePayload::SetPauseFlags { flags, paused_until };
+        prop_assert_eq!(
+            validate_governance_payload(&payload).is_ok(),
+            flags & !PAUSE_ALL == 0 && paused_until >= 0
+        );
     }
 
-    /// PA-3: Claim always works
-    /// INVARIANT: Claiming works regardless of pause
+    /// PA-6: The pre-flags pause bool still means "pause staking"
+    /// INVARIANT: SetPaused(p) ≡ SetPauseFlags(p ? PAUSE_STAKE : 0, 0)
     #[test]
-    fn pa3_claim_always_works(
-        is_paused in prop::bool::ANY,
-        pending_rewards in 0u64..=u64::MAX,
+    fn pa6_legacy_pause_pauses_staking(
+        paused in prop::bool::ANY,
+        now in 0i64..=1_000_000_000_000,
     ) {
-        // Claim should work regardless of pause state
-        let claim_blocked_by_pause = false;
-        prop_assert!(!claim_blocked_by_pause, "PA-3: Claim must work when paused");
+        let flags = legacy_pause_flags(paused);
+        prop_assert!(valid_pause(flags, 0, now));
+
+        let config = paused_config(flags, 0);
+        for (scope, _) in PAUSABLE {
+            prop_assert_eq!(is_paused(&config, scope, now), paused && scope == PauseScope::Stake);
+        }
+
+        let payload = GovernancePayload::SetPaused { paused };
+        prop_assert!(validate_governance_payload(&payload).is_ok());
+        prop_assert_eq!(action_kind(&payload), ActionKind::Pause);
     }
 }
 
@@ -567,3 +2975,52 @@ proptest! {
         prop_assert!(fee <= (amount as u128) * 500 / 10000);
     }
 }
+
+proptest! {
+    /// MS-1: Overflow surfaces as MathOverflow, never a panic
+    /// INVARIANT: shares * acc / PRECISION > u64::MAX →
+    ///   calculate_pending_rewards = Err(MathOverflow)
+    #[test]
+    fn ms1_pending_overflow_is_error(
+        shares in (u64::MAX / 2)..=u64::MAX,
+        multiple in 3u128..=1000,
+    ) {
+        let result = calculate_pending_rewards(shares, PRECISION * multiple, 0);
+        prop_assert_eq!(result.unwrap_err(), KernelError::MathOverflow.into());
+        prop_assert_eq!(
+            to_u64(u64::MAX as u128 + 1).unwrap_err(),
+            KernelError::MathOverflow.into()
+        );
+    }
+
+    /// MS-2: Counters that would wrap reject the deposit
+    /// INVARIANT: pending_reflections + amount > u64::MAX →
+    ///   record_reflection_deposit = Err(MathOverflow)
+    #[test]
+    fn ms2_deposit_overflow_is_error(
+        pending in (u64::MAX - 1000)..=u64::MAX,
+        amount in 1001u64..=u64::MAX,
+    ) {
+        let mut config = config_fixture(0, 0, 0);
+        config.pending_reflections = pending;
+
+        let result = record_reflection_deposit(&mut config, amount);
+        prop_assert_eq!(result.unwrap_err(), KernelError::MathOverflow.into());
+        prop_assert_eq!(
+            pending.safe_add(amount).unwrap_err(),
+            KernelError::MathOverflow.into()
+        );
+        prop_assert_eq!(
+            0u64.safe_sub(amount).unwrap_err(),
+            KernelError::MathOverflow.into()
+        );
+    }
+
+    /// MS-3: A zero divisor is DivisionByZero
+    /// INVARIANT: ∀ x: x.safe_div(0) = Err(DivisionByZero)
+    #[test]
+    fn ms3_division_by_zero_is_error(x in 0u128..=u128::MAX, y in 1u128..=u128::MAX) {
+        prop_assert_eq!(x.safe_div(0).unwrap_err(), KernelError::DivisionByZero.into());
+        prop_assert_eq!(x.safe_div(y).unwrap(), x / y);
+    }
+}
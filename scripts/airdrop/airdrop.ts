@@ -1,29 +1,72 @@
 /**
  * $KERNEL Airdrop Script
- * Distributes tokens to community members
+ * Builds a Merkle tree of allocations, creates an airdrop campaign with
+ * that root and funds its vault. Recipients then claim with `claim_airdrop`.
  * "Popcorn for everyone!"
  */
 
-import {
-  Connection,
-  Keypair,
-  PublicKey,
-} from "@solana/web3.js";
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import { AnchorProvider, Program, Wallet, BN } from "@coral-xyz/anchor";
 import {
   TOKEN_2022_PROGRAM_ID,
-  getOrCreateAssociatedTokenAccount,
-  transfer,
-  getAccount,
+  getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
+import { createHash } from "crypto";
 import * as fs from "fs";
 import * as path from "path";
 
+// Campaign settings (each campaign id can only be created once)
+const CAMPAIGN_ID = new BN(process.env.CAMPAIGN_ID || "1");
+const CAMPAIGN_DURATION_DAYS = 30;
+
 // Example recipients (replace with actual addresses)
-const AIRDROP_RECIPIENTS = [
+const AIRDROP_RECIPIENTS: { address: string; amount: number }[] = [
   // { address: "recipient1pubkey", amount: 1000000 },
   // { address: "recipient2pubkey", amount: 500000 },
 ];
 
+const KERNEL_PROGRAM_ID = new PublicKey("5QVVrCBUgqjG3pWcSmRkqaagFaokaAwgoFFDLXBJgFJw");
+const proofsPath = (campaignId: BN) =>
+  path.join(__dirname, `../../.kernel-airdrop-${campaignId.toString()}-proofs.json`);
+
+// Leaf = sha256(0x00 || claimant || amount_le), node = sha256(0x01 || lo || hi)
+function leafFor(claimant: PublicKey, amount: BN): Buffer {
+  return createHash("sha256")
+    .update(Buffer.from([0]))
+    .update(claimant.toBuffer())
+    .update(amount.toArrayLike(Buffer, "le", 8))
+    .digest();
+}
+
+function nodeFor(a: Buffer, b: Buffer): Buffer {
+  const [lo, hi] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return createHash("sha256").update(Buffer.from([1])).update(lo).update(hi).digest();
+}
+
+/** Build every level of the tree; odd nodes are promoted unchanged */
+function buildLevels(leaves: Buffer[]): Buffer[][] {
+  const levels = [leaves];
+  while (levels[levels.length - 1].length > 1) {
+    const level = levels[levels.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(i + 1 < level.length ? nodeFor(level[i], level[i + 1]) : level[i]);
+    }
+    levels.push(next);
+  }
+  return levels;
+}
+
+function proofFor(levels: Buffer[][], index: number): Buffer[] {
+  const proof: Buffer[] = [];
+  for (const level of levels.slice(0, -1)) {
+    const sibling = index ^ 1;
+    if (sibling < level.length) proof.push(level[sibling]);
+    index = Math.floor(index / 2);
+  }
+  return proof;
+}
+
 async function main() {
   console.log("===========================================");
   console.log("   $KERNEL Airdrop Distribution");
@@ -68,89 +111,110 @@ async function main() {
     return;
   }
 
-  // Get source account
-  const sourceAccount = await getOrCreateAssociatedTokenAccount(
-    connection,
-    walletKeypair,
-    mintPubkey,
-    walletKeypair.publicKey,
-    false,
-    "confirmed",
-    undefined,
-    TOKEN_2022_PROGRAM_ID
+  // Build the Merkle tree
+  const allocations = AIRDROP_RECIPIENTS.map((r) => ({
+    claimant: new PublicKey(r.address),
+    amount: new BN(r.amount).mul(new BN(10).pow(new BN(decimals))),
+  }));
+  const leaves = allocations.map((a) => leafFor(a.claimant, a.amount));
+  const levels = buildLevels(leaves);
+  const root = levels[levels.length - 1][0];
+  const totalAirdrop = allocations.reduce((sum, a) => sum.add(a.amount), new BN(0));
+
+  console.log(`Merkle root: ${root.toString("hex")}`);
+  console.log(`Recipients: ${allocations.length}`);
+  console.log(`Total to Airdrop: ${AIRDROP_RECIPIENTS.reduce((s, r) => s + r.amount, 0).toLocaleString()} KERNEL\n`);
+
+  // Set up Anchor
+  const idlPath = path.join(__dirname, "../../target/idl/kernel_token.json");
+  const IDL = JSON.parse(fs.readFileSync(idlPath, "utf-8"));
+  const provider = new AnchorProvider(connection, new Wallet(walletKeypair), {
+    commitment: "confirmed",
+  });
+  const program = new Program(IDL, provider);
+
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config"), mintPubkey.toBuffer()],
+    KERNEL_PROGRAM_ID
   );
-
-  const accountInfo = await getAccount(
-    connection,
-    sourceAccount.address,
-    "confirmed",
-    TOKEN_2022_PROGRAM_ID
+  // The distributor wallet must hold the airdrop manager role
+  const [rolesPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("roles"), configPda.toBuffer()],
+    KERNEL_PROGRAM_ID
+  );
+  const [campaignPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("campaign"), configPda.toBuffer(), CAMPAIGN_ID.toArrayLike(Buffer, "le", 8)],
+    KERNEL_PROGRAM_ID
+  );
+  const [campaignVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("campaign_vault"), campaignPda.toBuffer()],
+    KERNEL_PROGRAM_ID
   );
 
-  const sourceBalance = Number(accountInfo.amount) / (10 ** decimals);
-  console.log(`Source Balance: ${sourceBalance.toLocaleString()} KERNEL\n`);
-
-  // Calculate total airdrop
-  const totalAirdrop = AIRDROP_RECIPIENTS.reduce((sum, r) => sum + r.amount, 0);
-  console.log(`Total to Airdrop: ${totalAirdrop.toLocaleString()} KERNEL`);
-  console.log(`Recipients: ${AIRDROP_RECIPIENTS.length}\n`);
-
-  if (totalAirdrop > sourceBalance) {
-    console.log("Insufficient balance for airdrop.");
-    process.exit(1);
-  }
-
-  console.log("Processing airdrops...\n");
-
-  let successCount = 0;
-  let failCount = 0;
-
-  for (const recipient of AIRDROP_RECIPIENTS) {
-    try {
-      const recipientPubkey = new PublicKey(recipient.address);
-
-      // Get or create recipient's token account
-      const recipientAccount = await getOrCreateAssociatedTokenAccount(
-        connection,
-        walletKeypair,
+  const now = Math.floor(Date.now() / 1000);
+
+  console.log(`Creating campaign ${CAMPAIGN_ID.toString()}...`);
+  const createTx = await program.methods
+    .createCampaign(
+      CAMPAIGN_ID,
+      [...root],
+      totalAirdrop,
+      new BN(now),
+      new BN(now + CAMPAIGN_DURATION_DAYS * 24 * 60 * 60)
+    )
+    .accounts({
+      authority: walletKeypair.publicKey,
+      tokenMint: mintPubkey,
+      config: configPda,
+      roles: rolesPda,
+      campaign: campaignPda,
+      campaignVault: campaignVaultPda,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc();
+  console.log(`  Campaign created: ${createTx}`);
+
+  console.log("Funding campaign vault...");
+  const fundTx = await program.methods
+    .fundCampaign(totalAirdrop)
+    .accounts({
+      authority: walletKeypair.publicKey,
+      tokenMint: mintPubkey,
+      config: configPda,
+      roles: rolesPda,
+      campaign: campaignPda,
+      campaignVault: campaignVaultPda,
+      authorityTokenAccount: getAssociatedTokenAddressSync(
         mintPubkey,
-        recipientPubkey,
+        walletKeypair.publicKey,
         false,
-        "confirmed",
-        undefined,
-        TOKEN_2022_PROGRAM_ID
-      );
-
-      // Transfer
-      const amountRaw = BigInt(recipient.amount * (10 ** decimals));
-      await transfer(
-        connection,
-        walletKeypair,
-        sourceAccount.address,
-        recipientAccount.address,
-        walletKeypair,
-        amountRaw,
-        [],
-        { commitment: "confirmed" },
         TOKEN_2022_PROGRAM_ID
-      );
-
-      console.log(`  Sent ${recipient.amount.toLocaleString()} KERNEL to ${recipient.address.slice(0, 8)}...`);
-      successCount++;
-    } catch (err) {
-      console.log(`  Failed: ${recipient.address.slice(0, 8)}... - ${err}`);
-      failCount++;
-    }
-  }
+      ),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc();
+  console.log(`  Campaign funded and active: ${fundTx}`);
+
+  // Publish proofs for the claim page
+  const proofs = allocations.map((a, i) => ({
+    claimant: a.claimant.toBase58(),
+    amount: a.amount.toString(),
+    proof: proofFor(levels, i).map((p) => p.toString("hex")),
+  }));
+  fs.writeFileSync(
+    proofsPath(CAMPAIGN_ID),
+    JSON.stringify(
+      { campaignId: CAMPAIGN_ID.toString(), root: root.toString("hex"), proofs },
+      null,
+      2
+    )
+  );
 
   console.log("\n===========================================");
-  console.log("   AIRDROP COMPLETE!");
+  console.log("   AIRDROP READY TO CLAIM!");
   console.log("   Colonel Kernel thanks you!");
   console.log("===========================================\n");
-
-  console.log(`Successful: ${successCount}`);
-  console.log(`Failed: ${failCount}`);
-  console.log(`Total Distributed: ${(successCount * (totalAirdrop / AIRDROP_RECIPIENTS.length)).toLocaleString()} KERNEL`);
+  console.log(`Proofs written to ${proofsPath(CAMPAIGN_ID)}`);
 }
 
 main().catch((err) => {
@@ -1,24 +1,26 @@
 /**
  * $KERNEL Fee Harvester
  *
- * This script harvests transfer fees from Token-2022 and distributes them:
+ * This script cranks `harvest_and_distribute`, which pulls Token-2022
+ * withheld transfer fees into the program's fee vault PDA and splits them:
  * - 40% (2/5) to Reflection Pool for stakers
  * - 40% (2/5) to LP for liquidity provision
  * - 20% (1/5) burned for deflation
  *
+ * The instruction is permissionless - any wallet can run this script.
+ * The fee vault PDA must be the mint's withdraw-withheld authority.
+ *
  * Run periodically (e.g., every hour via cron) to process accumulated fees.
  */
 
-import { Connection, Keypair, PublicKey, Transaction } from '@solana/web3.js';
-import { AnchorProvider, Program, Wallet, BN } from '@coral-xyz/anchor';
+import { Connection, Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import { AnchorProvider, Program, Wallet } from '@coral-xyz/anchor';
 import {
   TOKEN_2022_PROGRAM_ID,
-  getAssociatedTokenAddressSync,
-  getAccount,
+  getMint,
   getTransferFeeAmount,
-  harvestWithheldTokensToMint,
-  withdrawWithheldTokensFromMint,
-  createAssociatedTokenAccountInstruction,
+  getTransferFeeConfig,
+  unpackAccount,
 } from '@solana/spl-token';
 import * as fs from 'fs';
 import * as path from 'path';
@@ -34,6 +36,9 @@ const LP_SHARE = 2;         // 2/5 = 40%
 const BURN_SHARE = 1;       // 1/5 = 20%
 const TOTAL_SHARES = 5;
 
+// Token accounts harvested into the mint per transaction
+const HARVEST_BATCH_SIZE = 20;
+
 // Load IDL
 const idlPath = path.join(__dirname, '..', 'target', 'idl', 'kernel_token.json');
 const IDL = JSON.parse(fs.readFileSync(idlPath, 'utf-8'));
@@ -55,7 +60,7 @@ async function main() {
   console.log('='.repeat(60));
   console.log('$KERNEL Fee Harvester');
   console.log('='.repeat(60));
-  console.log('Authority:', wallet.publicKey.toBase58());
+  console.log('Harvester:', wallet.publicKey.toBase58());
   console.log('Token Mint:', KERNEL_MINT.toBase58());
   console.log('RPC:', RPC_ENDPOINT);
 
@@ -95,92 +100,31 @@ async function main() {
 async function harvestFees(connection: Connection, wallet: Keypair): Promise<HarvestResult> {
   const txSignatures: string[] = [];
 
-  // 1. Get all token accounts holding KERNEL (to harvest withheld fees)
+  // 1. Find token accounts with withheld fees
   console.log('\n📊 Checking for withheld fees...');
 
-  // Get fee authority's token account
-  const feeAuthorityAta = getAssociatedTokenAddressSync(
-    KERNEL_MINT,
-    wallet.publicKey,
-    false,
-    TOKEN_2022_PROGRAM_ID
-  );
-
-  // Check if ATA exists
-  let ataExists = false;
-  try {
-    await getAccount(connection, feeAuthorityAta, 'confirmed', TOKEN_2022_PROGRAM_ID);
-    ataExists = true;
-  } catch {
-    ataExists = false;
-  }
-
-  // Create ATA if needed
-  if (!ataExists) {
-    console.log('Creating fee authority token account...');
-    const tx = new Transaction().add(
-      createAssociatedTokenAccountInstruction(
-        wallet.publicKey,
-        feeAuthorityAta,
-        wallet.publicKey,
-        KERNEL_MINT,
-        TOKEN_2022_PROGRAM_ID
-      )
-    );
-    const sig = await connection.sendTransaction(tx, [wallet]);
-    await connection.confirmTransaction(sig);
-    txSignatures.push(sig);
-    console.log('Created ATA:', sig);
-  }
-
-  // 2. Harvest withheld tokens from all accounts to mint
-  console.log('\n🌾 Harvesting withheld tokens to mint...');
-
-  // Get token accounts with withheld amounts
-  const tokenAccounts = await connection.getTokenAccountsByOwner(
-    KERNEL_MINT,
-    { programId: TOKEN_2022_PROGRAM_ID }
-  );
-
-  // For Token-2022, we need to harvest from individual accounts
-  // This is done by calling harvestWithheldTokensToMint
-  // In production, you'd iterate through accounts with withheld fees
-
-  console.log('Found', tokenAccounts.value.length, 'token accounts');
-
-  // 3. Withdraw withheld tokens from mint to authority
-  console.log('\n💰 Withdrawing fees to authority...');
-
-  try {
-    const withdrawSig = await withdrawWithheldTokensFromMint(
-      connection,
-      wallet,
-      KERNEL_MINT,
-      feeAuthorityAta,
-      wallet,
-      [],
-      { commitment: 'confirmed' },
-      TOKEN_2022_PROGRAM_ID
-    );
-    txSignatures.push(withdrawSig);
-    console.log('Withdrew fees:', withdrawSig);
-  } catch (error: any) {
-    if (!error.message?.includes('insufficient')) {
-      throw error;
+  const tokenAccounts = await connection.getProgramAccounts(TOKEN_2022_PROGRAM_ID, {
+    commitment: 'confirmed',
+    filters: [{ memcmp: { offset: 0, bytes: KERNEL_MINT.toBase58() } }],
+  });
+
+  const withheldSources: PublicKey[] = [];
+  let accountsWithheld = BigInt(0);
+  for (const { pubkey, account } of tokenAccounts) {
+    const parsed = unpackAccount(pubkey, account, TOKEN_2022_PROGRAM_ID);
+    const feeAmount = getTransferFeeAmount(parsed);
+    if (feeAmount && feeAmount.withheldAmount > BigInt(0)) {
+      withheldSources.push(pubkey);
+      accountsWithheld += feeAmount.withheldAmount;
     }
-    console.log('No withheld fees to withdraw');
   }
 
-  // 4. Check harvested amount
-  const authorityAccount = await getAccount(
-    connection,
-    feeAuthorityAta,
-    'confirmed',
-    TOKEN_2022_PROGRAM_ID
-  );
+  const mintInfo = await getMint(connection, KERNEL_MINT, 'confirmed', TOKEN_2022_PROGRAM_ID);
+  const mintWithheld = getTransferFeeConfig(mintInfo)?.withheldAmount ?? BigInt(0);
+  const harvestedAmount = accountsWithheld + mintWithheld;
 
-  const harvestedAmount = authorityAccount.amount;
-  console.log('\nHarvested amount:', formatTokens(harvestedAmount));
+  console.log('Found', withheldSources.length, 'token accounts with withheld fees');
+  console.log('\nWithheld amount:', formatTokens(harvestedAmount));
 
   if (harvestedAmount === BigInt(0)) {
     console.log('No fees to distribute');
@@ -193,7 +137,7 @@ async function harvestFees(connection: Connection, wallet: Keypair): Promise<Har
     };
   }
 
-  // 5. Calculate distribution
+  // 2. Expected distribution (mirrors split_fees on-chain; dust goes to burn)
   const reflectionAmount = (harvestedAmount * BigInt(REFLECTION_SHARE)) / BigInt(TOTAL_SHARES);
   const lpAmount = (harvestedAmount * BigInt(LP_SHARE)) / BigInt(TOTAL_SHARES);
   const burnAmount = harvestedAmount - reflectionAmount - lpAmount; // Remainder to burn
@@ -203,7 +147,7 @@ async function harvestFees(connection: Connection, wallet: Keypair): Promise<Har
   console.log('  LP:', formatTokens(lpAmount));
   console.log('  Burn:', formatTokens(burnAmount));
 
-  // 6. Set up Anchor for program interactions
+  // 3. Set up Anchor for program interactions
   const provider = new AnchorProvider(
     connection,
     new Wallet(wallet),
@@ -216,66 +160,61 @@ async function harvestFees(connection: Connection, wallet: Keypair): Promise<Har
     [Buffer.from('config'), KERNEL_MINT.toBuffer()],
     KERNEL_PROGRAM_ID
   );
-
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('fee_vault'), KERNEL_MINT.toBuffer()],
+    KERNEL_PROGRAM_ID
+  );
   const [reflectionPoolPda] = PublicKey.findProgramAddressSync(
     [Buffer.from('reflection_pool'), KERNEL_MINT.toBuffer()],
     KERNEL_PROGRAM_ID
   );
-
+  const [lpVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('lp_vault'), KERNEL_MINT.toBuffer()],
+    KERNEL_PROGRAM_ID
+  );
+  const [lpVaultTokenPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('lp_vault_token'), KERNEL_MINT.toBuffer()],
+    KERNEL_PROGRAM_ID
+  );
   const [burnRecordPda] = PublicKey.findProgramAddressSync(
     [Buffer.from('burn'), configPda.toBuffer()],
     KERNEL_PROGRAM_ID
   );
 
-  // 7. Deposit reflections
-  if (reflectionAmount > BigInt(0)) {
-    console.log('\n🔄 Depositing to reflection pool...');
-
-    const depositTx = await program.methods
-      .depositReflections(new BN(reflectionAmount.toString()))
-      .accounts({
-        authority: wallet.publicKey,
-        tokenMint: KERNEL_MINT,
-        config: configPda,
-        authorityTokenAccount: feeAuthorityAta,
-        reflectionPool: reflectionPoolPda,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
-      })
-      .signers([wallet])
-      .rpc();
+  // 4. Harvest and split on-chain - fees never pass through this wallet.
+  // Source accounts are batched to stay under the transaction size limit;
+  // every batch also withdraws whatever is already withheld on the mint.
+  console.log('\n🌾 Harvesting and distributing on-chain...');
 
-    txSignatures.push(depositTx);
-    console.log('Deposited reflections:', depositTx);
+  const batches: PublicKey[][] = [];
+  for (let i = 0; i < withheldSources.length; i += HARVEST_BATCH_SIZE) {
+    batches.push(withheldSources.slice(i, i + HARVEST_BATCH_SIZE));
   }
+  if (batches.length === 0) batches.push([]);
 
-  // 8. Burn tokens
-  if (burnAmount > BigInt(0)) {
-    console.log('\n🔥 Burning tokens...');
-
-    const burnTx = await program.methods
-      .burnTokens(new BN(burnAmount.toString()))
+  for (const batch of batches) {
+    const tx = await program.methods
+      .harvestAndDistribute()
       .accounts({
-        authority: wallet.publicKey,
+        harvester: wallet.publicKey,
         tokenMint: KERNEL_MINT,
         config: configPda,
-        authorityTokenAccount: feeAuthorityAta,
+        feeVault: feeVaultPda,
+        reflectionPool: reflectionPoolPda,
+        lpVault: lpVaultPda,
+        lpVaultToken: lpVaultTokenPda,
         burnRecord: burnRecordPda,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
-        systemProgram: PublicKey.default,
+        systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(
+        batch.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+      )
       .signers([wallet])
       .rpc();
 
-    txSignatures.push(burnTx);
-    console.log('Burned tokens:', burnTx);
-  }
-
-  // 9. LP handling (keep tokens in authority account for LP provision)
-  // In production, this would swap half for SOL and add to Raydium/Orca LP
-  if (lpAmount > BigInt(0)) {
-    console.log('\n💧 LP tokens held for liquidity provision');
-    console.log('  Amount:', formatTokens(lpAmount));
-    console.log('  → In production: Auto-add to Raydium/Orca LP');
+    txSignatures.push(tx);
+    console.log('Harvested batch:', tx);
   }
 
   return {
@@ -4,7 +4,7 @@
  */
 
 import { Connection, Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
-import { AnchorProvider, Program, Wallet } from '@coral-xyz/anchor';
+import { AnchorProvider, Program, Wallet, BN } from '@coral-xyz/anchor';
 import {
   TOKEN_2022_PROGRAM_ID,
   createInitializeMintInstruction,
@@ -25,6 +25,9 @@ const REFLECTION_SHARE_BPS = 200; // 2%
 const LP_SHARE_BPS = 200;         // 2%
 const BURN_SHARE_BPS = 100;       // 1%
 
+// Governance timelock for every action type (min 5 minutes, use short delays on devnet)
+const ACTION_DELAY_SECONDS = Number(process.env.ACTION_DELAY_SECONDS || 24 * 60 * 60);
+
 // Token config
 const DECIMALS = 6;
 const TOTAL_SUPPLY = 10_000_000_000; // 10 billion
@@ -166,7 +169,7 @@ async function main() {
   const program = new Program(IDL, provider);
 
   const initTx = await program.methods
-    .initialize(REFLECTION_SHARE_BPS, LP_SHARE_BPS, BURN_SHARE_BPS)
+    .initialize(REFLECTION_SHARE_BPS, LP_SHARE_BPS, BURN_SHARE_BPS, new BN(ACTION_DELAY_SECONDS))
     .accounts({
       authority: authority.publicKey,
       tokenMint: mintKeypair.publicKey,
@@ -181,6 +184,25 @@ async function main() {
 
   console.log('✅ Program initialized:', initTx);
 
+  // Every role starts with the authority; split them later through governance
+  const [rolesPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('roles'), configPda.toBuffer()],
+    KERNEL_PROGRAM_ID
+  );
+  const rolesTx = await program.methods
+    .initializeRoles()
+    .accounts({
+      authority: authority.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      config: configPda,
+      roles: rolesPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([authority])
+    .rpc();
+
+  console.log('✅ Roles initialized:', rolesTx);
+
   // Update constants file
   console.log('\n📝 Updating constants...');
   const constantsPath = path.join(__dirname, '..', 'app', 'src', 'lib', 'constants.ts');
@@ -23,6 +23,9 @@ const REFLECTION_SHARE_BPS = 200; // 2%
 const LP_SHARE_BPS = 200;         // 2%
 const BURN_SHARE_BPS = 100;       // 1%
 
+// Governance timelock for every action type (min 5 minutes, use short delays on devnet)
+const ACTION_DELAY_SECONDS = Number(process.env.ACTION_DELAY_SECONDS || 24 * 60 * 60);
+
 // Load IDL
 const idlPath = path.join(__dirname, '..', 'target', 'idl', 'kernel_token.json');
 const IDL = JSON.parse(fs.readFileSync(idlPath, 'utf-8'));
@@ -104,7 +107,7 @@ async function main() {
 
     // Initialize the program
     const tx = await program.methods
-      .initialize(REFLECTION_SHARE_BPS, LP_SHARE_BPS, BURN_SHARE_BPS)
+      .initialize(REFLECTION_SHARE_BPS, LP_SHARE_BPS, BURN_SHARE_BPS, new BN(ACTION_DELAY_SECONDS))
       .accounts({
         authority: wallet.publicKey,
         tokenMint: KERNEL_MINT,
@@ -121,6 +124,25 @@ async function main() {
     console.log('Transaction:', tx);
     console.log('View on Solscan: https://solscan.io/tx/' + tx + '?cluster=devnet');
 
+    // Every role starts with the authority; split them later through governance
+    const [rolesPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('roles'), configPda.toBuffer()],
+      KERNEL_PROGRAM_ID
+    );
+    const rolesTx = await program.methods
+      .initializeRoles()
+      .accounts({
+        authority: wallet.publicKey,
+        tokenMint: KERNEL_MINT,
+        config: configPda,
+        roles: rolesPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([wallet])
+      .rpc();
+
+    console.log('✅ Roles initialized:', rolesTx);
+
     // Verify initialization by checking the config account exists
     const verifyConfig = await connection.getAccountInfo(configPda);
     if (verifyConfig) {
@@ -104,7 +104,7 @@ async function main() {
   console.log("  2. Transfer 20% to Airdrop pool wallet");
   console.log("  3. Transfer 15% to Marketing/Dev wallet");
   console.log("  4. Burn 10% (send to burn address)");
-  console.log("  5. Transfer 5% to Team vesting wallet");
+  console.log("  5. Lock 5% for the team with create_vesting (one schedule per member)");
   console.log("\nTo execute distribution, update wallet addresses in script.");
 
   // For devnet testing, just show what would happen
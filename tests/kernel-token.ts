@@ -9,6 +9,7 @@ import {
 } from "@solana/web3.js";
 import {
   TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
   createMint,
   createAssociatedTokenAccount,
   mintTo,
@@ -16,8 +17,16 @@ import {
   getMint,
   getAssociatedTokenAddressSync,
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeMintInstruction,
+  transferChecked,
+  getTransferFeeAmount,
 } from "@solana/spl-token";
 import { assert, expect } from "chai";
+import { createHash } from "crypto";
 
 describe("kernel-token", () => {
   // Configure the client to use the local cluster
@@ -38,6 +47,7 @@ describe("kernel-token", () => {
   let configPda: PublicKey;
   let stakingVaultPda: PublicKey;
   let reflectionPoolPda: PublicKey;
+  let rolesPda: PublicKey;
 
   // Token accounts
   let authorityTokenAccount: PublicKey;
@@ -49,6 +59,120 @@ describe("kernel-token", () => {
   const REFLECTION_BPS = 200; // 2%
   const LP_BPS = 200; // 2%
   const BURN_BPS = 100; // 1%
+  const ACTION_DELAY = new anchor.BN(24 * 60 * 60); // Timelock for every action type
+  const PAUSE_STAKE = 1 << 0;
+  const PAUSE_BURNS = 1 << 4;
+  const PAUSE_ALL = 0x1f; // Stake, reward deposits, airdrop claims, LP allocation, burns
+  const PAUSE_ALL_PAYLOAD = {
+    setPauseFlags: { flags: PAUSE_ALL, pausedUntil: new anchor.BN(0) },
+  };
+
+  // Governance actions live at a PDA keyed by the config's running nonce
+  const governanceActionPda = (config: PublicKey, nonce: number | anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("governance_action"),
+        config.toBuffer(),
+        new anchor.BN(nonce).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  // Role table for a config; initialize_roles hands every role to the authority
+  const rolesPdaFor = (config: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("roles"), config.toBuffer()], program.programId)[0];
+
+  const initializeRoles = (mint: PublicKey, config: PublicKey) =>
+    program.methods
+      .initializeRoles()
+      .accounts({
+        authority: authority.publicKey,
+        tokenMint: mint,
+        config,
+        roles: rolesPdaFor(config),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+  const proposeAction = async (payload: any, signer: Keypair = authority) => {
+    const config = await program.account.kernelConfig.fetch(configPda);
+    const governanceAction = governanceActionPda(configPda, config.governanceActionCount);
+    await program.methods
+      .proposeAction(payload)
+      .accounts({
+        authority: signer.publicKey,
+        tokenMint,
+        config: configPda,
+        governanceAction,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+    return governanceAction;
+  };
+
+  const executeAction = (governanceAction: PublicKey, targets: any = {}, signer = authority) =>
+    program.methods
+      .executeAction()
+      .accounts({
+        authority: signer.publicKey,
+        tokenMint,
+        config: configPda,
+        governanceAction,
+        stakingTiers: null,
+        guardianSet: null,
+        lpVault: null,
+        roles: null,
+        ...targets,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  const cancelAction = (governanceAction: PublicKey, signer = authority) =>
+    program.methods
+      .cancelAction()
+      .accounts({
+        authority: signer.publicKey,
+        tokenMint,
+        config: configPda,
+        governanceAction,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  // Replaces the main config's pause mask (signed by the pauser role)
+  const setPaused = (flags: number, pausedUntil = 0, signer = authority) =>
+    program.methods
+      .setPaused(flags, new anchor.BN(pausedUntil))
+      .accounts({
+        authority: signer.publicKey,
+        tokenMint,
+        config: configPda,
+        roles: rolesPda,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  // emit_cpi! events are self-CPIs: strip the 8-byte event ix tag and
+  // decode the rest with the program's event coder
+  const cpiEvents = async (signature: string) => {
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const keys = tx!.transaction.message.getAccountKeys();
+    return tx!.meta!.innerInstructions!.flatMap((inner) =>
+      inner.instructions
+        .filter((ix) => keys.get(ix.programIdIndex)!.equals(program.programId))
+        .map((ix) =>
+          program.coder.events.decode(
+            Buffer.from(anchor.utils.bytes.bs58.decode(ix.data).subarray(8)).toString("base64")
+          )
+        )
+        .filter((event) => event !== null)
+    );
+  };
 
   before(async () => {
     // Generate test keypairs
@@ -100,6 +224,8 @@ describe("kernel-token", () => {
       program.programId
     );
 
+    rolesPda = rolesPdaFor(configPda);
+
     console.log("Config PDA:", configPda.toBase58());
     console.log("Staking Vault PDA:", stakingVaultPda.toBase58());
     console.log("Reflection Pool PDA:", reflectionPoolPda.toBase58());
@@ -179,7 +305,7 @@ describe("kernel-token", () => {
   describe("Initialize", () => {
     it("initializes the program correctly", async () => {
       const tx = await program.methods
-        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS)
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
         .accounts({
           authority: authority.publicKey,
           tokenMint,
@@ -203,7 +329,27 @@ describe("kernel-token", () => {
       assert.equal(config.lpShareBps, LP_BPS);
       assert.equal(config.burnShareBps, BURN_BPS);
       assert.equal(config.totalStaked.toNumber(), 0);
-      assert.equal(config.isPaused, false);
+      assert.equal(config.pauseFlags, 0);
+      assert.equal(config.pausedUntil.toNumber(), 0);
+      config.actionDelays.forEach((delay: anchor.BN) =>
+        assert.equal(delay.toString(), ACTION_DELAY.toString())
+      );
+    });
+
+    it("hands every role to the authority", async () => {
+      await initializeRoles(tokenMint, configPda);
+
+      const roles = await program.account.roles.fetch(rolesPda);
+      for (const holder of [
+        roles.admin,
+        roles.pauser,
+        roles.reflectionDepositor,
+        roles.lpOperator,
+        roles.burner,
+        roles.airdropManager,
+      ]) {
+        assert.equal(holder.toBase58(), authority.publicKey.toBase58());
+      }
     });
 
     it("fails with invalid fee configuration", async () => {
@@ -236,7 +382,7 @@ describe("kernel-token", () => {
 
       try {
         await program.methods
-          .initialize(100, 100, 100) // Total 300, should be 500
+          .initialize(100, 100, 100, ACTION_DELAY) // Total 300, should be 500
           .accounts({
             authority: authority.publicKey,
             tokenMint: newMint,
@@ -254,6 +400,44 @@ describe("kernel-token", () => {
         expect(err.message).to.include("InvalidFeeConfig");
       }
     });
+
+    it("fails with an action delay below the floor", async () => {
+      const newMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        9,
+        Keypair.generate(),
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      const pda = (seed: string) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from(seed), newMint.toBuffer()],
+          program.programId
+        )[0];
+
+      try {
+        await program.methods
+          .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, new anchor.BN(60)) // Floor is 5 minutes
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint: newMint,
+            stakingVault: pda("staking_vault"),
+            reflectionPool: pda("reflection_pool"),
+            config: pda("config"),
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown InvalidActionDelay error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidActionDelay");
+      }
+    });
   });
 
   describe("Staking", () => {
@@ -273,7 +457,7 @@ describe("kernel-token", () => {
       );
 
       const tx = await program.methods
-        .stake(STAKE_AMOUNT)
+        .stake(STAKE_AMOUNT, null)
         .accounts({
           owner: user1.publicKey,
           tokenMint,
@@ -281,6 +465,7 @@ describe("kernel-token", () => {
           userTokenAccount: user1TokenAccount,
           stakingVault: stakingVaultPda,
           userStake: userStakePda,
+          stakingTiers: null,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -331,7 +516,7 @@ describe("kernel-token", () => {
 
       try {
         await program.methods
-          .stake(new anchor.BN(0))
+          .stake(new anchor.BN(0), null)
           .accounts({
             owner: user2.publicKey,
             tokenMint,
@@ -339,6 +524,7 @@ describe("kernel-token", () => {
             userTokenAccount: user2TokenAccount,
             stakingVault: stakingVaultPda,
             userStake: userStakePda,
+            stakingTiers: null,
             tokenProgram: TOKEN_2022_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -362,7 +548,7 @@ describe("kernel-token", () => {
       const stakeBefore = await program.account.userStake.fetch(userStakePda);
 
       const tx = await program.methods
-        .stake(additionalAmount)
+        .stake(additionalAmount, null)
         .accounts({
           owner: user1.publicKey,
           tokenMint,
@@ -370,6 +556,7 @@ describe("kernel-token", () => {
           userTokenAccount: user1TokenAccount,
           stakingVault: stakingVaultPda,
           userStake: userStakePda,
+          stakingTiers: null,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -484,6 +671,7 @@ describe("kernel-token", () => {
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
+          roles: rolesPda,
           authorityTokenAccount,
           reflectionPool: reflectionPoolPda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -582,6 +770,34 @@ describe("kernel-token", () => {
         expect(err.message).to.include("NothingToClaim");
       }
     });
+
+    it("syncs the reflection pool against pending reflections", async () => {
+      await program.methods
+        .syncReflectionPool()
+        .accounts({
+          tokenMint,
+          config: configPda,
+          reflectionPool: reflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+
+      const pool = await getAccount(
+        connection,
+        reflectionPoolPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      const config = await program.account.kernelConfig.fetch(configPda);
+
+      // Claims round down, so the pool only ever ends up with spare dust
+      assert.equal(config.reflectionDeficit.toNumber(), 0);
+      assert.equal(
+        config.reflectionSurplus.toString(),
+        new anchor.BN(pool.amount.toString()).sub(config.pendingReflections).toString()
+      );
+      assert.equal(config.reflectionOwed.toNumber(), 0);
+    });
   });
 
   describe("Burn", () => {
@@ -606,6 +822,7 @@ describe("kernel-token", () => {
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
+          roles: rolesPda,
           authorityTokenAccount,
           burnRecord: burnRecordPda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -638,20 +855,27 @@ describe("kernel-token", () => {
   });
 
   describe("Admin Functions", () => {
-    it("pauses and unpauses the program", async () => {
-      // Pause
-      await program.methods
-        .setPaused(true)
+    let transferAction: PublicKey;
+    let newAuthority: Keypair;
+
+    const acceptAuthority = (signer: Keypair) =>
+      program.methods
+        .acceptAuthority()
         .accounts({
-          authority: authority.publicKey,
+          newAuthority: signer.publicKey,
           tokenMint,
           config: configPda,
+          governanceAction: transferAction,
         })
-        .signers([authority])
-        .rpc();
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    it("pauses and unpauses staking", async () => {
+      // Pause
+      await setPaused(PAUSE_STAKE);
 
       let config = await program.account.kernelConfig.fetch(configPda);
-      assert.equal(config.isPaused, true);
+      assert.equal(config.pauseFlags, PAUSE_STAKE);
 
       // Try to stake while paused
       const [userStakePda] = PublicKey.findProgramAddressSync(
@@ -661,7 +885,7 @@ describe("kernel-token", () => {
 
       try {
         await program.methods
-          .stake(new anchor.BN(1000))
+          .stake(new anchor.BN(1000), null)
           .accounts({
             owner: user2.publicKey,
             tokenMint,
@@ -669,6 +893,7 @@ describe("kernel-token", () => {
             userTokenAccount: user2TokenAccount,
             stakingVault: stakingVaultPda,
             userStake: userStakePda,
+            stakingTiers: null,
             tokenProgram: TOKEN_2022_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -681,512 +906,442 @@ describe("kernel-token", () => {
       }
 
       // Unpause
-      await program.methods
-        .setPaused(false)
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint,
-          config: configPda,
-        })
-        .signers([authority])
-        .rpc();
+      await setPaused(0);
 
       config = await program.account.kernelConfig.fetch(configPda);
-      assert.equal(config.isPaused, false);
+      assert.equal(config.pauseFlags, 0);
     });
 
-    it("updates fee configuration with guardian co-signature", async () => {
-      const newReflection = 250; // 2.5%
-      const newLp = 150; // 1.5%
-      const newBurn = 100; // 1%
+    it("still lets users unstake and claim with every pause flag set", async () => {
+      const [userStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
 
-      // Emergency fee update requires both authority AND guardian signatures (multisig)
+      // Give user1 something to claim before deposits are paused too
       await program.methods
-        .updateFees(newReflection, newLp, newBurn)
+        .depositReflections(new anchor.BN(10_000 * 10 ** 9))
         .accounts({
           authority: authority.publicKey,
-          guardian: guardian.publicKey,
           tokenMint,
           config: configPda,
+          roles: rolesPda,
+          authorityTokenAccount,
+          reflectionPool: reflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
         })
-        .signers([authority, guardian])
-        .rpc();
-
-      const config = await program.account.kernelConfig.fetch(configPda);
-      assert.equal(config.reflectionShareBps, newReflection);
-      assert.equal(config.lpShareBps, newLp);
-      assert.equal(config.burnShareBps, newBurn);
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
 
-      // Reset to original (also requires multisig)
-      await program.methods
-        .updateFees(REFLECTION_BPS, LP_BPS, BURN_BPS)
-        .accounts({
-          authority: authority.publicKey,
-          guardian: guardian.publicKey,
-          tokenMint,
-          config: configPda,
-        })
-        .signers([authority, guardian])
-        .rpc();
-    });
+      await setPaused(PAUSE_ALL);
 
-    it("fails emergency update without guardian signature", async () => {
       try {
-        // Attempt update with only authority (missing guardian)
+        const stakeBefore = await program.account.userStake.fetch(userStakePda);
+
         await program.methods
-          .updateFees(300, 100, 100)
+          .claimReflections()
           .accounts({
-            authority: authority.publicKey,
-            guardian: user1.publicKey, // Wrong signer - user1 is not signing
+            owner: user1.publicKey,
             tokenMint,
             config: configPda,
+            userTokenAccount: user1TokenAccount,
+            reflectionPool: reflectionPoolPda,
+            userStake: userStakePda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
           })
-          .signers([authority]) // Only authority signing, guardian missing
-          .rpc();
+          .signers([user1])
+          .rpc({ commitment: "confirmed" });
 
-        assert.fail("Should have thrown error - guardian signature required");
-      } catch (err: any) {
-        // Expected: signature verification failure or missing signer error
-        expect(err.message).to.satisfy((msg: string) =>
-          msg.includes("Signature") || msg.includes("signature") || msg.includes("unknown signer")
+        const unstakeAmount = new anchor.BN(1_000 * 10 ** 9);
+        await program.methods
+          .unstake(unstakeAmount)
+          .accounts({
+            owner: user1.publicKey,
+            tokenMint,
+            config: configPda,
+            userTokenAccount: user1TokenAccount,
+            stakingVault: stakingVaultPda,
+            userStake: userStakePda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([user1])
+          .rpc({ commitment: "confirmed" });
+
+        const stakeAfter = await program.account.userStake.fetch(userStakePda);
+        assert.isTrue(stakeAfter.totalClaimed.gt(stakeBefore.totalClaimed));
+        assert.equal(
+          stakeAfter.stakedAmount.toString(),
+          stakeBefore.stakedAmount.sub(unstakeAmount).toString()
         );
+      } finally {
+        await setPaused(0);
       }
     });
 
-    it("prevents non-authority from proposing authority transfer", async () => {
-      // This test must run BEFORE any proposal is created (no PDA exists yet)
-      const newAuthority = Keypair.generate();
-
-      const [pendingTransferPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("pending_authority_transfer"), configPda.toBuffer()],
+    it("pauses burns on their own flag until the pause expires", async () => {
+      const [burnRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("burn"), configPda.toBuffer()],
         program.programId
       );
-
-      try {
-        await program.methods
-          .proposeAuthorityTransfer(newAuthority.publicKey)
+      const burn = () =>
+        program.methods
+          .burnTokens(new anchor.BN(1_000 * 10 ** 9))
           .accounts({
-            authority: user1.publicKey,
+            authority: authority.publicKey,
             tokenMint,
             config: configPda,
-            pendingTransfer: pendingTransferPda,
+            roles: rolesPda,
+            authorityTokenAccount,
+            burnRecord: burnRecordPda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([user1])
-          .rpc();
+          .signers([authority])
+          .rpc({ commitment: "confirmed" });
 
-        assert.fail("Should have thrown authority error");
+      await setPaused(PAUSE_BURNS, Math.floor(Date.now() / 1000) + 3);
+
+      try {
+        await burn();
+        assert.fail("Should have thrown ProgramPaused error");
+      } catch (err: any) {
+        expect(err.message).to.include("ProgramPaused");
+      }
+
+      // The flag is still set, but the pause has lifted itself
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+      await burn();
+
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.pauseFlags, PAUSE_BURNS);
+      await setPaused(0);
+    });
+
+    it("rejects unknown pause flags and past expiries", async () => {
+      try {
+        await setPaused(1 << 5);
+        assert.fail("Should have thrown InvalidPause error");
       } catch (err: any) {
-        const isAuthError = err.message.includes("NotAuthority") ||
-                           err.message.includes("ConstraintHasOne") ||
-                           err.message.includes("has_one") ||
-                           err.message.includes("A has one constraint");
-        expect(isAuthError).to.be.true;
+        expect(err.message).to.include("InvalidPause");
+      }
+
+      try {
+        await setPaused(PAUSE_STAKE, 1);
+        assert.fail("Should have thrown InvalidPause error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidPause");
       }
     });
 
-    it("proposes authority transfer with timelock", async () => {
+    it("prevents non-authority from proposing governance actions", async () => {
       const newAuthority = Keypair.generate();
 
-      // Derive PDA for pending transfer
-      const [pendingTransferPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("pending_authority_transfer"), configPda.toBuffer()],
-        program.programId
-      );
+      try {
+        await proposeAction({ transferAuthority: { newAuthority: newAuthority.publicKey } }, user1);
+
+        assert.fail("Should have thrown authority error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotAuthority");
+      }
+    });
+
+    it("queues authority transfer behind the timelock", async () => {
+      newAuthority = Keypair.generate();
 
       // Propose authority transfer (starts 24-hour timelock)
-      await program.methods
-        .proposeAuthorityTransfer(newAuthority.publicKey)
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint,
-          config: configPda,
-          pendingTransfer: pendingTransferPda,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([authority])
-        .rpc();
+      transferAction = await proposeAction({
+        transferAuthority: { newAuthority: newAuthority.publicKey },
+      });
 
-      // Verify proposal was created
-      const transfer = await program.account.pendingAuthorityTransfer.fetch(pendingTransferPda);
-      assert.equal(transfer.proposer.toBase58(), authority.publicKey.toBase58());
-      assert.equal(transfer.newAuthority.toBase58(), newAuthority.publicKey.toBase58());
-      assert.equal(transfer.executed, false);
-      assert.equal(transfer.cancelled, false);
+      // Verify the action was queued with its execution window
+      const action = await program.account.governanceAction.fetch(transferAction);
+      assert.equal(action.proposer.toBase58(), authority.publicKey.toBase58());
+      assert.equal(
+        action.payload.transferAuthority.newAuthority.toBase58(),
+        newAuthority.publicKey.toBase58()
+      );
+      assert.equal(action.eta.sub(action.proposedAt).toNumber(), 24 * 60 * 60);
+      assert.equal(action.expiresAt.sub(action.eta).toNumber(), 7 * 24 * 60 * 60);
+      assert.equal(action.executed, false);
+      assert.equal(action.cancelled, false);
 
       // Trying to execute before timelock should fail
       try {
-        await program.methods
-          .executeAuthorityTransfer()
-          .accounts({
-            authority: authority.publicKey,
-            tokenMint,
-            config: configPda,
-            pendingTransfer: pendingTransferPda,
-          })
-          .signers([authority])
-          .rpc();
-
+        await executeAction(transferAction);
         assert.fail("Should have thrown TimelockNotExpired error");
       } catch (err: any) {
         expect(err.message).to.include("TimelockNotExpired");
       }
+    });
 
-      // Cancel the transfer instead of waiting 24 hours
-      await program.methods
-        .cancelAuthorityTransfer()
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint,
-          config: configPda,
-          pendingTransfer: pendingTransferPda,
-        })
-        .signers([authority])
-        .rpc();
+    it("only lets the proposed authority accept, after the timelock", async () => {
+      try {
+        await acceptAuthority(user1);
+        assert.fail("Should have thrown NotPendingAuthority error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotPendingAuthority");
+      }
+
+      try {
+        await acceptAuthority(newAuthority);
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
 
-      // Verify authority unchanged
       const config = await program.account.kernelConfig.fetch(configPda);
       assert.equal(config.authority.toBase58(), authority.publicKey.toBase58());
     });
 
-    it("prevents non-authority from executing authority transfer", async () => {
-      // Use the existing pending transfer from the previous test
-      // (the cancel set cancelled=true but account still exists)
-      const [pendingTransferPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("pending_authority_transfer"), configPda.toBuffer()],
-        program.programId
-      );
-
+    it("prevents non-authority from executing governance actions", async () => {
       try {
-        await program.methods
-          .executeAuthorityTransfer()
-          .accounts({
-            authority: user1.publicKey,
-            tokenMint,
-            config: configPda,
-            pendingTransfer: pendingTransferPda,
-          })
-          .signers([user1])
-          .rpc();
-
+        await executeAction(transferAction, {}, user1);
         assert.fail("Should have thrown authority error");
       } catch (err: any) {
-        // Anchor constraint errors may appear as "ConstraintHasOne" or custom error
-        const isAuthError = err.message.includes("NotAuthority") ||
-                           err.message.includes("ConstraintHasOne") ||
-                           err.message.includes("has_one") ||
-                           err.message.includes("A has one constraint") ||
-                           err.message.includes("AuthorityTransferCancelled");
-        expect(isAuthError).to.be.true;
+        expect(err.message).to.include("NotAuthority");
       }
     });
 
-    it("prevents non-authority from admin functions", async () => {
+    it("prevents callers without the pauser role from pausing", async () => {
       try {
-        await program.methods
-          .setPaused(true)
-          .accounts({
-            authority: user1.publicKey,
-            tokenMint,
-            config: configPda,
-          })
-          .signers([user1])
-          .rpc();
+        await setPaused(PAUSE_ALL, 0, user1);
 
         assert.fail("Should have thrown error");
       } catch (err: any) {
-        expect(err.message).to.include("NotAuthority");
+        expect(err.message).to.include("MissingRole");
       }
     });
 
-    it("prevents non-authority from cancelling authority transfer", async () => {
-      // PDA already exists from "proposes authority transfer with timelock" test
-      // which was cancelled, so we use the existing PDA
-      const [pendingTransferPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("pending_authority_transfer"), configPda.toBuffer()],
-        program.programId
-      );
-
-      // Non-authority tries to cancel the already-cancelled transfer
-      // This should still fail with authority error
+    it("prevents non-authority from cancelling governance actions", async () => {
       try {
-        await program.methods
-          .cancelAuthorityTransfer()
-          .accounts({
-            authority: user1.publicKey,
-            tokenMint,
-            config: configPda,
-            pendingTransfer: pendingTransferPda,
-          })
-          .signers([user1])
-          .rpc();
-
+        await cancelAction(transferAction, user1);
         assert.fail("Should have thrown authority error");
       } catch (err: any) {
-        const isAuthError = err.message.includes("NotAuthority") ||
-                           err.message.includes("ConstraintHasOne") ||
-                           err.message.includes("has_one") ||
-                           err.message.includes("A has one constraint");
-        expect(isAuthError).to.be.true;
+        expect(err.message).to.include("NotAuthority");
       }
     });
 
-    it("prevents executing a cancelled authority transfer", async () => {
-      // PDA already exists from "proposes authority transfer with timelock" test
-      // which was cancelled
-      const [pendingTransferPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("pending_authority_transfer"), configPda.toBuffer()],
-        program.programId
-      );
+    it("prevents executing a cancelled governance action", async () => {
+      // Cancel the transfer instead of waiting 24 hours
+      await cancelAction(transferAction);
+
+      // Verify authority unchanged
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.authority.toBase58(), authority.publicKey.toBase58());
 
-      // Try to execute the already-cancelled transfer
       try {
-        await program.methods
-          .executeAuthorityTransfer()
-          .accounts({
-            authority: authority.publicKey,
-            tokenMint,
-            config: configPda,
-            pendingTransfer: pendingTransferPda,
-          })
-          .signers([authority])
-          .rpc();
+        await executeAction(transferAction);
+        assert.fail("Should have thrown ProposalCancelled error");
+      } catch (err: any) {
+        expect(err.message).to.include("ProposalCancelled");
+      }
 
-        assert.fail("Should have thrown AuthorityTransferCancelled error");
+      try {
+        await acceptAuthority(newAuthority);
+        assert.fail("Should have thrown ProposalCancelled error");
       } catch (err: any) {
-        expect(err.message).to.include("AuthorityTransferCancelled");
+        expect(err.message).to.include("ProposalCancelled");
       }
     });
   });
 
-  describe("Airdrop Registration", () => {
-    it("registers airdrop", async () => {
-      const [airdropStatePda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("airdrop"), configPda.toBuffer()],
+  describe("Airdrop Campaigns", () => {
+    const CAMPAIGN_ID = new anchor.BN(1);
+    let campaignPda: PublicKey;
+    let campaignVaultPda: PublicKey;
+
+    const USER1_ALLOCATION = new anchor.BN(1000 * 10 ** 9); // 1000 tokens
+    const USER2_ALLOCATION = new anchor.BN(500 * 10 ** 9); // 500 tokens
+    const BUDGET = USER1_ALLOCATION.add(USER2_ALLOCATION);
+
+    // Leaf = sha256(0x00 || claimant || amount_le), node = sha256(0x01 || lo || hi)
+    const leafFor = (claimant: PublicKey, amount: anchor.BN) =>
+      createHash("sha256")
+        .update(Buffer.from([0]))
+        .update(claimant.toBuffer())
+        .update(amount.toArrayLike(Buffer, "le", 8))
+        .digest();
+    const nodeFor = (a: Buffer, b: Buffer) => {
+      const [lo, hi] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+      return createHash("sha256").update(Buffer.from([1])).update(lo).update(hi).digest();
+    };
+
+    let user1Leaf: Buffer;
+    let user2Leaf: Buffer;
+
+    before(() => {
+      [campaignPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("campaign"),
+          configPda.toBuffer(),
+          CAMPAIGN_ID.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      [campaignVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("campaign_vault"), campaignPda.toBuffer()],
         program.programId
       );
 
-      const recipients = [user1.publicKey, user2.publicKey];
-      const amountPerRecipient = new anchor.BN(1000 * 10 ** 9); // 1000 tokens each
+      user1Leaf = leafFor(user1.publicKey, USER1_ALLOCATION);
+      user2Leaf = leafFor(user2.publicKey, USER2_ALLOCATION);
+    });
 
-      const tx = await program.methods
-        .airdrop(recipients, amountPerRecipient)
+    const claimReceiptFor = (claimant: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("airdrop_claim"), campaignPda.toBuffer(), claimant.toBuffer()],
+        program.programId
+      )[0];
+
+    const claimAccounts = (claimant: Keypair, claimantTokenAccount: PublicKey) => ({
+      claimant: claimant.publicKey,
+      tokenMint,
+      config: configPda,
+      campaign: campaignPda,
+      campaignVault: campaignVaultPda,
+      claimantTokenAccount,
+      claimReceipt: claimReceiptFor(claimant.publicKey),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("creates a campaign in draft", async () => {
+      const root = nodeFor(user1Leaf, user2Leaf);
+      const now = Math.floor(Date.now() / 1000);
+
+      await program.methods
+        .createCampaign(
+          CAMPAIGN_ID,
+          [...root],
+          BUDGET,
+          new anchor.BN(now - 60),
+          new anchor.BN(now + 3600)
+        )
         .accounts({
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
-          airdropState: airdropStatePda,
+          roles: rolesPda,
+          campaign: campaignPda,
+          campaignVault: campaignVaultPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
         .signers([authority])
         .rpc();
 
-      console.log("Airdrop registration tx:", tx);
-
-      const airdropState = await program.account.airdropState.fetch(airdropStatePda);
-      assert.equal(
-        airdropState.totalAirdropped.toNumber(),
-        amountPerRecipient.toNumber() * recipients.length
-      );
-      assert.equal(airdropState.recipientCount.toNumber(), recipients.length);
+      const campaign = await program.account.campaign.fetch(campaignPda);
+      assert.deepEqual(Buffer.from(campaign.merkleRoot), root);
+      assert.equal(campaign.budget.toString(), BUDGET.toString());
+      assert.deepEqual(campaign.status, { draft: {} });
     });
-  });
 
-  describe("Fee Proposal Timelock", () => {
-    let feeProposalPda: PublicKey;
+    it("rejects claims before the campaign is funded", async () => {
+      try {
+        await program.methods
+          .claimAirdrop(USER1_ALLOCATION, [[...user2Leaf]])
+          .accounts(claimAccounts(user1, user1TokenAccount))
+          .signers([user1])
+          .rpc();
 
-    before(() => {
-      [feeProposalPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("fee_proposal"), configPda.toBuffer()],
-        program.programId
-      );
+        assert.fail("Should have thrown CampaignNotActive error");
+      } catch (err: any) {
+        expect(err.message).to.include("CampaignNotActive");
+      }
     });
 
-    it("proposes fee update with timelock", async () => {
-      const newReflection = 300; // 3%
-      const newLp = 100; // 1%
-      const newBurn = 100; // 1%
-
-      const tx = await program.methods
-        .proposeFeeUpdate(newReflection, newLp, newBurn)
+    it("activates the campaign once fully funded", async () => {
+      await program.methods
+        .fundCampaign(BUDGET)
         .accounts({
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
-          feeProposal: feeProposalPda,
-          systemProgram: SystemProgram.programId,
+          roles: rolesPda,
+          campaign: campaignPda,
+          campaignVault: campaignVaultPda,
+          authorityTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
         })
         .signers([authority])
         .rpc();
 
-      console.log("Fee proposal tx:", tx);
-
-      // Verify proposal was created
-      const proposal = await program.account.feeProposal.fetch(feeProposalPda);
-      assert.equal(proposal.proposer.toBase58(), authority.publicKey.toBase58());
-      assert.equal(proposal.reflectionShareBps, newReflection);
-      assert.equal(proposal.lpShareBps, newLp);
-      assert.equal(proposal.burnShareBps, newBurn);
-      assert.equal(proposal.executed, false);
-      assert.equal(proposal.cancelled, false);
+      const campaign = await program.account.campaign.fetch(campaignPda);
+      assert.equal(campaign.totalFunded.toString(), BUDGET.toString());
+      assert.deepEqual(campaign.status, { active: {} });
     });
 
-    it("fails to execute before timelock expires", async () => {
-      try {
-        await program.methods
-          .executeFeeUpdate()
-          .accounts({
-            authority: authority.publicKey,
-            tokenMint,
-            config: configPda,
-            feeProposal: feeProposalPda,
-          })
-          .signers([authority])
-          .rpc();
-
-        assert.fail("Should have thrown TimelockNotExpired error");
-      } catch (err: any) {
-        expect(err.message).to.include("TimelockNotExpired");
-      }
-    });
-
-    it("fails proposal with invalid fee total", async () => {
-      // Create a new mint to test invalid proposal
-      const newMint = await createMint(
+    it("allows a recipient to claim with a valid proof", async () => {
+      const balanceBefore = await getAccount(
         connection,
-        authority,
-        authority.publicKey,
-        null,
-        9,
-        Keypair.generate(),
-        { commitment: "confirmed" },
+        user1TokenAccount,
+        "confirmed",
         TOKEN_2022_PROGRAM_ID
       );
 
-      const [newConfigPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("config"), newMint.toBuffer()],
-        program.programId
-      );
-
-      const [newVaultPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("staking_vault"), newMint.toBuffer()],
-        program.programId
-      );
+      await program.methods
+        .claimAirdrop(USER1_ALLOCATION, [[...user2Leaf]])
+        .accounts(claimAccounts(user1, user1TokenAccount))
+        .signers([user1])
+        .rpc();
 
-      const [newPoolPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("reflection_pool"), newMint.toBuffer()],
-        program.programId
+      const balanceAfter = await getAccount(
+        connection,
+        user1TokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
       );
-
-      const [newFeeProposalPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("fee_proposal"), newConfigPda.toBuffer()],
-        program.programId
+      assert.equal(
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        USER1_ALLOCATION.toString()
       );
 
-      // Initialize new config first
-      await program.methods
-        .initialize(200, 200, 100)
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint: newMint,
-          stakingVault: newVaultPda,
-          reflectionPool: newPoolPda,
-          config: newConfigPda,
-          tokenProgram: TOKEN_2022_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([authority])
-        .rpc();
+      const campaign = await program.account.campaign.fetch(campaignPda);
+      assert.equal(campaign.totalClaimed.toString(), USER1_ALLOCATION.toString());
+      assert.equal(campaign.claimCount.toNumber(), 1);
+    });
 
+    it("prevents claiming twice", async () => {
       try {
         await program.methods
-          .proposeFeeUpdate(100, 100, 100) // Total 300, should be 500
-          .accounts({
-            authority: authority.publicKey,
-            tokenMint: newMint,
-            config: newConfigPda,
-            feeProposal: newFeeProposalPda,
-            systemProgram: SystemProgram.programId,
-          })
-          .signers([authority])
+          .claimAirdrop(USER1_ALLOCATION, [[...user2Leaf]])
+          .accounts(claimAccounts(user1, user1TokenAccount))
+          .signers([user1])
           .rpc();
 
-        assert.fail("Should have thrown InvalidFeeConfig error");
+        assert.fail("Should have thrown error");
       } catch (err: any) {
-        expect(err.message).to.include("InvalidFeeConfig");
+        expect(err.message).to.include("already in use");
       }
     });
 
-    it("cancels fee proposal", async () => {
-      const tx = await program.methods
-        .cancelFeeProposal()
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint,
-          config: configPda,
-          feeProposal: feeProposalPda,
-        })
-        .signers([authority])
-        .rpc();
-
-      console.log("Cancel fee proposal tx:", tx);
-
-      // Verify proposal was cancelled
-      const proposal = await program.account.feeProposal.fetch(feeProposalPda);
-      assert.equal(proposal.cancelled, true);
-
-      // Verify config unchanged
-      const config = await program.account.kernelConfig.fetch(configPda);
-      assert.equal(config.reflectionShareBps, REFLECTION_BPS);
-      assert.equal(config.lpShareBps, LP_BPS);
-      assert.equal(config.burnShareBps, BURN_BPS);
-    });
-  });
-
-  describe("LP Vault Operations", () => {
-    let lpVaultPda: PublicKey;
-    let lpVaultTokenPda: PublicKey;
-    const ALLOCATE_AMOUNT = new anchor.BN(10_000 * 10 ** 9); // 10K tokens
-
-    before(() => {
-      [lpVaultPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("lp_vault"), tokenMint.toBuffer()],
-        program.programId
-      );
+    it("rejects an inflated allocation", async () => {
+      try {
+        await program.methods
+          .claimAirdrop(USER2_ALLOCATION.muln(2), [[...user1Leaf]])
+          .accounts(claimAccounts(user2, user2TokenAccount))
+          .signers([user2])
+          .rpc();
 
-      [lpVaultTokenPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("lp_vault_token"), tokenMint.toBuffer()],
-        program.programId
-      );
+        assert.fail("Should have thrown error");
+      } catch (err: any) {
+        expect(err.message).to.match(/InvalidMerkleProof|CampaignBudgetExceeded/);
+      }
     });
 
-    it("initializes LP vault", async () => {
-      const tx = await program.methods
-        .initializeLpVault()
+    it("closes the campaign and sweeps unclaimed tokens", async () => {
+      await program.methods
+        .closeCampaign()
         .accounts({
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
-          lpVault: lpVaultPda,
-          lpVaultToken: lpVaultTokenPda,
-          tokenProgram: TOKEN_2022_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
+          roles: rolesPda,
+          campaign: campaignPda,
         })
         .signers([authority])
         .rpc();
 
-      console.log("Initialize LP vault tx:", tx);
-
-      // Verify LP vault was created
-      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
-      assert.equal(lpVault.authority.toBase58(), authority.publicKey.toBase58());
-      assert.equal(lpVault.tokenMint.toBase58(), tokenMint.toBase58());
-      assert.equal(lpVault.totalAllocated.toNumber(), 0);
-      assert.equal(lpVault.totalDeployed.toNumber(), 0);
-      assert.equal(lpVault.pendingDeployment.toNumber(), 0);
-    });
-
-    it("allocates tokens to LP vault", async () => {
       const balanceBefore = await getAccount(
         connection,
         authorityTokenAccount,
@@ -1194,39 +1349,21 @@ describe("kernel-token", () => {
         TOKEN_2022_PROGRAM_ID
       );
 
-      const tx = await program.methods
-        .allocateToLp(ALLOCATE_AMOUNT)
+      await program.methods
+        .sweepCampaign()
         .accounts({
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
-          lpVault: lpVaultPda,
+          roles: rolesPda,
+          campaign: campaignPda,
+          campaignVault: campaignVaultPda,
           authorityTokenAccount,
-          lpVaultToken: lpVaultTokenPda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
         })
         .signers([authority])
-        .rpc({ commitment: "confirmed" });
-
-      // Wait for confirmation and state propagation
-      await connection.confirmTransaction(tx, "confirmed");
-      console.log("Allocate to LP tx:", tx);
-
-      // Verify LP vault updated
-      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
-      assert.equal(lpVault.totalAllocated.toNumber(), ALLOCATE_AMOUNT.toNumber());
-      assert.equal(lpVault.pendingDeployment.toNumber(), ALLOCATE_AMOUNT.toNumber());
-
-      // Verify vault token balance
-      const vaultBalance = await getAccount(
-        connection,
-        lpVaultTokenPda,
-        "confirmed",
-        TOKEN_2022_PROGRAM_ID
-      );
-      assert.equal(Number(vaultBalance.amount), ALLOCATE_AMOUNT.toNumber());
+        .rpc();
 
-      // Verify authority balance decreased
       const balanceAfter = await getAccount(
         connection,
         authorityTokenAccount,
@@ -1234,83 +1371,463 @@ describe("kernel-token", () => {
         TOKEN_2022_PROGRAM_ID
       );
       assert.equal(
-        Number(balanceBefore.amount) - Number(balanceAfter.amount),
-        ALLOCATE_AMOUNT.toNumber()
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        USER2_ALLOCATION.toString()
       );
+
+      const campaign = await program.account.campaign.fetch(campaignPda);
+      assert.deepEqual(campaign.status, { closed: {} });
     });
+  });
 
-    it("fails to allocate zero amount", async () => {
+  describe("Governance Actions", () => {
+    let feeAction: PublicKey;
+
+    it("proposes fee update with timelock", async () => {
+      const newReflection = 300; // 3%
+      const newLp = 100; // 1%
+      const newBurn = 100; // 1%
+
+      const { governanceActionCount } = await program.account.kernelConfig.fetch(configPda);
+
+      feeAction = await proposeAction({
+        updateFees: {
+          reflectionShareBps: newReflection,
+          lpShareBps: newLp,
+          burnShareBps: newBurn,
+        },
+      });
+
+      // Verify action was queued under the next nonce
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(action.nonce.toString(), governanceActionCount.toString());
+      assert.equal(action.proposer.toBase58(), authority.publicKey.toBase58());
+      assert.equal(action.payload.updateFees.reflectionShareBps, newReflection);
+      assert.equal(action.payload.updateFees.lpShareBps, newLp);
+      assert.equal(action.payload.updateFees.burnShareBps, newBurn);
+      assert.equal(action.executed, false);
+      assert.equal(action.cancelled, false);
+      assert.equal(action.eta.sub(action.proposedAt).toString(), ACTION_DELAY.toString());
+
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(
+        config.governanceActionCount.toString(),
+        governanceActionCount.addn(1).toString()
+      );
+    });
+
+    it("fails to execute before timelock expires", async () => {
       try {
-        await program.methods
-          .allocateToLp(new anchor.BN(0))
-          .accounts({
-            authority: authority.publicKey,
-            tokenMint,
-            config: configPda,
-            lpVault: lpVaultPda,
-            authorityTokenAccount,
-            lpVaultToken: lpVaultTokenPda,
-            tokenProgram: TOKEN_2022_PROGRAM_ID,
-          })
-          .signers([authority])
-          .rpc();
+        await executeAction(feeAction);
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+    });
 
-        assert.fail("Should have thrown ZeroAmount error");
+    it("fails proposal with invalid fee total", async () => {
+      try {
+        await proposeAction({
+          // Total 300, should be 500
+          updateFees: { reflectionShareBps: 100, lpShareBps: 100, burnShareBps: 100 },
+        });
+
+        assert.fail("Should have thrown InvalidFeeConfig error");
       } catch (err: any) {
-        expect(err.message).to.include("ZeroAmount");
+        expect(err.message).to.include("InvalidFeeConfig");
       }
     });
 
-    it("records LP deployment", async () => {
-      const deployAmount = new anchor.BN(5_000 * 10 ** 9); // Deploy 5K tokens
-      const lpTokensReceived = new anchor.BN(1000 * 10 ** 9); // Mock LP tokens
-      const poolAddress = Keypair.generate().publicKey;
+    it("keeps several actions outstanding at once", async () => {
+      const pauseAction = await proposeAction(PAUSE_ALL_PAYLOAD);
+      assert.notEqual(pauseAction.toBase58(), feeAction.toBase58());
 
-      // Derive LP deployment PDA (uses total_deployed which is 0 before first deployment)
-      const lpVaultBefore = await program.account.lpVault.fetch(lpVaultPda);
-      const [lpDeploymentPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("lp_deployment"),
-          lpVaultPda.toBuffer(),
-          Buffer.from(lpVaultBefore.totalDeployed.toArray("le", 8)),
-        ],
-        program.programId
-      );
+      const fee = await program.account.governanceAction.fetch(feeAction);
+      const pause = await program.account.governanceAction.fetch(pauseAction);
+      assert.equal(pause.nonce.toNumber(), fee.nonce.toNumber() + 1);
 
-      const tx = await program.methods
-        .recordLpDeployment(deployAmount, lpTokensReceived, poolAddress)
-        .accounts({
-          authority: authority.publicKey,
-          tokenMint,
-          config: configPda,
-          lpVault: lpVaultPda,
-          lpDeployment: lpDeploymentPda,
-          systemProgram: SystemProgram.programId,
+      await cancelAction(pauseAction);
+    });
+
+    it("rejects an action delay below the floor", async () => {
+      try {
+        await proposeAction({ setActionDelay: { kind: { fees: {} }, delay: new anchor.BN(60) } });
+        assert.fail("Should have thrown InvalidActionDelay error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidActionDelay");
+      }
+    });
+
+    it("times a delay change by the delay it replaces", async () => {
+      const delayAction = await proposeAction({
+        setActionDelay: { kind: { pause: {} }, delay: new anchor.BN(5 * 60) },
+      });
+
+      const action = await program.account.governanceAction.fetch(delayAction);
+      assert.equal(action.eta.sub(action.proposedAt).toString(), ACTION_DELAY.toString());
+
+      try {
+        await executeAction(delayAction);
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      await cancelAction(delayAction);
+    });
+
+    it("cancels fee proposal", async () => {
+      await cancelAction(feeAction);
+
+      // Verify action was cancelled
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(action.cancelled, true);
+
+      // Verify config unchanged
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.reflectionShareBps, REFLECTION_BPS);
+      assert.equal(config.lpShareBps, LP_BPS);
+      assert.equal(config.burnShareBps, BURN_BPS);
+    });
+
+    const closeAction = (governanceAction: PublicKey, proposer = authority.publicKey) =>
+      program.methods
+        .closeAction()
+        .accounts({
+          closer: user1.publicKey,
+          proposer,
+          tokenMint,
+          config: configPda,
+          governanceAction,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+    it("refuses to close an action that can still execute", async () => {
+      const pauseAction = await proposeAction(PAUSE_ALL_PAYLOAD);
+
+      try {
+        await closeAction(pauseAction);
+        assert.fail("Should have thrown ActionStillPending error");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionStillPending");
+      }
+
+      await cancelAction(pauseAction);
+    });
+
+    it("returns rent only to the proposer", async () => {
+      try {
+        await closeAction(feeAction, user1.publicKey);
+        assert.fail("Should have thrown NotProposer error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotProposer");
+      }
+    });
+
+    it("lets anyone close a settled action and refunds the proposer", async () => {
+      const rent = await connection.getBalance(feeAction);
+      const before = await connection.getBalance(authority.publicKey);
+
+      await closeAction(feeAction);
+
+      assert.isNull(await connection.getAccountInfo(feeAction));
+      const after = await connection.getBalance(authority.publicKey);
+      assert.equal(after - before, rent);
+    });
+  });
+
+  describe("Fee Voting", () => {
+    // Separate config so the staked supply behind the vote is known
+    const STAKE = new anchor.BN(1_000 * 10 ** 9);
+    let voteMint: PublicKey;
+    let voteConfigPda: PublicKey;
+    let feeAction: PublicKey;
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const stakePda = (owner: PublicKey) =>
+      pda(Buffer.from("stake"), voteConfigPda.toBuffer(), owner.toBuffer());
+    const receiptPda = (action: PublicKey, voter: PublicKey) =>
+      pda(Buffer.from("vote"), action.toBuffer(), voter.toBuffer());
+
+    const stakeOn = async (owner: Keypair) => {
+      const ownerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        voteMint,
+        owner.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        voteMint,
+        ownerTokenAccount,
+        authority,
+        BigInt(STAKE.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .stake(STAKE, null)
+        .accounts({
+          owner: owner.publicKey,
+          tokenMint: voteMint,
+          config: voteConfigPda,
+          userTokenAccount: ownerTokenAccount,
+          stakingVault: pda(Buffer.from("staking_vault"), voteMint.toBuffer()),
+          userStake: stakePda(owner.publicKey),
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+    };
+
+    const propose = async (payload: any) => {
+      const config = await program.account.kernelConfig.fetch(voteConfigPda);
+      const governanceAction = governanceActionPda(voteConfigPda, config.governanceActionCount);
+      await program.methods
+        .proposeAction(payload)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: voteMint,
+          config: voteConfigPda,
+          governanceAction,
+          systemProgram: SystemProgram.programId,
         })
         .signers([authority])
-        .rpc();
+        .rpc({ commitment: "confirmed" });
+      return governanceAction;
+    };
 
-      console.log("Record LP deployment tx:", tx);
+    const vote = (voter: Keypair, action: PublicKey, support: boolean) =>
+      program.methods
+        .castVote(support)
+        .accounts({
+          voter: voter.publicKey,
+          tokenMint: voteMint,
+          config: voteConfigPda,
+          governanceAction: action,
+          userStake: stakePda(voter.publicKey),
+          voteReceipt: receiptPda(action, voter.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
 
-      // Verify LP vault updated
-      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
-      assert.equal(lpVault.totalDeployed.toNumber(), deployAmount.toNumber());
-      assert.equal(
-        lpVault.pendingDeployment.toNumber(),
-        ALLOCATE_AMOUNT.toNumber() - deployAmount.toNumber()
+    before(async () => {
+      voteMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        9,
+        undefined,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
       );
+      voteConfigPda = pda(Buffer.from("config"), voteMint.toBuffer());
 
-      // Verify deployment record
-      const deployment = await program.account.lpDeployment.fetch(lpDeploymentPda);
-      assert.equal(deployment.poolAddress.toBase58(), poolAddress.toBase58());
-      assert.equal(deployment.kernelAmount.toNumber(), deployAmount.toNumber());
-      assert.equal(deployment.lpTokensReceived.toNumber(), lpTokensReceived.toNumber());
-      assert.equal(deployment.withdrawn, false);
+      await program.methods
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: voteMint,
+          stakingVault: pda(Buffer.from("staking_vault"), voteMint.toBuffer()),
+          reflectionPool: pda(Buffer.from("reflection_pool"), voteMint.toBuffer()),
+          config: voteConfigPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      await stakeOn(user1);
+      await stakeOn(user2);
+      // Make sure the stake lands strictly before the proposal's timestamp
+      await new Promise((resolve) => setTimeout(resolve, 2000));
     });
 
-    it("withdraws from LP vault", async () => {
-      const withdrawAmount = new anchor.BN(2_000 * 10 ** 9); // Withdraw 2K tokens
+    it("opens a vote when a fee change is proposed", async () => {
+      feeAction = await propose({
+        updateFees: { reflectionShareBps: 300, lpShareBps: 100, burnShareBps: 100 },
+      });
+
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(action.totalStakedSnapshot.toString(), STAKE.muln(2).toString());
+      assert.equal(action.votesFor.toNumber(), 0);
+      assert.equal(action.votesAgainst.toNumber(), 0);
+    });
+
+    it("counts a vote weighted by staked amount", async () => {
+      await vote(user1, feeAction, true);
+
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(action.votesFor.toString(), STAKE.toString());
+
+      const receipt = await program.account.voteReceipt.fetch(
+        receiptPda(feeAction, user1.publicKey)
+      );
+      assert.equal(receipt.support, true);
+      assert.equal(receipt.weight.toString(), STAKE.toString());
+    });
+
+    it("keeps the receipt open until voting ends", async () => {
+      const receiptKey = receiptPda(feeAction, user1.publicKey);
+      const receipt = await program.account.voteReceipt.fetch(receiptKey);
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(receipt.votingEndsAt.toString(), action.eta.toString());
+
+      try {
+        await program.methods
+          .closeVoteReceipt()
+          .accounts({
+            closer: user2.publicKey,
+            voter: user1.publicKey,
+            voteReceipt: receiptKey,
+          })
+          .signers([user2])
+          .rpc();
+        assert.fail("Should have thrown VotingStillOpen error");
+      } catch (err: any) {
+        expect(err.message).to.include("VotingStillOpen");
+      }
+    });
+
+    it("prevents voting twice", async () => {
+      try {
+        await vote(user1, feeAction, false);
+        assert.fail("Should have thrown error - receipt already exists");
+      } catch (err: any) {
+        expect(err.message).to.include("already in use");
+      }
+    });
+
+    it("votes with the stake held at the proposal after a gift", async () => {
+      const funderTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        voteMint,
+        authority.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        voteMint,
+        funderTokenAccount,
+        authority,
+        BigInt(STAKE.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .stakeFor(user2.publicKey, STAKE, null, true)
+        .accounts({
+          funder: authority.publicKey,
+          beneficiarySigner: null,
+          tokenMint: voteMint,
+          config: voteConfigPda,
+          funderTokenAccount,
+          stakingVault: pda(Buffer.from("staking_vault"), voteMint.toBuffer()),
+          userStake: stakePda(user2.publicKey),
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      await vote(user2, feeAction, false);
+
+      const receipt = await program.account.voteReceipt.fetch(
+        receiptPda(feeAction, user2.publicKey)
+      );
+      assert.equal(receipt.weight.toString(), STAKE.toString());
+      const action = await program.account.governanceAction.fetch(feeAction);
+      assert.equal(action.votesAgainst.toString(), STAKE.toString());
+    });
+
+    it("gives no weight to stake added after the proposal", async () => {
+      const latecomer = Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(latecomer.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      await stakeOn(latecomer);
+
+      try {
+        await vote(latecomer, feeAction, false);
+        assert.fail("Should have thrown NoVotingPower error");
+      } catch (err: any) {
+        expect(err.message).to.include("NoVotingPower");
+      }
+    });
+
+    it("only puts fee changes to a vote", async () => {
+      const pauseAction = await propose(PAUSE_ALL_PAYLOAD);
+
+      try {
+        await vote(user1, pauseAction, true);
+        assert.fail("Should have thrown VotingNotOpen error");
+      } catch (err: any) {
+        expect(err.message).to.include("VotingNotOpen");
+      }
+    });
+  });
+
+  describe("LP Vault Operations", () => {
+    let lpVaultPda: PublicKey;
+    let lpVaultTokenPda: PublicKey;
+    const ALLOCATE_AMOUNT = new anchor.BN(10_000 * 10 ** 9); // 10K tokens
+
+    before(() => {
+      [lpVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp_vault"), tokenMint.toBuffer()],
+        program.programId
+      );
+
+      [lpVaultTokenPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp_vault_token"), tokenMint.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("initializes LP vault", async () => {
+      const tx = await program.methods
+        .initializeLpVault()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          lpVault: lpVaultPda,
+          lpVaultToken: lpVaultTokenPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("Initialize LP vault tx:", tx);
+
+      // Verify LP vault was created
+      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
+      assert.equal(lpVault.authority.toBase58(), authority.publicKey.toBase58());
+      assert.equal(lpVault.tokenMint.toBase58(), tokenMint.toBase58());
+      assert.equal(lpVault.totalAllocated.toNumber(), 0);
+      assert.equal(lpVault.totalDeployed.toNumber(), 0);
+      assert.equal(lpVault.pendingDeployment.toNumber(), 0);
+    });
 
+    it("allocates tokens to LP vault", async () => {
       const balanceBefore = await getAccount(
         connection,
         authorityTokenAccount,
@@ -1318,121 +1835,3274 @@ describe("kernel-token", () => {
         TOKEN_2022_PROGRAM_ID
       );
 
-      const lpVaultBefore = await program.account.lpVault.fetch(lpVaultPda);
+      const tx = await program.methods
+        .allocateToLp(ALLOCATE_AMOUNT)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          lpVault: lpVaultPda,
+          authorityTokenAccount,
+          lpVaultToken: lpVaultTokenPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      // Wait for confirmation and state propagation
+      await connection.confirmTransaction(tx, "confirmed");
+      console.log("Allocate to LP tx:", tx);
+
+      // Verify LP vault updated
+      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
+      assert.equal(lpVault.totalAllocated.toNumber(), ALLOCATE_AMOUNT.toNumber());
+      assert.equal(lpVault.pendingDeployment.toNumber(), ALLOCATE_AMOUNT.toNumber());
+
+      // Verify vault token balance
+      const vaultBalance = await getAccount(
+        connection,
+        lpVaultTokenPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(Number(vaultBalance.amount), ALLOCATE_AMOUNT.toNumber());
+
+      // Verify authority balance decreased
+      const balanceAfter = await getAccount(
+        connection,
+        authorityTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(
+        Number(balanceBefore.amount) - Number(balanceAfter.amount),
+        ALLOCATE_AMOUNT.toNumber()
+      );
+    });
+
+    it("fails to allocate zero amount", async () => {
+      try {
+        await program.methods
+          .allocateToLp(new anchor.BN(0))
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint,
+            config: configPda,
+            roles: rolesPda,
+            lpVault: lpVaultPda,
+            authorityTokenAccount,
+            lpVaultToken: lpVaultTokenPda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown ZeroAmount error");
+      } catch (err: any) {
+        expect(err.message).to.include("ZeroAmount");
+      }
+    });
+
+    it("records LP deployment", async () => {
+      const deployAmount = new anchor.BN(5_000 * 10 ** 9); // Deploy 5K tokens
+      const lpTokensReceived = new anchor.BN(1000 * 10 ** 9); // Mock LP tokens
+      const poolAddress = Keypair.generate().publicKey;
+
+      // Derive LP deployment PDA (uses total_deployed which is 0 before first deployment)
+      const lpVaultBefore = await program.account.lpVault.fetch(lpVaultPda);
+      const [lpDeploymentPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("lp_deployment"),
+          lpVaultPda.toBuffer(),
+          Buffer.from(lpVaultBefore.totalDeployed.toArray("le", 8)),
+        ],
+        program.programId
+      );
+
+      const tx = await program.methods
+        .recordLpDeployment(deployAmount, lpTokensReceived, poolAddress)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          lpVault: lpVaultPda,
+          lpDeployment: lpDeploymentPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("Record LP deployment tx:", tx);
+
+      // Verify LP vault updated
+      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
+      assert.equal(lpVault.totalDeployed.toNumber(), deployAmount.toNumber());
+      assert.equal(
+        lpVault.pendingDeployment.toNumber(),
+        ALLOCATE_AMOUNT.toNumber() - deployAmount.toNumber()
+      );
+
+      // Verify deployment record
+      const deployment = await program.account.lpDeployment.fetch(lpDeploymentPda);
+      assert.equal(deployment.poolAddress.toBase58(), poolAddress.toBase58());
+      assert.equal(deployment.kernelAmount.toNumber(), deployAmount.toNumber());
+      assert.equal(deployment.lpTokensReceived.toNumber(), lpTokensReceived.toNumber());
+      assert.equal(deployment.withdrawn, false);
+    });
+
+    it("withdraws from LP vault", async () => {
+      const withdrawAmount = new anchor.BN(2_000 * 10 ** 9); // Withdraw 2K tokens
+
+      const balanceBefore = await getAccount(
+        connection,
+        authorityTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const lpVaultBefore = await program.account.lpVault.fetch(lpVaultPda);
+
+      const tx = await program.methods
+        .withdrawFromLpVault(withdrawAmount)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          lpVault: lpVaultPda,
+          authorityTokenAccount,
+          lpVaultToken: lpVaultTokenPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      // Wait for confirmation to ensure state propagation
+      await connection.confirmTransaction(tx, "confirmed");
+
+      console.log("Withdraw from LP vault tx:", tx);
+
+      // Verify LP vault updated
+      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
+      assert.equal(
+        lpVault.pendingDeployment.toNumber(),
+        lpVaultBefore.pendingDeployment.toNumber() - withdrawAmount.toNumber()
+      );
+
+      // Verify authority balance increased
+      const balanceAfter = await getAccount(
+        connection,
+        authorityTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(
+        Number(balanceAfter.amount) - Number(balanceBefore.amount),
+        withdrawAmount.toNumber()
+      );
+    });
+
+    it("fails to withdraw more than pending", async () => {
+      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
+      const tooMuch = new anchor.BN(lpVault.pendingDeployment.toNumber() + 1);
+
+      try {
+        await program.methods
+          .withdrawFromLpVault(tooMuch)
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint,
+            config: configPda,
+            roles: rolesPda,
+            lpVault: lpVaultPda,
+            authorityTokenAccount,
+            lpVaultToken: lpVaultTokenPda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown InsufficientLPFunds error");
+      } catch (err: any) {
+        expect(err.message).to.include("InsufficientLPFunds");
+      }
+    });
+  });
+
+  describe("Multi-User Reflections", () => {
+    it("distributes reflections proportionally to multiple stakers", async () => {
+      // User2 stakes tokens
+      const user2StakeAmount = new anchor.BN(2_000_000 * 10 ** 9); // 2M tokens
+
+      const [user2StakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .stake(user2StakeAmount, null)
+        .accounts({
+          owner: user2.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: user2TokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: user2StakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+
+      console.log("User2 staked:", user2StakeAmount.toString());
+
+      // Get stake amounts for proportion calculation
+      const [user1StakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const user1Stake = await program.account.userStake.fetch(user1StakePda);
+      const user2Stake = await program.account.userStake.fetch(user2StakePda);
+
+      console.log("User1 staked:", user1Stake.stakedAmount.toString());
+      console.log("User2 staked:", user2Stake.stakedAmount.toString());
+
+      // Deposit reflections
+      const reflectionAmount = new anchor.BN(50_000 * 10 ** 9); // 50K tokens
+
+      await program.methods
+        .depositReflections(reflectionAmount)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          authorityTokenAccount,
+          reflectionPool: reflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      console.log("Deposited reflections:", reflectionAmount.toString());
+
+      // Get balances before claims
+      const user1BalanceBefore = await getAccount(
+        connection,
+        user1TokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const user2BalanceBefore = await getAccount(
+        connection,
+        user2TokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      // User1 claims
+      await program.methods
+        .claimReflections()
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: user1TokenAccount,
+          reflectionPool: reflectionPoolPda,
+          userStake: user1StakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      // User2 claims
+      await program.methods
+        .claimReflections()
+        .accounts({
+          owner: user2.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: user2TokenAccount,
+          reflectionPool: reflectionPoolPda,
+          userStake: user2StakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+
+      // Get balances after claims
+      const user1BalanceAfter = await getAccount(
+        connection,
+        user1TokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const user2BalanceAfter = await getAccount(
+        connection,
+        user2TokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const user1Rewards = Number(user1BalanceAfter.amount) - Number(user1BalanceBefore.amount);
+      const user2Rewards = Number(user2BalanceAfter.amount) - Number(user2BalanceBefore.amount);
+
+      console.log("User1 rewards:", user1Rewards);
+      console.log("User2 rewards:", user2Rewards);
+
+      // Both users should have received rewards
+      assert.isTrue(user1Rewards > 0, "User1 should receive rewards");
+      assert.isTrue(user2Rewards > 0, "User2 should receive rewards");
+
+      // User2 staked more, should receive more rewards
+      // User2 has 2M, proportionally more than user1's remaining stake
+      const totalStaked = user1Stake.stakedAmount.toNumber() + user2Stake.stakedAmount.toNumber();
+      const expectedUser2Proportion = user2Stake.stakedAmount.toNumber() / totalStaked;
+
+      // User2's rewards should be proportional to their stake (with some tolerance for rounding)
+      const actualUser2Proportion = user2Rewards / (user1Rewards + user2Rewards);
+      const tolerance = 0.05; // 5% tolerance for rounding
+
+      assert.approximately(
+        actualUser2Proportion,
+        expectedUser2Proportion,
+        tolerance,
+        "Rewards should be distributed proportionally"
+      );
+    });
+  });
+
+  describe("Lock-up Tiers", () => {
+    const LOCK_STAKE_AMOUNT = new anchor.BN(100_000 * 10 ** 9); // 100K tokens
+    let staker: Keypair;
+    let stakerTokenAccount: PublicKey;
+    let stakerStakePda: PublicKey;
+    let stakingTiersPda: PublicKey;
+
+    before(async () => {
+      staker = Keypair.generate();
+      await connection.requestAirdrop(staker.publicKey, 10 * LAMPORTS_PER_SOL);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      stakerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        tokenMint,
+        staker.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const { createTransferInstruction } = await import("@solana/spl-token");
+      const tx = new anchor.web3.Transaction().add(
+        createTransferInstruction(
+          authorityTokenAccount,
+          stakerTokenAccount,
+          authority.publicKey,
+          BigInt(LOCK_STAKE_AMOUNT.toString()),
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [authority]);
+
+      [stakerStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), staker.publicKey.toBuffer()],
+        program.programId
+      );
+      [stakingTiersPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("staking_tiers"), configPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    const stakeAccounts = () => ({
+      owner: staker.publicKey,
+      tokenMint,
+      config: configPda,
+      userTokenAccount: stakerTokenAccount,
+      stakingVault: stakingVaultPda,
+      userStake: stakerStakePda,
+      stakingTiers: stakingTiersPda,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("initializes the default tier table", async () => {
+      await program.methods
+        .initializeStakingTiers()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          stakingTiers: stakingTiersPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const tiers = await program.account.stakingTiers.fetch(stakingTiersPda);
+      assert.equal(tiers.tiers.length, 4);
+      assert.equal(tiers.tiers[0].lockDuration.toNumber(), 30 * 24 * 60 * 60);
+      assert.equal(tiers.tiers[0].multiplierBps, 11000);
+      assert.equal(tiers.tiers[3].multiplierBps, 20000);
+    });
+
+    it("rejects an unknown tier index", async () => {
+      try {
+        await program.methods
+          .stake(LOCK_STAKE_AMOUNT, 9)
+          .accounts(stakeAccounts())
+          .signers([staker])
+          .rpc();
+
+        assert.fail("Should have thrown InvalidLockTier error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidLockTier");
+      }
+    });
+
+    it("stakes with a lock tier and earns boosted shares", async () => {
+      const configBefore = await program.account.kernelConfig.fetch(configPda);
+
+      await program.methods
+        .stake(LOCK_STAKE_AMOUNT, 0)
+        .accounts(stakeAccounts())
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+      const stake = await program.account.userStake.fetch(stakerStakePda);
+      const expectedShares = LOCK_STAKE_AMOUNT.muln(11000).divn(10000);
+      assert.equal(stake.lockMultiplierBps, 11000);
+      assert.equal(stake.shares.toString(), expectedShares.toString());
+      assert.isTrue(stake.lockUntil.gt(stake.stakeTime));
+
+      const configAfter = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(
+        configAfter.totalShares.sub(configBefore.totalShares).toString(),
+        expectedShares.toString()
+      );
+    });
+
+    it("blocks unstaking while locked", async () => {
+      try {
+        await program.methods
+          .unstake(LOCK_STAKE_AMOUNT)
+          .accounts({
+            owner: staker.publicKey,
+            tokenMint,
+            config: configPda,
+            userTokenAccount: stakerTokenAccount,
+            stakingVault: stakingVaultPda,
+            userStake: stakerStakePda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([staker])
+          .rpc();
+
+        assert.fail("Should have thrown StakeLocked error");
+      } catch (err: any) {
+        expect(err.message).to.include("StakeLocked");
+      }
+    });
+
+    it("rejects a tier table with unordered durations", async () => {
+      try {
+        await proposeAction({
+          updateTiers: {
+            tiers: [
+              { lockDuration: new anchor.BN(90 * 24 * 60 * 60), multiplierBps: 12500 },
+              { lockDuration: new anchor.BN(30 * 24 * 60 * 60), multiplierBps: 11000 },
+            ],
+          },
+        });
+
+        assert.fail("Should have thrown InvalidTierConfig error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidTierConfig");
+      }
+    });
+
+    it("proposes a tier update behind the timelock", async () => {
+      const tierAction = await proposeAction({
+        updateTiers: {
+          tiers: [{ lockDuration: new anchor.BN(60 * 24 * 60 * 60), multiplierBps: 12000 }],
+        },
+      });
+
+      try {
+        await executeAction(tierAction, { stakingTiers: stakingTiersPda });
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      await cancelAction(tierAction);
+
+      const action = await program.account.governanceAction.fetch(tierAction);
+      assert.equal(action.cancelled, true);
+    });
+  });
+
+  describe("Unbonding Queue", () => {
+    // The cooldown is timelocked, so tickets here release immediately
+    const UNBOND_STAKE_AMOUNT = new anchor.BN(50_000 * 10 ** 9); // 50K tokens
+    const COOLDOWN_SECONDS = 2 * 24 * 60 * 60;
+    let staker: Keypair;
+    let stakerTokenAccount: PublicKey;
+    let stakerStakePda: PublicKey;
+
+    const ticketPda = (nonce: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("unbonding"),
+          stakerStakePda.toBuffer(),
+          new anchor.BN(nonce).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+
+    const requestUnstake = (amount: anchor.BN, nonce: number) =>
+      program.methods
+        .requestUnstake(amount)
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userStake: stakerStakePda,
+          unbondingTicket: ticketPda(nonce),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+    const withdrawUnbonded = (nonce: number) =>
+      program.methods
+        .withdrawUnbonded()
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          unbondingTicket: ticketPda(nonce),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      staker = Keypair.generate();
+      await connection.requestAirdrop(staker.publicKey, 10 * LAMPORTS_PER_SOL);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      stakerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        tokenMint,
+        staker.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const { createTransferInstruction } = await import("@solana/spl-token");
+      const tx = new anchor.web3.Transaction().add(
+        createTransferInstruction(
+          authorityTokenAccount,
+          stakerTokenAccount,
+          authority.publicKey,
+          BigInt(UNBOND_STAKE_AMOUNT.toString()),
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [authority]);
+
+      [stakerStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), staker.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .stake(UNBOND_STAKE_AMOUNT, null)
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+    });
+
+    it("rejects a cooldown above the maximum", async () => {
+      try {
+        await proposeAction({
+          setUnbondingPeriod: { unbondingPeriod: new anchor.BN(31 * 24 * 60 * 60) },
+        });
+        assert.fail("Should have thrown InvalidUnbondingPeriod error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidUnbondingPeriod");
+      }
+    });
+
+    it("changes the cooldown only through the timelock", async () => {
+      const cooldownAction = await proposeAction({
+        setUnbondingPeriod: { unbondingPeriod: new anchor.BN(COOLDOWN_SECONDS) },
+      });
+
+      const action = await program.account.governanceAction.fetch(cooldownAction);
+      assert.equal(action.eta.sub(action.proposedAt).toString(), ACTION_DELAY.toString());
+
+      try {
+        await executeAction(cooldownAction);
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      await cancelAction(cooldownAction);
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.unbondingPeriod.toNumber(), 0);
+    });
+
+    it("opens several tickets that stop earning immediately", async () => {
+      const half = UNBOND_STAKE_AMOUNT.divn(2);
+      const configBefore = await program.account.kernelConfig.fetch(configPda);
+
+      await requestUnstake(half, 0);
+      await requestUnstake(half, 1);
+
+      const stake = await program.account.userStake.fetch(stakerStakePda);
+      assert.equal(stake.stakedAmount.toNumber(), 0);
+      assert.equal(stake.shares.toNumber(), 0);
+      assert.equal(stake.unbondingNonce.toNumber(), 2);
+      assert.equal(stake.unbondingAmount.toString(), UNBOND_STAKE_AMOUNT.toString());
+
+      const configAfter = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(
+        configBefore.totalStaked.sub(configAfter.totalStaked).toString(),
+        UNBOND_STAKE_AMOUNT.toString()
+      );
+      assert.equal(
+        configAfter.totalUnbonding.sub(configBefore.totalUnbonding).toString(),
+        UNBOND_STAKE_AMOUNT.toString()
+      );
+
+      const ticket = await program.account.unbondingTicket.fetch(ticketPda(1));
+      assert.equal(ticket.amount.toString(), half.toString());
+      assert.equal(ticket.releaseTime.toNumber(), ticket.requestedAt.toNumber());
+    });
+
+    it("withdraws each ticket once released, even while paused", async () => {
+      await setPaused(PAUSE_ALL);
+
+      const balanceBefore = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await withdrawUnbonded(0);
+      await withdrawUnbonded(1);
+
+      await setPaused(0);
+
+      const balanceAfter = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        UNBOND_STAKE_AMOUNT.toString()
+      );
+
+      const stake = await program.account.userStake.fetch(stakerStakePda);
+      assert.equal(stake.unbondingAmount.toNumber(), 0);
+      assert.isNull(await connection.getAccountInfo(ticketPda(0)));
+    });
+  });
+
+  describe("Compounding", () => {
+    let user1StakePda: PublicKey;
+    let user2StakePda: PublicKey;
+
+    const depositReflections = (amount: anchor.BN) =>
+      program.methods
+        .depositReflections(amount)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          authorityTokenAccount,
+          reflectionPool: reflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+    const crankAccounts = (userStake: PublicKey) => ({
+      cranker: authority.publicKey,
+      tokenMint,
+      config: configPda,
+      crankerTokenAccount: authorityTokenAccount,
+      reflectionPool: reflectionPoolPda,
+      stakingVault: stakingVaultPda,
+      userStake,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    before(async () => {
+      [user1StakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      [user2StakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await depositReflections(new anchor.BN(100_000 * 10 ** 9));
+    });
+
+    it("compounds reflections into stake", async () => {
+      const stakeBefore = await program.account.userStake.fetch(user1StakePda);
+      const configBefore = await program.account.kernelConfig.fetch(configPda);
+
+      await program.methods
+        .compoundReflections()
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint,
+          config: configPda,
+          reflectionPool: reflectionPoolPda,
+          stakingVault: stakingVaultPda,
+          userStake: user1StakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const stakeAfter = await program.account.userStake.fetch(user1StakePda);
+      const configAfter = await program.account.kernelConfig.fetch(configPda);
+      const compounded = stakeAfter.stakedAmount.sub(stakeBefore.stakedAmount);
+
+      assert.isTrue(compounded.gt(new anchor.BN(0)));
+      assert.equal(stakeAfter.pendingRewards.toNumber(), 0);
+      assert.equal(
+        configAfter.totalStaked.sub(configBefore.totalStaked).toString(),
+        compounded.toString()
+      );
+      assert.equal(
+        stakeAfter.totalClaimed.sub(stakeBefore.totalClaimed).toString(),
+        compounded.toString()
+      );
+    });
+
+    it("rejects cranking a staker who has not opted in", async () => {
+      try {
+        await program.methods
+          .crankCompound()
+          .accounts(crankAccounts(user1StakePda))
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown AutoCompoundDisabled error");
+      } catch (err: any) {
+        expect(err.message).to.include("AutoCompoundDisabled");
+      }
+    });
+
+    it("rejects a crank tip above the cap", async () => {
+      try {
+        await program.methods
+          .setAutoCompound(true, 51)
+          .accounts({
+            owner: user2.publicKey,
+            tokenMint,
+            config: configPda,
+            userStake: user2StakePda,
+          })
+          .signers([user2])
+          .rpc();
+
+        assert.fail("Should have thrown CrankTipTooHigh error");
+      } catch (err: any) {
+        expect(err.message).to.include("CrankTipTooHigh");
+      }
+    });
+
+    it("lets anyone crank an opted-in staker for the tip", async () => {
+      await program.methods
+        .setAutoCompound(true, 50)
+        .accounts({
+          owner: user2.publicKey,
+          tokenMint,
+          config: configPda,
+          userStake: user2StakePda,
+        })
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+
+      await depositReflections(new anchor.BN(100_000 * 10 ** 9));
+
+      const stakeBefore = await program.account.userStake.fetch(user2StakePda);
+      const crankerBefore = await getAccount(
+        connection,
+        authorityTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .crankCompound()
+        .accounts(crankAccounts(user2StakePda))
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const stakeAfter = await program.account.userStake.fetch(user2StakePda);
+      const crankerAfter = await getAccount(
+        connection,
+        authorityTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const compounded = BigInt(stakeAfter.stakedAmount.sub(stakeBefore.stakedAmount).toString());
+      const tip = crankerAfter.amount - crankerBefore.amount;
+      const claimed = BigInt(stakeAfter.totalClaimed.sub(stakeBefore.totalClaimed).toString());
+
+      assert.isTrue(compounded > 0n);
+      assert.equal((tip + compounded).toString(), claimed.toString());
+      assert.isTrue(tip <= (claimed * 50n) / 10000n);
+    });
+  });
+
+  describe("On-chain Fee Harvest", () => {
+    // Separate mint with a 5% transfer fee whose withdraw-withheld
+    // authority is the program's fee vault PDA
+    const FEE_BPS = 500;
+    const TRANSFER_AMOUNT = BigInt(1_000_000 * 10 ** 9); // 1M tokens
+    let feeMint: Keypair;
+    let feeConfigPda: PublicKey;
+    let feeVaultPda: PublicKey;
+    let feeReflectionPoolPda: PublicKey;
+    let feeLpVaultPda: PublicKey;
+    let feeLpVaultTokenPda: PublicKey;
+    let feeBurnRecordPda: PublicKey;
+    let senderAccount: PublicKey;
+    let receiverAccount: PublicKey;
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    // Token-2022 rounds the transfer fee up
+    const netOf = (amount: bigint) =>
+      amount - (amount * BigInt(FEE_BPS) + BigInt(9_999)) / BigInt(10_000);
+
+    before(async () => {
+      feeMint = Keypair.generate();
+      feeConfigPda = pda(Buffer.from("config"), feeMint.publicKey.toBuffer());
+      feeVaultPda = pda(Buffer.from("fee_vault"), feeMint.publicKey.toBuffer());
+      feeReflectionPoolPda = pda(Buffer.from("reflection_pool"), feeMint.publicKey.toBuffer());
+      feeLpVaultPda = pda(Buffer.from("lp_vault"), feeMint.publicKey.toBuffer());
+      feeLpVaultTokenPda = pda(Buffer.from("lp_vault_token"), feeMint.publicKey.toBuffer());
+      feeBurnRecordPda = pda(Buffer.from("burn"), feeConfigPda.toBuffer());
+
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const mintRent = await connection.getMinimumBalanceForRentExemption(mintLen);
+      const createMintTx = new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: mintRent,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          authority.publicKey,
+          feeVaultPda, // Withdraw withheld authority
+          FEE_BPS,
+          BigInt(Number.MAX_SAFE_INTEGER),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint.publicKey,
+          9,
+          authority.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, createMintTx, [
+        authority,
+        feeMint,
+      ]);
+
+      await program.methods
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          stakingVault: pda(Buffer.from("staking_vault"), feeMint.publicKey.toBuffer()),
+          reflectionPool: feeReflectionPoolPda,
+          config: feeConfigPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      await initializeRoles(feeMint.publicKey, feeConfigPda);
+
+      await program.methods
+        .initializeLpVault()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          config: feeConfigPda,
+          roles: rolesPdaFor(feeConfigPda),
+          lpVault: feeLpVaultPda,
+          lpVaultToken: feeLpVaultTokenPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      // Generate withheld fees with a transfer between two holders
+      senderAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        feeMint.publicKey,
+        authority.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      receiverAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        feeMint.publicKey,
+        user1.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        feeMint.publicKey,
+        senderAccount,
+        authority,
+        TRANSFER_AMOUNT,
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await transferChecked(
+        connection,
+        authority,
+        senderAccount,
+        feeMint.publicKey,
+        receiverAccount,
+        authority,
+        TRANSFER_AMOUNT,
+        9,
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    const harvestAccounts = () => ({
+      harvester: user2.publicKey,
+      tokenMint: feeMint.publicKey,
+      config: feeConfigPda,
+      feeVault: feeVaultPda,
+      reflectionPool: feeReflectionPoolPda,
+      lpVault: feeLpVaultPda,
+      lpVaultToken: feeLpVaultTokenPda,
+      burnRecord: feeBurnRecordPda,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("initializes the fee vault", async () => {
+      await program.methods
+        .initializeFeeVault()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          config: feeConfigPda,
+          roles: rolesPdaFor(feeConfigPda),
+          feeVault: feeVaultPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const feeVault = await getAccount(
+        connection,
+        feeVaultPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(feeVault.owner.toBase58(), feeVaultPda.toBase58());
+    });
+
+    it("lets anyone harvest and split withheld fees in one transaction", async () => {
+      const receiver = await getAccount(
+        connection,
+        receiverAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      const withheld = getTransferFeeAmount(receiver)!.withheldAmount;
+      assert.isTrue(withheld > BigInt(0));
+
+      await program.methods
+        .harvestAndDistribute()
+        .accounts(harvestAccounts())
+        .remainingAccounts([{ pubkey: receiverAccount, isSigner: false, isWritable: true }])
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+
+      const expectedReflection = (withheld * BigInt(REFLECTION_BPS)) / BigInt(FEE_BPS);
+      const expectedLp = (withheld * BigInt(LP_BPS)) / BigInt(FEE_BPS);
+      const expectedBurn = withheld - expectedReflection - expectedLp;
+
+      // Only what reaches the pool and the LP vault is credited
+      const config = await program.account.kernelConfig.fetch(feeConfigPda);
+      assert.equal(config.pendingReflections.toString(), netOf(expectedReflection).toString());
+      const pool = await getAccount(
+        connection,
+        feeReflectionPoolPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(pool.amount.toString(), netOf(expectedReflection).toString());
+
+      const lpVault = await program.account.lpVault.fetch(feeLpVaultPda);
+      assert.equal(lpVault.totalAllocated.toString(), netOf(expectedLp).toString());
+      assert.equal(lpVault.pendingDeployment.toString(), netOf(expectedLp).toString());
+      const lpToken = await getAccount(
+        connection,
+        feeLpVaultTokenPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(lpToken.amount.toString(), netOf(expectedLp).toString());
+
+      const burnRecord = await program.account.burnRecord.fetch(feeBurnRecordPda);
+      assert.equal(burnRecord.totalBurned.toString(), expectedBurn.toString());
+
+      const feeVault = await getAccount(
+        connection,
+        feeVaultPda,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(Number(feeVault.amount), 0);
+    });
+
+    it("fails when there is nothing to harvest", async () => {
+      try {
+        await program.methods
+          .harvestAndDistribute()
+          .accounts(harvestAccounts())
+          .signers([user2])
+          .rpc();
+
+        assert.fail("Should have thrown NothingToHarvest error");
+      } catch (err: any) {
+        expect(err.message).to.include("NothingToHarvest");
+      }
+    });
+
+    it("activates a campaign only once its vault holds the budget", async () => {
+      const budget = BigInt(1_000 * 10 ** 9);
+      const campaignId = new anchor.BN(1);
+      const campaign = pda(
+        Buffer.from("campaign"),
+        feeConfigPda.toBuffer(),
+        campaignId.toArrayLike(Buffer, "le", 8)
+      );
+      const campaignVault = pda(Buffer.from("campaign_vault"), campaign.toBuffer());
+      const now = Math.floor(Date.now() / 1000);
+
+      await program.methods
+        .createCampaign(
+          campaignId,
+          [...Buffer.alloc(32, 1)],
+          new anchor.BN(budget.toString()),
+          new anchor.BN(now - 60),
+          new anchor.BN(now + 3600)
+        )
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          config: feeConfigPda,
+          roles: rolesPdaFor(feeConfigPda),
+          campaign,
+          campaignVault,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const fund = (amount: bigint) =>
+        program.methods
+          .fundCampaign(new anchor.BN(amount.toString()))
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint: feeMint.publicKey,
+            config: feeConfigPda,
+            roles: rolesPdaFor(feeConfigPda),
+            campaign,
+            campaignVault,
+            authorityTokenAccount: senderAccount,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc({ commitment: "confirmed" });
+
+      await mintTo(
+        connection,
+        authority,
+        feeMint.publicKey,
+        senderAccount,
+        authority,
+        budget * BigInt(2),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      // The gross budget leaves the vault short by the fee, so it stays a draft
+      await fund(budget);
+      let state = await program.account.campaign.fetch(campaign);
+      assert.equal(state.totalFunded.toString(), netOf(budget).toString());
+      assert.deepEqual(state.status, { draft: {} });
+
+      // Top up with whatever lands exactly on the budget
+      const missing = budget - netOf(budget);
+      let topUp = (missing * BigInt(20)) / BigInt(19);
+      while (netOf(topUp) < missing) topUp++;
+      while (netOf(topUp) > missing) topUp--;
+      await fund(topUp);
+
+      state = await program.account.campaign.fetch(campaign);
+      assert.equal(state.totalFunded.toString(), budget.toString());
+      assert.deepEqual(state.status, { active: {} });
+      const vault = await getAccount(connection, campaignVault, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(vault.amount.toString(), budget.toString());
+    });
+  });
+
+  describe("Events", () => {
+    it("emits TokensBurned through a CPI event", async () => {
+      const burnAmount = new anchor.BN(1_000 * 10 ** 9);
+
+      const [burnRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("burn"), configPda.toBuffer()],
+        program.programId
+      );
+
+      const tx = await program.methods
+        .burnTokens(burnAmount)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          authorityTokenAccount,
+          burnRecord: burnRecordPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const events = await cpiEvents(tx);
+      assert.equal(events.length, 1);
+      assert.equal(events[0].name, "tokensBurned");
+      assert.equal(events[0].data.burner.toBase58(), authority.publicKey.toBase58());
+      assert.equal(events[0].data.amount.toString(), burnAmount.toString());
+
+      const burnRecord = await program.account.burnRecord.fetch(burnRecordPda);
+      assert.equal(events[0].data.totalBurned.toString(), burnRecord.totalBurned.toString());
+    });
+
+    it("emits Paused when the program is paused and unpaused", async () => {
+      for (const flags of [PAUSE_ALL, 0]) {
+        const tx = await setPaused(flags);
+
+        const events = await cpiEvents(tx);
+        assert.equal(events.length, 1);
+        assert.equal(events[0].name, "paused");
+        assert.equal(events[0].data.flags, flags);
+        assert.equal(events[0].data.pausedUntil.toNumber(), 0);
+      }
+    });
+  });
+
+  describe("Layout Migration", () => {
+    it("writes the current layout version on init", async () => {
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.version, 1);
+
+      const [stakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      const userStake = await program.account.userStake.fetch(stakePda);
+      assert.equal(userStake.version, 1);
+    });
+
+    it("rejects migrating a config that already uses the current layout", async () => {
+      const [burnRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("burn"), configPda.toBuffer()],
+        program.programId
+      );
+      const [lpVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp_vault"), tokenMint.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .migrateConfig()
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint,
+            config: configPda,
+            burnRecord: burnRecordPda,
+            lpVault: lpVaultPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown AlreadyMigrated error");
+      } catch (err: any) {
+        expect(err.message).to.include("AlreadyMigrated");
+      }
+    });
+
+    it("rejects migrating a stake that already uses the current layout", async () => {
+      const [stakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .migrateUserStake()
+          .accounts({
+            payer: user2.publicKey,
+            tokenMint,
+            config: configPda,
+            owner: user1.publicKey,
+            userStake: stakePda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user2])
+          .rpc();
+
+        assert.fail("Should have thrown AlreadyMigrated error");
+      } catch (err: any) {
+        expect(err.message).to.include("AlreadyMigrated");
+      }
+    });
+  });
+
+  describe("Reward Pools", () => {
+    // Separate staking config with a partner reward mint on the classic
+    // token program, so the main config's stakers need no reward accounts
+    const STAKE = new anchor.BN(1_000 * 10 ** 9);
+    const REWARD = new anchor.BN(500 * 10 ** 6);
+    let stakeMint: PublicKey;
+    let rewardMint: PublicKey;
+    let poolConfigPda: PublicKey;
+    let rewardPoolPda: PublicKey;
+    let rewardVaultPda: PublicKey;
+    let userStakePda: PublicKey;
+    let userRewardStatePda: PublicKey;
+    let stakerTokenAccount: PublicKey;
+    let stakerRewardAccount: PublicKey;
+    let funderRewardAccount: PublicKey;
+    let latePoolPda: PublicKey;
+    let lateStatePda: PublicKey;
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    const stakeAccounts = () => ({
+      owner: user1.publicKey,
+      tokenMint: stakeMint,
+      config: poolConfigPda,
+      userTokenAccount: stakerTokenAccount,
+      stakingVault: pda(Buffer.from("staking_vault"), stakeMint.toBuffer()),
+      userStake: userStakePda,
+      stakingTiers: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    before(async () => {
+      stakeMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        9,
+        undefined,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      rewardMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        6,
+        undefined,
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+
+      poolConfigPda = pda(Buffer.from("config"), stakeMint.toBuffer());
+      rewardPoolPda = pda(
+        Buffer.from("reward_pool"),
+        poolConfigPda.toBuffer(),
+        rewardMint.toBuffer()
+      );
+      rewardVaultPda = pda(Buffer.from("reward_vault"), rewardPoolPda.toBuffer());
+      userStakePda = pda(
+        Buffer.from("stake"),
+        poolConfigPda.toBuffer(),
+        user1.publicKey.toBuffer()
+      );
+      userRewardStatePda = pda(
+        Buffer.from("user_reward"),
+        rewardPoolPda.toBuffer(),
+        userStakePda.toBuffer()
+      );
+
+      await program.methods
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: stakeMint,
+          stakingVault: pda(Buffer.from("staking_vault"), stakeMint.toBuffer()),
+          reflectionPool: pda(Buffer.from("reflection_pool"), stakeMint.toBuffer()),
+          config: poolConfigPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      await initializeRoles(stakeMint, poolConfigPda);
+
+      stakerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        stakeMint,
+        user1.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        stakeMint,
+        stakerTokenAccount,
+        authority,
+        BigInt(STAKE.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      stakerRewardAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        rewardMint,
+        user1.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+      funderRewardAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        rewardMint,
+        user2.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        rewardMint,
+        funderRewardAccount,
+        authority,
+        BigInt(REWARD.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+    });
+
+    it("registers a partner reward mint", async () => {
+      await program.methods
+        .addRewardPool()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          roles: rolesPdaFor(poolConfigPda),
+          rewardMint,
+          rewardPool: rewardPoolPda,
+          rewardVault: rewardVaultPda,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const config = await program.account.kernelConfig.fetch(poolConfigPda);
+      assert.equal(config.rewardPoolCount, 1);
+
+      const pool = await program.account.rewardPool.fetch(rewardPoolPda);
+      assert.equal(pool.rewardMint.toBase58(), rewardMint.toBase58());
+      assert.equal(pool.rewardVault.toBase58(), rewardVaultPda.toBase58());
+    });
+
+    it("requires every reward state when shares change", async () => {
+      try {
+        await program.methods
+          .stake(STAKE, null)
+          .accounts(stakeAccounts())
+          .signers([user1])
+          .rpc();
+
+        assert.fail("Should have thrown InvalidRewardAccounts error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidRewardAccounts");
+      }
+    });
+
+    it("stakes with the reward state synced", async () => {
+      const openTx = await program.methods
+        .initUserRewardState()
+        .accounts({
+          payer: user1.publicKey,
+          owner: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          userStake: userStakePda,
+          rewardPool: rewardPoolPda,
+          userRewardState: userRewardStatePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const events = await cpiEvents(openTx);
+      assert.equal(events.length, 1);
+      assert.equal(events[0].name, "rewardStateOpened");
+      assert.equal(events[0].data.userStake.toBase58(), userStakePda.toBase58());
+      assert.equal(events[0].data.owner.toBase58(), user1.publicKey.toBase58());
+
+      await program.methods
+        .stake(STAKE, null)
+        .accounts(stakeAccounts())
+        .remainingAccounts([
+          { pubkey: rewardPoolPda, isSigner: false, isWritable: false },
+          { pubkey: userRewardStatePda, isSigner: false, isWritable: true },
+        ])
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const state = await program.account.userRewardState.fetch(userRewardStatePda);
+      assert.equal(state.userStake.toBase58(), userStakePda.toBase58());
+      assert.equal(state.rewardDebt.toNumber(), 0);
+    });
+
+    it("opens a reward state for a position before it exists", async () => {
+      const config = await program.account.kernelConfig.fetch(poolConfigPda);
+      const positionId = config.positionCount;
+      const position = pda(
+        Buffer.from("position"),
+        poolConfigPda.toBuffer(),
+        positionId.toArrayLike(Buffer, "le", 8)
+      );
+      const positionStake = pda(
+        Buffer.from("stake"),
+        poolConfigPda.toBuffer(),
+        position.toBuffer()
+      );
+
+      const openTx = await program.methods
+        .initPositionRewardState(positionId)
+        .accounts({
+          payer: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          position,
+          userStake: positionStake,
+          rewardPool: rewardPoolPda,
+          userRewardState: pda(
+            Buffer.from("user_reward"),
+            rewardPoolPda.toBuffer(),
+            positionStake.toBuffer()
+          ),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const events = await cpiEvents(openTx);
+      assert.equal(events.length, 1);
+      assert.equal(events[0].name, "rewardStateOpened");
+      assert.equal(events[0].data.userStake.toBase58(), positionStake.toBase58());
+      assert.equal(events[0].data.owner.toBase58(), position.toBase58());
+      assert.equal(events[0].data.payer.toBase58(), user1.publicKey.toBase58());
+    });
+
+    it("lets partners fund the pool and stakers claim everything at once", async () => {
+      await program.methods
+        .fundRewardPool(REWARD)
+        .accounts({
+          funder: user2.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          rewardMint,
+          rewardPool: rewardPoolPda,
+          rewardVault: rewardVaultPda,
+          funderTokenAccount: funderRewardAccount,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+
+      await program.methods
+        .claimAllRewards()
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          userTokenAccount: stakerTokenAccount,
+          reflectionPool: pda(Buffer.from("reflection_pool"), stakeMint.toBuffer()),
+          userStake: userStakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: rewardPoolPda, isSigner: false, isWritable: true },
+          { pubkey: userRewardStatePda, isSigner: false, isWritable: true },
+          { pubkey: rewardVaultPda, isSigner: false, isWritable: true },
+          { pubkey: rewardMint, isSigner: false, isWritable: false },
+          { pubkey: stakerRewardAccount, isSigner: false, isWritable: true },
+        ])
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      // Sole staker receives the whole deposit
+      const received = await getAccount(
+        connection,
+        stakerRewardAccount,
+        "confirmed",
+        TOKEN_PROGRAM_ID
+      );
+      assert.equal(received.amount.toString(), REWARD.toString());
+
+      const state = await program.account.userRewardState.fetch(userRewardStatePda);
+      assert.equal(state.totalClaimed.toString(), REWARD.toString());
+    });
+
+    it("unstakes without a reward state for a pool added later", async () => {
+      const lateMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        6,
+        undefined,
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+      latePoolPda = pda(
+        Buffer.from("reward_pool"),
+        poolConfigPda.toBuffer(),
+        lateMint.toBuffer()
+      );
+      lateStatePda = pda(
+        Buffer.from("user_reward"),
+        latePoolPda.toBuffer(),
+        userStakePda.toBuffer()
+      );
+
+      await program.methods
+        .addRewardPool()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          roles: rolesPdaFor(poolConfigPda),
+          rewardMint: lateMint,
+          rewardPool: latePoolPda,
+          rewardVault: pda(Buffer.from("reward_vault"), latePoolPda.toBuffer()),
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      // The late pool's state was never opened: its uninitialized address is skipped
+      await program.methods
+        .unstake(STAKE)
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: pda(Buffer.from("staking_vault"), stakeMint.toBuffer()),
+          userStake: userStakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: rewardPoolPda, isSigner: false, isWritable: false },
+          { pubkey: userRewardStatePda, isSigner: false, isWritable: true },
+          { pubkey: latePoolPda, isSigner: false, isWritable: false },
+          { pubkey: lateStatePda, isSigner: false, isWritable: false },
+        ])
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const stake = await program.account.userStake.fetch(userStakePda);
+      assert.equal(stake.stakedAmount.toNumber(), 0);
+      assert.isNull(await connection.getAccountInfo(lateStatePda));
+    });
+
+    it("forfeits reward pool rewards on emergency unstake", async () => {
+      await program.methods
+        .initUserRewardState()
+        .accounts({
+          payer: user1.publicKey,
+          owner: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          userStake: userStakePda,
+          rewardPool: latePoolPda,
+          userRewardState: lateStatePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      const pairs = [
+        { pubkey: rewardPoolPda, isSigner: false, isWritable: false },
+        { pubkey: userRewardStatePda, isSigner: false, isWritable: true },
+        { pubkey: latePoolPda, isSigner: false, isWritable: false },
+        { pubkey: lateStatePda, isSigner: false, isWritable: true },
+      ];
+      const stakeHalf = () =>
+        program.methods
+          .stake(STAKE.divn(2), null)
+          .accounts(stakeAccounts())
+          .remainingAccounts(pairs)
+          .signers([user1])
+          .rpc({ commitment: "confirmed" });
+
+      // Fund between two stakes so the second one settles rewards into the state
+      await stakeHalf();
+      await mintTo(
+        connection,
+        authority,
+        rewardMint,
+        funderRewardAccount,
+        authority,
+        BigInt(REWARD.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_PROGRAM_ID
+      );
+      await program.methods
+        .fundRewardPool(REWARD)
+        .accounts({
+          funder: user2.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          rewardMint,
+          rewardPool: rewardPoolPda,
+          rewardVault: rewardVaultPda,
+          funderTokenAccount: funderRewardAccount,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user2])
+        .rpc({ commitment: "confirmed" });
+      await stakeHalf();
+
+      let state = await program.account.userRewardState.fetch(userRewardStatePda);
+      assert.isTrue(state.pendingRewards.gtn(0));
+
+      await program.methods
+        .emergencyUnstake()
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint: stakeMint,
+          config: poolConfigPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: pda(Buffer.from("staking_vault"), stakeMint.toBuffer()),
+          userStake: userStakePda,
+          unbondingTicket: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(pairs)
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+      state = await program.account.userRewardState.fetch(userRewardStatePda);
+      assert.equal(state.pendingRewards.toNumber(), 0);
+      assert.equal(state.rewardDebt.toString(), "0");
+
+      const balance = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(balance.amount.toString(), STAKE.toString());
+    });
+  });
+
+  describe("Reward Streaming", () => {
+    // Separate config so the stream does not affect the main config's rewards
+    const STAKE = new anchor.BN(1_000 * 10 ** 9);
+    const DEPOSIT = new anchor.BN(3_600 * 10 ** 9);
+    const STREAM_DURATION = 3_600; // 1 hour
+    let streamMint: PublicKey;
+    let streamConfigPda: PublicKey;
+    let streamReflectionPoolPda: PublicKey;
+    let userStakePda: PublicKey;
+    let stakerTokenAccount: PublicKey;
+    let depositorTokenAccount: PublicKey;
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    before(async () => {
+      streamMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        null,
+        9,
+        undefined,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      streamConfigPda = pda(Buffer.from("config"), streamMint.toBuffer());
+      streamReflectionPoolPda = pda(Buffer.from("reflection_pool"), streamMint.toBuffer());
+      userStakePda = pda(
+        Buffer.from("stake"),
+        streamConfigPda.toBuffer(),
+        user1.publicKey.toBuffer()
+      );
+
+      await program.methods
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: streamMint,
+          stakingVault: pda(Buffer.from("staking_vault"), streamMint.toBuffer()),
+          reflectionPool: streamReflectionPoolPda,
+          config: streamConfigPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      await initializeRoles(streamMint, streamConfigPda);
+
+      stakerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        streamMint,
+        user1.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      depositorTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        streamMint,
+        authority.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        streamMint,
+        stakerTokenAccount,
+        authority,
+        BigInt(STAKE.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        streamMint,
+        depositorTokenAccount,
+        authority,
+        BigInt(DEPOSIT.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .stake(STAKE, null)
+        .accounts({
+          owner: user1.publicKey,
+          tokenMint: streamMint,
+          config: streamConfigPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: pda(Buffer.from("staking_vault"), streamMint.toBuffer()),
+          userStake: userStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+    });
+
+    const proposeStreamDuration = async (seconds: number) => {
+      const config = await program.account.kernelConfig.fetch(streamConfigPda);
+      const governanceAction = governanceActionPda(streamConfigPda, config.governanceActionCount);
+      await program.methods
+        .proposeAction({
+          setRewardStreamDuration: { rewardStreamDuration: new anchor.BN(seconds) },
+        })
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: streamMint,
+          config: streamConfigPda,
+          governanceAction,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      return governanceAction;
+    };
+
+    it("rejects a stream duration above the maximum", async () => {
+      try {
+        await proposeStreamDuration(31 * 24 * 60 * 60);
+        assert.fail("Should have thrown InvalidStreamDuration error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidStreamDuration");
+      }
+    });
+
+    it("changes the stream duration only through the timelock", async () => {
+      const streamAction = await proposeStreamDuration(STREAM_DURATION);
+
+      try {
+        await program.methods
+          .executeAction()
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint: streamMint,
+            config: streamConfigPda,
+            governanceAction: streamAction,
+            stakingTiers: null,
+            guardianSet: null,
+            lpVault: null,
+            roles: null,
+          })
+          .signers([authority])
+          .rpc();
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      await program.methods
+        .cancelAction()
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: streamMint,
+          config: streamConfigPda,
+          governanceAction: streamAction,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+    });
+
+    it("credits deposits at once while no stream duration is set", async () => {
+      await program.methods
+        .depositReflections(DEPOSIT)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: streamMint,
+          config: streamConfigPda,
+          roles: rolesPdaFor(streamConfigPda),
+          authorityTokenAccount: depositorTokenAccount,
+          reflectionPool: streamReflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const config = await program.account.kernelConfig.fetch(streamConfigPda);
+      assert.equal(config.rewardStreamDuration.toNumber(), 0);
+      assert.equal(config.rewardRate.toNumber(), 0);
+      assert.isTrue(config.accumulatedPerShare.gtn(0));
+    });
+  });
+
+  describe("Reflection Shortfall", () => {
+    // Separate mint whose permanent delegate lets the test drain the
+    // reflection pool, standing in for a bug that loses pool funds
+    const STAKE = new anchor.BN(1_000 * 10 ** 9);
+    const DEPOSIT = new anchor.BN(4_000 * 10 ** 9);
+    const SHORTFALL = DEPOSIT.divn(2);
+    let shortMint: Keypair;
+    let shortConfigPda: PublicKey;
+    let shortPoolPda: PublicKey;
+    let depositorTokenAccount: PublicKey;
+    const stakers: { keypair: Keypair; tokenAccount: PublicKey; stake: PublicKey }[] = [];
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    const moveTokens = (from: PublicKey, to: PublicKey, amount: anchor.BN) =>
+      transferChecked(
+        connection,
+        authority,
+        from,
+        shortMint.publicKey,
+        to,
+        authority, // Permanent delegate, so it can also move pool funds
+        BigInt(amount.toString()),
+        9,
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+    const claim = async (staker: (typeof stakers)[number]) => {
+      const before = await getAccount(
+        connection,
+        staker.tokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .claimReflections()
+        .accounts({
+          owner: staker.keypair.publicKey,
+          tokenMint: shortMint.publicKey,
+          config: shortConfigPda,
+          userTokenAccount: staker.tokenAccount,
+          reflectionPool: shortPoolPda,
+          userStake: staker.stake,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([staker.keypair])
+        .rpc({ commitment: "confirmed" });
+      const after = await getAccount(
+        connection,
+        staker.tokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      return (after.amount - before.amount).toString();
+    };
+
+    before(async () => {
+      shortMint = Keypair.generate();
+      shortConfigPda = pda(Buffer.from("config"), shortMint.publicKey.toBuffer());
+      shortPoolPda = pda(Buffer.from("reflection_pool"), shortMint.publicKey.toBuffer());
+
+      const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+      const createMintTx = new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: shortMint.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(
+          shortMint.publicKey,
+          authority.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          shortMint.publicKey,
+          9,
+          authority.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, createMintTx, [
+        authority,
+        shortMint,
+      ]);
+
+      await program.methods
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: shortMint.publicKey,
+          stakingVault: pda(Buffer.from("staking_vault"), shortMint.publicKey.toBuffer()),
+          reflectionPool: shortPoolPda,
+          config: shortConfigPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      await initializeRoles(shortMint.publicKey, shortConfigPda);
+
+      // Two equal stakers, each owed half of every deposit
+      for (const keypair of [user1, user2]) {
+        const tokenAccount = await createAssociatedTokenAccount(
+          connection,
+          authority,
+          shortMint.publicKey,
+          keypair.publicKey,
+          { commitment: "confirmed" },
+          TOKEN_2022_PROGRAM_ID
+        );
+        await mintTo(
+          connection,
+          authority,
+          shortMint.publicKey,
+          tokenAccount,
+          authority,
+          BigInt(STAKE.toString()),
+          [],
+          { commitment: "confirmed" },
+          TOKEN_2022_PROGRAM_ID
+        );
+        const stake = pda(
+          Buffer.from("stake"),
+          shortConfigPda.toBuffer(),
+          keypair.publicKey.toBuffer()
+        );
+        await program.methods
+          .stake(STAKE, null)
+          .accounts({
+            owner: keypair.publicKey,
+            tokenMint: shortMint.publicKey,
+            config: shortConfigPda,
+            userTokenAccount: tokenAccount,
+            stakingVault: pda(Buffer.from("staking_vault"), shortMint.publicKey.toBuffer()),
+            userStake: stake,
+            stakingTiers: null,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([keypair])
+          .rpc({ commitment: "confirmed" });
+        stakers.push({ keypair, tokenAccount, stake });
+      }
+
+      depositorTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        shortMint.publicKey,
+        authority.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        shortMint.publicKey,
+        depositorTokenAccount,
+        authority,
+        BigInt(DEPOSIT.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .depositReflections(DEPOSIT)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint: shortMint.publicKey,
+          config: shortConfigPda,
+          roles: rolesPdaFor(shortConfigPda),
+          authorityTokenAccount: depositorTokenAccount,
+          reflectionPool: shortPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      // Lose half the pool
+      await moveTokens(shortPoolPda, depositorTokenAccount, SHORTFALL);
+    });
+
+    it("pays every claim at the same ratio and keeps the rest owed", async () => {
+      // Each is owed 2000 against a 4000 liability the pool only half covers
+      const owedEach = DEPOSIT.divn(2);
+      const firstPaid = await claim(stakers[0]);
+      const secondPaid = await claim(stakers[1]);
+
+      assert.equal(firstPaid, owedEach.divn(2).toString());
+      assert.equal(secondPaid, firstPaid);
+
+      for (const staker of stakers) {
+        const userStake = await program.account.userStake.fetch(staker.stake);
+        assert.equal(userStake.reflectionOwed.toString(), owedEach.divn(2).toString());
+      }
+      const config = await program.account.kernelConfig.fetch(shortConfigPda);
+      assert.equal(config.pendingReflections.toNumber(), 0);
+      assert.equal(config.reflectionOwed.toString(), SHORTFALL.toString());
+    });
+
+    it("repays what was owed once the pool is topped up", async () => {
+      await moveTokens(depositorTokenAccount, shortPoolPda, SHORTFALL);
+
+      for (const staker of stakers) {
+        assert.equal(await claim(staker), SHORTFALL.divn(2).toString());
+        const userStake = await program.account.userStake.fetch(staker.stake);
+        assert.equal(userStake.reflectionOwed.toNumber(), 0);
+      }
+
+      await program.methods
+        .syncReflectionPool()
+        .accounts({
+          tokenMint: shortMint.publicKey,
+          config: shortConfigPda,
+          reflectionPool: shortPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+
+      const config = await program.account.kernelConfig.fetch(shortConfigPda);
+      assert.equal(config.reflectionOwed.toNumber(), 0);
+      assert.equal(config.reflectionDeficit.toNumber(), 0);
+    });
+  });
+
+  describe("Guardian Set", () => {
+    let guardianSetPda: PublicKey;
+    let outsider: Keypair;
+
+    const actionPda = (nonce: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("guardian_action"),
+          guardianSetPda.toBuffer(),
+          new anchor.BN(nonce).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+
+    const propose = (signer: Keypair, action: any) =>
+      program.methods
+        .proposeGuardianAction(action)
+        .accounts({
+          guardian: signer.publicKey,
+          tokenMint,
+          config: configPda,
+          guardianSet: guardianSetPda,
+        })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    const approve = (signer: Keypair, nonce: number) =>
+      program.methods
+        .approveGuardianAction()
+        .accounts({
+          guardian: signer.publicKey,
+          tokenMint,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          guardianAction: actionPda(nonce),
+        })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    const execute = (nonce: number) =>
+      program.methods
+        .executeGuardianAction()
+        .accounts({
+          executor: user1.publicKey,
+          tokenMint,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          guardianAction: actionPda(nonce),
+        })
+        .signers([user1])
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      outsider = Keypair.generate();
+      [guardianSetPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("guardian_set"), configPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("rejects a threshold above the guardian count", async () => {
+      try {
+        await program.methods
+          .initializeGuardianSet([guardian.publicKey, user2.publicKey], 3)
+          .accounts({
+            authority: authority.publicKey,
+            tokenMint,
+            config: configPda,
+          })
+          .signers([authority])
+          .rpc();
+
+        assert.fail("Should have thrown InvalidGuardianSet error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidGuardianSet");
+      }
+    });
+
+    it("registers a 2-of-2 guardian set", async () => {
+      await program.methods
+        .initializeGuardianSet([guardian.publicKey, user2.publicKey], 2)
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const guardianSet = await program.account.guardianSet.fetch(guardianSetPda);
+      assert.equal(guardianSet.threshold, 2);
+      assert.equal(guardianSet.guardians.length, 2);
+      assert.equal(guardianSet.epoch, 0);
+    });
+
+    it("rejects proposals from non-guardians", async () => {
+      try {
+        await propose(user1, PAUSE_ALL_PAYLOAD);
+        assert.fail("Should have thrown NotGuardian error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotGuardian");
+      }
+    });
+
+    it("executes an emergency fee update only after M approvals", async () => {
+      await propose(guardian, {
+        updateFees: { reflectionShareBps: 250, lpShareBps: 150, burnShareBps: 100 },
+      });
+
+      // One approval (the proposer's) is not enough
+      try {
+        await execute(0);
+        assert.fail("Should have thrown GuardianThresholdNotMet error");
+      } catch (err: any) {
+        expect(err.message).to.include("GuardianThresholdNotMet");
+      }
+
+      // Approving twice does not count twice
+      try {
+        await approve(guardian, 0);
+        assert.fail("Should have thrown AlreadyApproved error");
+      } catch (err: any) {
+        expect(err.message).to.include("AlreadyApproved");
+      }
+
+      await approve(user2, 0);
+      await execute(0);
+
+      let config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.reflectionShareBps, 250);
+      assert.equal(config.lpShareBps, 150);
+      assert.equal(config.burnShareBps, 100);
+
+      const action = await program.account.guardianAction.fetch(actionPda(0));
+      assert.isTrue(action.executed);
+      assert.equal(action.expiresAt.sub(action.proposedAt).toNumber(), 3 * 24 * 60 * 60);
+
+      // Restore the original split through the same path
+      await propose(user2, {
+        updateFees: {
+          reflectionShareBps: REFLECTION_BPS,
+          lpShareBps: LP_BPS,
+          burnShareBps: BURN_BPS,
+        },
+      });
+      await approve(guardian, 1);
+      await execute(1);
+
+      config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.reflectionShareBps, REFLECTION_BPS);
+    });
+
+    it("rejects an emergency action that was already executed", async () => {
+      try {
+        await execute(0);
+        assert.fail("Should have thrown ProposalAlreadyExecuted error");
+      } catch (err: any) {
+        expect(err.message).to.include("ProposalAlreadyExecuted");
+      }
+    });
+
+    it("rejects a pause whose expiry passed before it was executed", async () => {
+      await propose(guardian, {
+        setPauseFlags: {
+          flags: PAUSE_ALL,
+          pausedUntil: new anchor.BN(Math.floor(Date.now() / 1000) + 3),
+        },
+      });
+      await approve(user2, 2);
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+
+      try {
+        await execute(2);
+        assert.fail("Should have thrown InvalidPause error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidPause");
+      }
+
+      const config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.pauseFlags, 0);
+    });
+
+    it("maps the pre-flags guardian pause onto the stake flag", async () => {
+      await propose(guardian, { setPaused: { paused: true } });
+      await approve(user2, 3);
+      await execute(3);
+
+      let config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.pauseFlags, PAUSE_STAKE);
+      assert.equal(config.pausedUntil.toNumber(), 0);
+
+      await propose(guardian, { setPaused: { paused: false } });
+      await approve(user2, 4);
+      await execute(4);
+
+      config = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(config.pauseFlags, 0);
+    });
+
+    it("timelocks guardian membership changes", async () => {
+      const membershipAction = await proposeAction({
+        updateGuardianSet: {
+          guardians: [guardian.publicKey, user2.publicKey, outsider.publicKey],
+          threshold: 2,
+        },
+      });
+
+      try {
+        await executeAction(membershipAction, { guardianSet: guardianSetPda });
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      // Membership is unchanged until the timelock expires
+      const guardianSet = await program.account.guardianSet.fetch(guardianSetPda);
+      assert.equal(guardianSet.guardians.length, 2);
+
+      await cancelAction(membershipAction);
+    });
+  });
+
+  describe("Roles", () => {
+    it("creates the role table only once", async () => {
+      try {
+        await initializeRoles(tokenMint, configPda);
+        assert.fail("Should have thrown error - roles already exist");
+      } catch (err: any) {
+        expect(err.message).to.include("already in use");
+      }
+    });
+
+    it("queues a role grant behind the roles delay", async () => {
+      const grantAction = await proposeAction({
+        grantRole: { role: { pauser: {} }, holder: guardian.publicKey },
+      });
+
+      const action = await program.account.governanceAction.fetch(grantAction);
+      assert.equal(action.eta.sub(action.proposedAt).toString(), ACTION_DELAY.toString());
+
+      try {
+        await executeAction(grantAction, { roles: rolesPda });
+        assert.fail("Should have thrown TimelockNotExpired error");
+      } catch (err: any) {
+        expect(err.message).to.include("TimelockNotExpired");
+      }
+
+      // The grant only lands after the timelock
+      const roles = await program.account.roles.fetch(rolesPda);
+      assert.equal(roles.pauser.toBase58(), authority.publicKey.toBase58());
+
+      await cancelAction(grantAction);
+    });
+
+    it("rejects granting a role to the default key", async () => {
+      try {
+        await proposeAction({
+          grantRole: { role: { burner: {} }, holder: PublicKey.default },
+        });
+        assert.fail("Should have thrown InvalidRoleHolder error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidRoleHolder");
+      }
+    });
+  });
+
+  describe("Emergency Unstake", () => {
+    const STAKE_AMOUNT = new anchor.BN(10_000 * 10 ** 9);
+    const staker = Keypair.generate();
+    let stakerTokenAccount: PublicKey;
+    let stakerStakePda: PublicKey;
+
+    const emergencyUnstake = () =>
+      program.methods
+        .emergencyUnstake()
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          unbondingTicket: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(staker.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      stakerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        tokenMint,
+        staker.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        tokenMint,
+        stakerTokenAccount,
+        authority,
+        BigInt(STAKE_AMOUNT.toString()),
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      [stakerStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), staker.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .stake(STAKE_AMOUNT, null)
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+      // Give the position rewards to forfeit
+      await program.methods
+        .depositReflections(new anchor.BN(1_000 * 10 ** 9))
+        .accounts({
+          authority: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          roles: rolesPda,
+          authorityTokenAccount,
+          reflectionPool: reflectionPoolPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+    });
+
+    it("returns the principal and forfeits rewards, even while paused", async () => {
+      const configBefore = await program.account.kernelConfig.fetch(configPda);
+
+      await setPaused(PAUSE_ALL);
+      await emergencyUnstake();
+      await setPaused(0);
+
+      const balance = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(balance.amount.toString(), STAKE_AMOUNT.toString());
+
+      const userStake = await program.account.userStake.fetch(stakerStakePda);
+      assert.equal(userStake.stakedAmount.toNumber(), 0);
+      assert.equal(userStake.shares.toNumber(), 0);
+      assert.equal(userStake.pendingRewards.toNumber(), 0);
+      assert.equal(userStake.rewardDebt.toString(), "0");
+
+      const configAfter = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(
+        configBefore.totalStaked.sub(configAfter.totalStaked).toString(),
+        STAKE_AMOUNT.toString()
+      );
+      assert.equal(
+        configAfter.accumulatedPerShare.toString(),
+        configBefore.accumulatedPerShare.toString()
+      );
+    });
+
+    it("rejects an empty position", async () => {
+      try {
+        await emergencyUnstake();
+        assert.fail("Should have thrown InsufficientStake error");
+      } catch (err: any) {
+        expect(err.message).to.include("InsufficientStake");
+      }
+    });
+
+    it("sends the principal through the unbonding queue when given a ticket", async () => {
+      await program.methods
+        .stake(STAKE_AMOUNT, null)
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+      const stakeBefore = await program.account.userStake.fetch(stakerStakePda);
+      const [ticketPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("unbonding"),
+          stakerStakePda.toBuffer(),
+          stakeBefore.unbondingNonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .emergencyUnstake()
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          unbondingTicket: ticketPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+      // Nothing is paid out yet: the ticket waits out whatever cooldown is set
+      const balance = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(balance.amount.toString(), "0");
+
+      const config = await program.account.kernelConfig.fetch(configPda);
+      const ticket = await program.account.unbondingTicket.fetch(ticketPda);
+      assert.equal(ticket.amount.toString(), STAKE_AMOUNT.toString());
+      assert.equal(
+        ticket.releaseTime.sub(ticket.requestedAt).toString(),
+        config.unbondingPeriod.toString()
+      );
+
+      const stakeAfter = await program.account.userStake.fetch(stakerStakePda);
+      assert.equal(stakeAfter.stakedAmount.toNumber(), 0);
+      assert.equal(stakeAfter.unbondingAmount.toString(), STAKE_AMOUNT.toString());
+
+      await program.methods
+        .withdrawUnbonded()
+        .accounts({
+          owner: staker.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: stakerTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: stakerStakePda,
+          unbondingTicket: ticketPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" });
+
+      const released = await getAccount(
+        connection,
+        stakerTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(released.amount.toString(), STAKE_AMOUNT.toString());
+    });
+  });
+
+  describe("Delegated Staking", () => {
+    const STAKE_AMOUNT = new anchor.BN(5_000 * 10 ** 9);
+    const beneficiary = Keypair.generate();
+    let beneficiaryTokenAccount: PublicKey;
+    let beneficiaryStakePda: PublicKey;
+
+    const stakeFor = (beneficiaryOnly: boolean, signed = !beneficiaryOnly) =>
+      program.methods
+        .stakeFor(beneficiary.publicKey, STAKE_AMOUNT, null, beneficiaryOnly)
+        .accounts({
+          funder: authority.publicKey,
+          beneficiarySigner: signed ? beneficiary.publicKey : null,
+          tokenMint,
+          config: configPda,
+          funderTokenAccount: authorityTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: beneficiaryStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signed ? [authority, beneficiary] : [authority])
+        .rpc({ commitment: "confirmed" });
+
+    const emergencyUnstakeAs = (signer: Keypair, tokenAccount: PublicKey) =>
+      program.methods
+        .emergencyUnstake()
+        .accounts({
+          owner: signer.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: tokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake: beneficiaryStakePda,
+          unbondingTicket: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    const balanceOf = async (tokenAccount: PublicKey) =>
+      (await getAccount(connection, tokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+
+    before(async () => {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(beneficiary.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      beneficiaryTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        tokenMint,
+        beneficiary.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      [beneficiaryStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), beneficiary.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("rejects a delegated stake the beneficiary did not sign", async () => {
+      try {
+        await stakeFor(false, false);
+        assert.fail("Should have thrown BeneficiaryNotSigner error");
+      } catch (err: any) {
+        expect(err.message).to.include("BeneficiaryNotSigner");
+      }
+    });
+
+    it("credits the beneficiary and keeps withdrawals with the funder", async () => {
+      await stakeFor(false);
+
+      const userStake = await program.account.userStake.fetch(beneficiaryStakePda);
+      assert.equal(userStake.owner.toBase58(), beneficiary.publicKey.toBase58());
+      assert.equal(userStake.delegate.toBase58(), authority.publicKey.toBase58());
+      assert.equal(userStake.stakedAmount.toString(), STAKE_AMOUNT.toString());
+    });
+
+    it("rejects withdrawals by the beneficiary of a delegated stake", async () => {
+      try {
+        await emergencyUnstakeAs(beneficiary, beneficiaryTokenAccount);
+        assert.fail("Should have thrown NotOwner error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotOwner");
+      }
+    });
+
+    it("returns delegated principal to the funder", async () => {
+      const before = await balanceOf(authorityTokenAccount);
+      await emergencyUnstakeAs(authority, authorityTokenAccount);
+      const after = await balanceOf(authorityTokenAccount);
+
+      assert.equal((after - before).toString(), STAKE_AMOUNT.toString());
+      const userStake = await program.account.userStake.fetch(beneficiaryStakePda);
+      assert.equal(userStake.delegate.toBase58(), PublicKey.default.toBase58());
+    });
+
+    it("lets only the beneficiary withdraw a beneficiary-only stake", async () => {
+      await stakeFor(true);
+
+      try {
+        await emergencyUnstakeAs(authority, authorityTokenAccount);
+        assert.fail("Should have thrown NotOwner error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotOwner");
+      }
+
+      await emergencyUnstakeAs(beneficiary, beneficiaryTokenAccount);
+      assert.equal(
+        (await balanceOf(beneficiaryTokenAccount)).toString(),
+        STAKE_AMOUNT.toString()
+      );
+    });
+
+    it("rejects delegating a position that already holds a gift", async () => {
+      await stakeFor(true);
+      try {
+        await stakeFor(false);
+        assert.fail("Should have thrown DelegationMismatch error");
+      } catch (err: any) {
+        expect(err.message).to.include("DelegationMismatch");
+      }
+    });
+  });
+
+  describe("Stake Positions", () => {
+    const STAKE_AMOUNT = new anchor.BN(2_000 * 10 ** 9);
+    const buyer = Keypair.generate();
+    let buyerTokenAccount: PublicKey;
+    let positionPda: PublicKey;
+    let positionStakePda: PublicKey;
+    let receiptMint: PublicKey;
+
+    const positionPdaFor = (id: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), configPda.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const receiptAccountOf = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(receiptMint, owner, false, TOKEN_2022_PROGRAM_ID);
+
+    const positionAccounts = (holder: Keypair, receiptAccount: PublicKey | null) => ({
+      holder: holder.publicKey,
+      tokenMint,
+      config: configPda,
+      position: positionPda,
+      receiptAccount,
+      userStake: positionStakePda,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    const unstakePosition = (
+      holder: Keypair,
+      holderTokenAccount: PublicKey,
+      receiptAccount: PublicKey | null
+    ) =>
+      program.methods
+        .unstakePosition(STAKE_AMOUNT)
+        .accounts({
+          ...positionAccounts(holder, receiptAccount),
+          holderTokenAccount,
+          stakingVault: stakingVaultPda,
+        })
+        .signers([holder])
+        .rpc({ commitment: "confirmed" });
+
+    const transferPosition = (owner: Keypair, newOwner: PublicKey) =>
+      program.methods
+        .transferPosition(newOwner)
+        .accounts({
+          owner: owner.publicKey,
+          tokenMint,
+          config: configPda,
+          position: positionPda,
+        })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(buyer.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      buyerTokenAccount = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        tokenMint,
+        buyer.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    it("opens a position under the next position id", async () => {
+      const config = await program.account.kernelConfig.fetch(configPda);
+      positionPda = positionPdaFor(config.positionCount);
+      [positionStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), positionPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .openPosition(STAKE_AMOUNT, null)
+        .accounts({
+          owner: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: authorityTokenAccount,
+          stakingVault: stakingVaultPda,
+          position: positionPda,
+          userStake: positionStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const position = await program.account.stakePosition.fetch(positionPda);
+      assert.equal(position.positionId.toString(), config.positionCount.toString());
+      assert.equal(position.owner.toBase58(), authority.publicKey.toBase58());
+      const userStake = await program.account.userStake.fetch(positionStakePda);
+      assert.equal(userStake.owner.toBase58(), positionPda.toBase58());
+      assert.equal(userStake.stakedAmount.toString(), STAKE_AMOUNT.toString());
+
+      const configAfter = await program.account.kernelConfig.fetch(configPda);
+      assert.equal(
+        configAfter.positionCount.toString(),
+        config.positionCount.addn(1).toString()
+      );
+    });
+
+    it("moves a position without a receipt to a new owner", async () => {
+      try {
+        await transferPosition(authority, PublicKey.default);
+        assert.fail("Should have thrown InvalidPositionOwner error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidPositionOwner");
+      }
+
+      await transferPosition(authority, buyer.publicKey);
+
+      try {
+        await unstakePosition(authority, authorityTokenAccount, null);
+        assert.fail("Should have thrown NotOwner error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotOwner");
+      }
+    });
+
+    it("mints a receipt NFT that takes over control", async () => {
+      [receiptMint] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position_receipt"), positionPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .mintPositionReceipt()
+        .accounts({
+          owner: buyer.publicKey,
+          tokenMint,
+          config: configPda,
+          position: positionPda,
+          receiptMint,
+          receiptAccount: receiptAccountOf(buyer.publicKey),
+          receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc({ commitment: "confirmed" });
+
+      const mint = await getMint(connection, receiptMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(mint.supply.toString(), "1");
+      assert.equal(mint.decimals, 0);
+      assert.isNull(mint.mintAuthority);
+
+      try {
+        await transferPosition(buyer, authority.publicKey);
+        assert.fail("Should have thrown ReceiptAlreadyMinted error");
+      } catch (err: any) {
+        expect(err.message).to.include("ReceiptAlreadyMinted");
+      }
+    });
+
+    it("hands the position to whoever holds the receipt", async () => {
+      const authorityReceipt = await createAssociatedTokenAccount(
+        connection,
+        authority,
+        receiptMint,
+        authority.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await transferChecked(
+        connection,
+        buyer,
+        receiptAccountOf(buyer.publicKey),
+        receiptMint,
+        authorityReceipt,
+        buyer,
+        1,
+        0,
+        [],
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      try {
+        await unstakePosition(buyer, buyerTokenAccount, receiptAccountOf(buyer.publicKey));
+        assert.fail("Should have thrown InvalidReceipt error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidReceipt");
+      }
+
+      const before = (
+        await getAccount(connection, authorityTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)
+      ).amount;
+      await unstakePosition(authority, authorityTokenAccount, authorityReceipt);
+      const after = (
+        await getAccount(connection, authorityTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)
+      ).amount;
+
+      assert.equal((after - before).toString(), STAKE_AMOUNT.toString());
+      const userStake = await program.account.userStake.fetch(positionStakePda);
+      assert.equal(userStake.stakedAmount.toNumber(), 0);
+    });
+
+    it("emergency-unstakes a position for its owner, even while paused", async () => {
+      const config = await program.account.kernelConfig.fetch(configPda);
+      const exitPositionPda = positionPdaFor(config.positionCount);
+      const [exitStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), exitPositionPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .openPosition(STAKE_AMOUNT, null)
+        .accounts({
+          owner: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          userTokenAccount: authorityTokenAccount,
+          stakingVault: stakingVaultPda,
+          position: exitPositionPda,
+          userStake: exitStakePda,
+          stakingTiers: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+      const emergencyUnstakePosition = (holder: Keypair, holderTokenAccount: PublicKey) =>
+        program.methods
+          .emergencyUnstakePosition()
+          .accounts({
+            ...positionAccounts(holder, null),
+            position: exitPositionPda,
+            userStake: exitStakePda,
+            holderTokenAccount,
+            stakingVault: stakingVaultPda,
+            unbondingTicket: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([holder])
+          .rpc({ commitment: "confirmed" });
+
+      try {
+        await emergencyUnstakePosition(buyer, buyerTokenAccount);
+        assert.fail("Should have thrown NotOwner error");
+      } catch (err: any) {
+        expect(err.message).to.include("NotOwner");
+      }
+
+      const before = (
+        await getAccount(connection, authorityTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)
+      ).amount;
+      await setPaused(PAUSE_ALL);
+      await emergencyUnstakePosition(authority, authorityTokenAccount);
+      await setPaused(0);
+      const after = (
+        await getAccount(connection, authorityTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)
+      ).amount;
+
+      assert.equal((after - before).toString(), STAKE_AMOUNT.toString());
+      const userStake = await program.account.userStake.fetch(exitStakePda);
+      assert.equal(userStake.stakedAmount.toNumber(), 0);
+      assert.equal(userStake.pendingRewards.toNumber(), 0);
+    });
+  });
+
+  describe("Vesting", () => {
+    const GRANT = new anchor.BN(10_000 * 10 ** 9);
+    const VESTING_STAKE = new anchor.BN(1_000 * 10 ** 9);
+    const DURATION = 1_000;
+    const beneficiary = Keypair.generate();
+    let beneficiaryTokenAccount: PublicKey;
+    let beneficiaryStakePda: PublicKey;
+    let schedulePda: PublicKey;
+    let scheduleStakePda: PublicKey;
+
+    const vestingPdaFor = (id: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting"), configPda.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const stakeOf = (schedule: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), schedule.toBuffer()],
+        program.programId
+      )[0];
+
+    const vaultOf = (schedule: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting_vault"), schedule.toBuffer()],
+        program.programId
+      )[0];
+
+    const createVesting = async (cliff: number, revocable: boolean) => {
+      const config = await program.account.kernelConfig.fetch(configPda);
+      const schedule = vestingPdaFor(config.vestingCount);
+      // Started half way through, so about half of the grant has vested
+      const start = Math.floor(Date.now() / 1000) - DURATION / 2;
+
+      await program.methods
+        .createVesting(
+          beneficiary.publicKey,
+          GRANT,
+          new anchor.BN(cliff),
+          new anchor.BN(start),
+          new anchor.BN(DURATION),
+          revocable
+        )
+        .accounts({
+          grantor: authority.publicKey,
+          tokenMint,
+          config: configPda,
+          grantorTokenAccount: authorityTokenAccount,
+          vestingSchedule: schedule,
+          vestingVault: vaultOf(schedule),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      return schedule;
+    };
 
-      const tx = await program.methods
-        .withdrawFromLpVault(withdrawAmount)
+    const revokeVesting = (schedule: PublicKey, userStake: PublicKey | null) =>
+      program.methods
+        .revokeVesting()
         .accounts({
-          authority: authority.publicKey,
+          grantor: authority.publicKey,
           tokenMint,
           config: configPda,
-          lpVault: lpVaultPda,
-          authorityTokenAccount,
-          lpVaultToken: lpVaultTokenPda,
+          vestingSchedule: schedule,
+          vestingVault: vaultOf(schedule),
+          grantorTokenAccount: authorityTokenAccount,
+          stakingVault: stakingVaultPda,
+          userStake,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
         })
         .signers([authority])
         .rpc({ commitment: "confirmed" });
 
-      // Wait for confirmation to ensure state propagation
-      await connection.confirmTransaction(tx, "confirmed");
-
-      console.log("Withdraw from LP vault tx:", tx);
+    const balanceOf = async (tokenAccount: PublicKey) =>
+      (await getAccount(connection, tokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
 
-      // Verify LP vault updated
-      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
-      assert.equal(
-        lpVault.pendingDeployment.toNumber(),
-        lpVaultBefore.pendingDeployment.toNumber() - withdrawAmount.toNumber()
+    before(async () => {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(beneficiary.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
       );
-
-      // Verify authority balance increased
-      const balanceAfter = await getAccount(
+      beneficiaryTokenAccount = await createAssociatedTokenAccount(
         connection,
-        authorityTokenAccount,
-        "confirmed",
+        authority,
+        tokenMint,
+        beneficiary.publicKey,
+        { commitment: "confirmed" },
         TOKEN_2022_PROGRAM_ID
       );
-      assert.equal(
-        Number(balanceAfter.amount) - Number(balanceBefore.amount),
-        withdrawAmount.toNumber()
+      [beneficiaryStakePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), beneficiary.publicKey.toBuffer()],
+        program.programId
       );
     });
 
-    it("fails to withdraw more than pending", async () => {
-      const lpVault = await program.account.lpVault.fetch(lpVaultPda);
-      const tooMuch = new anchor.BN(lpVault.pendingDeployment.toNumber() + 1);
+    it("rejects a cliff longer than the duration", async () => {
+      try {
+        await createVesting(DURATION + 1, true);
+        assert.fail("Should have thrown InvalidVestingSchedule error");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidVestingSchedule");
+      }
+    });
+
+    it("locks the grant in a vault per schedule", async () => {
+      schedulePda = await createVesting(0, true);
+      scheduleStakePda = stakeOf(schedulePda);
+
+      const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+      assert.equal(schedule.beneficiary.toBase58(), beneficiary.publicKey.toBase58());
+      assert.equal(schedule.totalAmount.toString(), GRANT.toString());
+      assert.equal((await balanceOf(vaultOf(schedulePda))).toString(), GRANT.toString());
+    });
+
+    it("releases only what has vested", async () => {
+      await program.methods
+        .releaseVested()
+        .accounts({
+          beneficiary: beneficiary.publicKey,
+          tokenMint,
+          config: configPda,
+          vestingSchedule: schedulePda,
+          vestingVault: vaultOf(schedulePda),
+          beneficiaryTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+      const released = await balanceOf(beneficiaryTokenAccount);
+      assert.isTrue(released > BigInt(0));
+      assert.isTrue(released < BigInt(GRANT.toString()));
+      const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+      assert.equal(schedule.releasedAmount.toString(), released.toString());
+    });
+
+    it("stakes locked tokens on the schedule's own stake", async () => {
+      await program.methods
+        .stakeVesting(VESTING_STAKE)
+        .accounts({
+          beneficiary: beneficiary.publicKey,
+          tokenMint,
+          config: configPda,
+          vestingSchedule: schedulePda,
+          vestingVault: vaultOf(schedulePda),
+          stakingVault: stakingVaultPda,
+          userStake: scheduleStakePda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
 
+      const userStake = await program.account.userStake.fetch(scheduleStakePda);
+      assert.equal(userStake.stakedAmount.toString(), VESTING_STAKE.toString());
+      assert.equal(userStake.owner.toBase58(), schedulePda.toBase58());
+      assert.equal(userStake.delegate.toBase58(), schedulePda.toBase58());
+
+      // The tokens are still locked, so the beneficiary cannot pull them out
       try {
         await program.methods
-          .withdrawFromLpVault(tooMuch)
+          .emergencyUnstake()
           .accounts({
-            authority: authority.publicKey,
+            owner: beneficiary.publicKey,
             tokenMint,
             config: configPda,
-            lpVault: lpVaultPda,
-            authorityTokenAccount,
-            lpVaultToken: lpVaultTokenPda,
+            userTokenAccount: beneficiaryTokenAccount,
+            stakingVault: stakingVaultPda,
+            userStake: scheduleStakePda,
+            unbondingTicket: null,
             tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
           })
-          .signers([authority])
-          .rpc();
-
-        assert.fail("Should have thrown InsufficientLPFunds error");
+          .signers([beneficiary])
+          .rpc({ commitment: "confirmed" });
+        assert.fail("Should have thrown NotOwner error");
       } catch (err: any) {
-        expect(err.message).to.include("InsufficientLPFunds");
+        expect(err.message).to.include("NotOwner");
       }
     });
-  });
-
-  describe("Multi-User Reflections", () => {
-    it("distributes reflections proportionally to multiple stakers", async () => {
-      // User2 stakes tokens
-      const user2StakeAmount = new anchor.BN(2_000_000 * 10 ** 9); // 2M tokens
-
-      const [user2StakePda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer()],
-        program.programId
-      );
 
+    it("leaves the beneficiary's own stake free and pays them the schedule's rewards", async () => {
+      // Released tokens go on the wallet stake while the schedule is staked
+      const released = await balanceOf(beneficiaryTokenAccount);
       await program.methods
-        .stake(user2StakeAmount)
+        .stake(new anchor.BN(released.toString()), null)
         .accounts({
-          owner: user2.publicKey,
+          owner: beneficiary.publicKey,
           tokenMint,
           config: configPda,
-          userTokenAccount: user2TokenAccount,
+          userTokenAccount: beneficiaryTokenAccount,
           stakingVault: stakingVaultPda,
-          userStake: user2StakePda,
+          userStake: beneficiaryStakePda,
+          stakingTiers: null,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([user2])
+        .signers([beneficiary])
         .rpc({ commitment: "confirmed" });
-
-      console.log("User2 staked:", user2StakeAmount.toString());
-
-      // Get stake amounts for proportion calculation
-      const [user1StakePda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
-        program.programId
-      );
-
-      const user1Stake = await program.account.userStake.fetch(user1StakePda);
-      const user2Stake = await program.account.userStake.fetch(user2StakePda);
-
-      console.log("User1 staked:", user1Stake.stakedAmount.toString());
-      console.log("User2 staked:", user2Stake.stakedAmount.toString());
-
-      // Deposit reflections
-      const reflectionAmount = new anchor.BN(50_000 * 10 ** 9); // 50K tokens
+      const walletStake = await program.account.userStake.fetch(beneficiaryStakePda);
+      assert.equal(walletStake.stakedAmount.toString(), released.toString());
+      assert.equal(walletStake.delegate.toBase58(), PublicKey.default.toBase58());
 
       await program.methods
-        .depositReflections(reflectionAmount)
+        .depositReflections(new anchor.BN(1_000 * 10 ** 9))
         .accounts({
           authority: authority.publicKey,
           tokenMint,
           config: configPda,
+          roles: rolesPda,
           authorityTokenAccount,
           reflectionPool: reflectionPoolPda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1440,93 +5110,311 @@ describe("kernel-token", () => {
         .signers([authority])
         .rpc({ commitment: "confirmed" });
 
-      console.log("Deposited reflections:", reflectionAmount.toString());
-
-      // Get balances before claims
-      const user1BalanceBefore = await getAccount(
-        connection,
-        user1TokenAccount,
-        "confirmed",
-        TOKEN_2022_PROGRAM_ID
-      );
-
-      const user2BalanceBefore = await getAccount(
-        connection,
-        user2TokenAccount,
-        "confirmed",
-        TOKEN_2022_PROGRAM_ID
-      );
-
-      // User1 claims
       await program.methods
-        .claimReflections()
+        .claimVestingRewards()
         .accounts({
-          owner: user1.publicKey,
+          beneficiary: beneficiary.publicKey,
           tokenMint,
           config: configPda,
-          userTokenAccount: user1TokenAccount,
+          beneficiaryTokenAccount,
           reflectionPool: reflectionPoolPda,
-          userStake: user1StakePda,
+          vestingSchedule: schedulePda,
+          userStake: scheduleStakePda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
         })
-        .signers([user1])
+        .signers([beneficiary])
         .rpc({ commitment: "confirmed" });
+      assert.isTrue((await balanceOf(beneficiaryTokenAccount)) > BigInt(0));
+    });
+
+    it("revokes the unvested part back to the grantor, unstaking it first", async () => {
+      const grantorBefore = await balanceOf(authorityTokenAccount);
+      await revokeVesting(schedulePda, scheduleStakePda);
+      const returned = (await balanceOf(authorityTokenAccount)) - grantorBefore;
+
+      const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+      assert.isTrue(schedule.revokedAt.toNumber() > 0);
+      assert.equal(schedule.stakedAmount.toNumber(), 0);
+      assert.equal(
+        (BigInt(schedule.totalAmount.toString()) + returned).toString(),
+        GRANT.toString()
+      );
+
+      const userStake = await program.account.userStake.fetch(scheduleStakePda);
+      assert.equal(userStake.stakedAmount.toNumber(), 0);
+      assert.equal(userStake.delegate.toBase58(), PublicKey.default.toBase58());
+
+      try {
+        await revokeVesting(schedulePda, null);
+        assert.fail("Should have thrown VestingRevoked error");
+      } catch (err: any) {
+        expect(err.message).to.include("VestingRevoked");
+      }
+    });
+
+    it("rejects revoking an irrevocable grant", async () => {
+      const schedule = await createVesting(0, false);
+      try {
+        await revokeVesting(schedule, null);
+        assert.fail("Should have thrown VestingNotRevocable error");
+      } catch (err: any) {
+        expect(err.message).to.include("VestingNotRevocable");
+      }
+    });
+  });
+
+  describe("Vesting Transfer Fees", () => {
+    // Separate mint with a 5% transfer fee, so every move between the
+    // vesting vault and the staking vault loses part of the amount
+    const FEE_BPS = 500;
+    const GRANT = new anchor.BN(10_000 * 10 ** 9);
+    const DURATION = 1_000;
+    const beneficiary = Keypair.generate();
+    let feeMint: Keypair;
+    let feeConfigPda: PublicKey;
+    let feeStakingVaultPda: PublicKey;
+    let grantorTokenAccount: PublicKey;
+    let beneficiaryTokenAccount: PublicKey;
+    let scheduleStakePda: PublicKey;
+    let schedulePda: PublicKey;
+    let vaultPda: PublicKey;
+
+    const pda = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    const balanceOf = async (tokenAccount: PublicKey) =>
+      (await getAccount(connection, tokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+
+    const netOf = (amount: bigint) => amount - (amount * BigInt(FEE_BPS)) / BigInt(10_000);
+
+    // The schedule must always account for exactly what its vault holds
+    const assertVaultMatchesSchedule = async () => {
+      const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+      const held = schedule.totalAmount.sub(schedule.releasedAmount).sub(schedule.stakedAmount);
+      assert.equal((await balanceOf(vaultPda)).toString(), held.toString());
+      return schedule;
+    };
+
+    const vestingAccounts = () => ({
+      beneficiary: beneficiary.publicKey,
+      tokenMint: feeMint.publicKey,
+      config: feeConfigPda,
+      vestingSchedule: schedulePda,
+      vestingVault: vaultPda,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    before(async () => {
+      feeMint = Keypair.generate();
+      feeConfigPda = pda(Buffer.from("config"), feeMint.publicKey.toBuffer());
+      feeStakingVaultPda = pda(Buffer.from("staking_vault"), feeMint.publicKey.toBuffer());
+
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const createMintTx = new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          authority.publicKey,
+          authority.publicKey,
+          FEE_BPS,
+          BigInt(Number.MAX_SAFE_INTEGER),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint.publicKey,
+          9,
+          authority.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, createMintTx, [
+        authority,
+        feeMint,
+      ]);
 
-      // User2 claims
       await program.methods
-        .claimReflections()
+        .initialize(REFLECTION_BPS, LP_BPS, BURN_BPS, ACTION_DELAY)
         .accounts({
-          owner: user2.publicKey,
-          tokenMint,
-          config: configPda,
-          userTokenAccount: user2TokenAccount,
-          reflectionPool: reflectionPoolPda,
-          userStake: user2StakePda,
+          authority: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          stakingVault: feeStakingVaultPda,
+          reflectionPool: pda(Buffer.from("reflection_pool"), feeMint.publicKey.toBuffer()),
+          config: feeConfigPda,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
-        .signers([user2])
-        .rpc({ commitment: "confirmed" });
+        .signers([authority])
+        .rpc();
 
-      // Get balances after claims
-      const user1BalanceAfter = await getAccount(
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(beneficiary.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      grantorTokenAccount = await createAssociatedTokenAccount(
         connection,
-        user1TokenAccount,
-        "confirmed",
+        authority,
+        feeMint.publicKey,
+        authority.publicKey,
+        { commitment: "confirmed" },
         TOKEN_2022_PROGRAM_ID
       );
-
-      const user2BalanceAfter = await getAccount(
+      beneficiaryTokenAccount = await createAssociatedTokenAccount(
         connection,
-        user2TokenAccount,
-        "confirmed",
+        authority,
+        feeMint.publicKey,
+        beneficiary.publicKey,
+        { commitment: "confirmed" },
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        authority,
+        feeMint.publicKey,
+        grantorTokenAccount,
+        authority,
+        BigInt(GRANT.toString()),
+        [],
+        { commitment: "confirmed" },
         TOKEN_2022_PROGRAM_ID
       );
+    });
 
-      const user1Rewards = Number(user1BalanceAfter.amount) - Number(user1BalanceBefore.amount);
-      const user2Rewards = Number(user2BalanceAfter.amount) - Number(user2BalanceBefore.amount);
+    it("records the grant the vault received, net of the fee", async () => {
+      schedulePda = pda(
+        Buffer.from("vesting"),
+        feeConfigPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      );
+      vaultPda = pda(Buffer.from("vesting_vault"), schedulePda.toBuffer());
+      scheduleStakePda = pda(Buffer.from("stake"), feeConfigPda.toBuffer(), schedulePda.toBuffer());
+      // Started half way through, so about half of the grant has vested
+      const start = Math.floor(Date.now() / 1000) - DURATION / 2;
 
-      console.log("User1 rewards:", user1Rewards);
-      console.log("User2 rewards:", user2Rewards);
+      await program.methods
+        .createVesting(
+          beneficiary.publicKey,
+          GRANT,
+          new anchor.BN(0),
+          new anchor.BN(start),
+          new anchor.BN(DURATION),
+          true
+        )
+        .accounts({
+          grantor: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          config: feeConfigPda,
+          grantorTokenAccount,
+          vestingSchedule: schedulePda,
+          vestingVault: vaultPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
 
-      // Both users should have received rewards
-      assert.isTrue(user1Rewards > 0, "User1 should receive rewards");
-      assert.isTrue(user2Rewards > 0, "User2 should receive rewards");
+      const schedule = await assertVaultMatchesSchedule();
+      assert.equal(
+        schedule.totalAmount.toString(),
+        netOf(BigInt(GRANT.toString())).toString()
+      );
+    });
 
-      // User2 staked more, should receive more rewards
-      // User2 has 2M, proportionally more than user1's remaining stake
-      const totalStaked = user1Stake.stakedAmount.toNumber() + user2Stake.stakedAmount.toNumber();
-      const expectedUser2Proportion = user2Stake.stakedAmount.toNumber() / totalStaked;
+    it("stakes and unstakes at the amounts that actually arrive", async () => {
+      const amount = BigInt(1_000 * 10 ** 9);
+      await program.methods
+        .stakeVesting(new anchor.BN(amount.toString()))
+        .accounts({
+          ...vestingAccounts(),
+          stakingVault: feeStakingVaultPda,
+          userStake: scheduleStakePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
 
-      // User2's rewards should be proportional to their stake (with some tolerance for rounding)
-      const actualUser2Proportion = user2Rewards / (user1Rewards + user2Rewards);
-      const tolerance = 0.05; // 5% tolerance for rounding
+      const userStake = await program.account.userStake.fetch(scheduleStakePda);
+      assert.equal(userStake.stakedAmount.toString(), netOf(amount).toString());
+      let schedule = await assertVaultMatchesSchedule();
+      assert.equal(schedule.stakedAmount.toString(), netOf(amount).toString());
 
-      assert.approximately(
-        actualUser2Proportion,
-        expectedUser2Proportion,
-        tolerance,
-        "Rewards should be distributed proportionally"
-      );
+      await program.methods
+        .unstakeVesting(userStake.stakedAmount)
+        .accounts({
+          ...vestingAccounts(),
+          stakingVault: feeStakingVaultPda,
+          userStake: scheduleStakePda,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+      schedule = await assertVaultMatchesSchedule();
+      assert.equal(schedule.stakedAmount.toNumber(), 0);
+    });
+
+    it("emergency-unstakes a schedule's whole stake back into its vault", async () => {
+      await program.methods
+        .stakeVesting(new anchor.BN(1_000 * 10 ** 9))
+        .accounts({
+          ...vestingAccounts(),
+          stakingVault: feeStakingVaultPda,
+          userStake: scheduleStakePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+      await program.methods
+        .emergencyUnstakeVesting()
+        .accounts({
+          ...vestingAccounts(),
+          stakingVault: feeStakingVaultPda,
+          userStake: scheduleStakePda,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+      const schedule = await assertVaultMatchesSchedule();
+      assert.equal(schedule.stakedAmount.toNumber(), 0);
+      const userStake = await program.account.userStake.fetch(scheduleStakePda);
+      assert.equal(userStake.stakedAmount.toNumber(), 0);
+      assert.equal(userStake.pendingRewards.toNumber(), 0);
+    });
+
+    it("revokes and releases the rest without running the vault dry", async () => {
+      await program.methods
+        .revokeVesting()
+        .accounts({
+          grantor: authority.publicKey,
+          tokenMint: feeMint.publicKey,
+          config: feeConfigPda,
+          vestingSchedule: schedulePda,
+          vestingVault: vaultPda,
+          grantorTokenAccount,
+          stakingVault: feeStakingVaultPda,
+          userStake: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      await assertVaultMatchesSchedule();
+
+      await program.methods
+        .releaseVested()
+        .accounts({ ...vestingAccounts(), beneficiaryTokenAccount })
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+      const schedule = await assertVaultMatchesSchedule();
+      assert.equal(schedule.releasedAmount.toString(), schedule.totalAmount.toString());
+      assert.equal((await balanceOf(vaultPda)).toString(), "0");
     });
   });
 });